@@ -11,6 +11,7 @@ use crate::ident;
 struct TopLevel {
     blocks: Vec<Block>,
     shapes: Vec<Shape>,
+    map_colors: Vec<MapColor>,
 }
 
 #[derive(Deserialize, Clone, Debug)]
@@ -18,6 +19,13 @@ struct Block {
     #[allow(unused)]
     id: u16,
     translation_key: String,
+    hardness: f32,
+    blast_resistance: f32,
+    requires_tool: bool,
+    replaceable: bool,
+    solid: bool,
+    flammable: bool,
+    map_color: u8,
     name: String,
     properties: Vec<Property>,
     default_state_id: u16,
@@ -48,6 +56,12 @@ struct State {
     collision_shapes: Vec<u16>,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+struct MapColor {
+    id: u8,
+    color: u32,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 struct Shape {
     min_x: f64,
@@ -59,8 +73,11 @@ struct Shape {
 }
 
 pub fn build() -> anyhow::Result<TokenStream> {
-    let TopLevel { blocks, shapes } =
-        serde_json::from_str(include_str!("../extracted/blocks.json"))?;
+    let TopLevel {
+        blocks,
+        shapes,
+        map_colors,
+    } = serde_json::from_str(include_str!("../extracted/blocks.json"))?;
 
     let max_state_id = blocks.iter().map(|b| b.max_state_id()).max().unwrap();
 
@@ -75,12 +92,72 @@ pub fn build() -> anyhow::Result<TokenStream> {
         })
         .collect::<TokenStream>();
 
+    let kind_to_hardness_arms = blocks
+        .iter()
+        .map(|b| {
+            let kind = ident(b.name.to_pascal_case());
+            let hardness = b.hardness;
+            quote! {
+                Self::#kind => #hardness,
+            }
+        })
+        .collect::<TokenStream>();
+
+    let kinds_where = |f: fn(&Block) -> bool| {
+        let kinds = blocks
+            .iter()
+            .filter(|&b| f(b))
+            .map(|b| ident(b.name.to_pascal_case()));
+
+        quote! {
+            #(Self::#kinds)|*
+        }
+    };
+
+    let requires_tool_kinds = kinds_where(|b| b.requires_tool);
+    let replaceable_kinds = kinds_where(|b| b.replaceable);
+    let solid_kinds = kinds_where(|b| b.solid);
+    let flammable_kinds = kinds_where(|b| b.flammable);
+
+    let kind_to_map_color_arms = blocks
+        .iter()
+        .map(|b| {
+            let kind = ident(b.name.to_pascal_case());
+            let map_color = b.map_color;
+            quote! {
+                Self::#kind => MapColor(#map_color),
+            }
+        })
+        .collect::<TokenStream>();
+
+    let map_color_to_rgb_arms = map_colors
+        .iter()
+        .map(|c| {
+            let id = c.id;
+            let color = c.color;
+            quote! {
+                #id => #color,
+            }
+        })
+        .collect::<TokenStream>();
+
+    let kind_to_blast_resistance_arms = blocks
+        .iter()
+        .map(|b| {
+            let kind = ident(b.name.to_pascal_case());
+            let blast_resistance = b.blast_resistance;
+            quote! {
+                Self::#kind => #blast_resistance,
+            }
+        })
+        .collect::<TokenStream>();
+
     let state_to_kind_arms = blocks
         .iter()
         .map(|b| {
             let min = b.min_state_id();
             let max = b.max_state_id();
-            let name = ident(&b.name.to_pascal_case());
+            let name = ident(b.name.to_pascal_case());
             quote! {
                 #min..=#max => BlockKind::#name,
             }
@@ -427,11 +504,6 @@ pub fn build() -> anyhow::Result<TokenStream> {
                 }
             }
 
-            pub(crate) const fn from_raw_unchecked(id: u16) -> Self {
-                debug_assert!(Self::from_raw(id).is_some());
-                Self(id)
-            }
-
             /// Returns the [`BlockKind`] of this block state.
             pub const fn to_kind(self) -> BlockKind {
                 match self.0 {
@@ -482,7 +554,10 @@ pub fn build() -> anyhow::Result<TokenStream> {
                 )
             }
 
-            // TODO: is_solid
+            /// If this block's material is solid. See [`BlockKind::is_solid`].
+            pub const fn is_solid(self) -> bool {
+                self.to_kind().is_solid()
+            }
 
             /// If this block is water or lava.
             pub const fn is_liquid(self) -> bool {
@@ -568,10 +643,76 @@ pub fn build() -> anyhow::Result<TokenStream> {
                 }
             }
 
+            /// Returns how long this block kind takes to break. Blocks that
+            /// cannot be broken, such as bedrock, have a hardness of `-1.0`.
+            pub const fn hardness(self) -> f32 {
+                match self {
+                    #kind_to_hardness_arms
+                }
+            }
+
+            /// Returns how well this block kind resists explosions.
+            pub const fn blast_resistance(self) -> f32 {
+                match self {
+                    #kind_to_blast_resistance_arms
+                }
+            }
+
+            /// If this block kind only drops items when broken with the
+            /// correct tool.
+            pub const fn requires_tool(self) -> bool {
+                matches!(self, #requires_tool_kinds)
+            }
+
+            /// If this block kind is replaced when a block is placed inside of
+            /// it, like grass and water.
+            pub const fn is_replaceable(self) -> bool {
+                matches!(self, #replaceable_kinds)
+            }
+
+            /// If this block kind's material is solid. Blocks that entities
+            /// can move through, such as flowers and torches, are not solid.
+            pub const fn is_solid(self) -> bool {
+                matches!(self, #solid_kinds)
+            }
+
+            /// If this block kind can catch fire.
+            pub const fn is_flammable(self) -> bool {
+                matches!(self, #flammable_kinds)
+            }
+
+            /// Returns the color of this block kind on maps.
+            pub const fn map_color(self) -> MapColor {
+                match self {
+                    #kind_to_map_color_arms
+                }
+            }
+
             /// An array of all block kinds.
             pub const ALL: [Self; #block_kind_count] = [#(Self::#block_kind_variants,)*];
         }
 
+        /// The color of a block on maps.
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub struct MapColor(u8);
+
+        impl MapColor {
+            /// Returns the ID of this map color, as used in map data.
+            ///
+            /// Each map pixel is `id * 4` plus a shade from `0` to `3`.
+            pub const fn id(self) -> u8 {
+                self.0
+            }
+
+            /// Returns the base color of this map color as `0xRRGGBB`.
+            pub const fn rgb(self) -> u32 {
+                match self.0 {
+                    #map_color_to_rgb_arms
+                    _ => 0,
+                }
+            }
+        }
+
         /// The default block kind is `air`.
         impl Default for BlockKind {
             fn default() -> Self {
@@ -32,6 +32,7 @@ struct Field {
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(tag = "type", content = "default_value", rename_all = "snake_case")]
+#[allow(dead_code)]
 enum Value {
     Byte(u8),
     Integer(i32),
@@ -117,7 +118,7 @@ impl Value {
             Value::String(_) => quote!(Box<str>),
             Value::TextComponent(_) => quote!(Text),
             Value::OptionalTextComponent(_) => quote!(Option<Text>),
-            Value::ItemStack(_) => quote!(()), // TODO
+            Value::ItemStack(_) => quote!(Option<ItemStack>),
             Value::Boolean(_) => quote!(bool),
             Value::Rotation { .. } => quote!(EulerAngle),
             Value::BlockPos(_) => quote!(BlockPos),
@@ -142,17 +143,24 @@ impl Value {
             Value::String(_) => quote!(&str),
             Value::TextComponent(_) => quote!(&Text),
             Value::OptionalTextComponent(_) => quote!(Option<&Text>),
+            Value::ItemStack(_) => quote!(Option<&ItemStack>),
             Value::NbtCompound(_) => quote!(&crate::nbt::Compound),
+            Value::Particle(_) => quote!(&Particle),
             _ => self.field_type(),
         }
     }
 
     pub fn getter_return_expr(&self, field_name: &Ident) -> TokenStream {
         match self {
-            Value::String(_) | Value::TextComponent(_) | Value::NbtCompound(_) => {
+            Value::String(_)
+            | Value::TextComponent(_)
+            | Value::NbtCompound(_)
+            | Value::Particle(_) => {
                 quote!(&self.#field_name)
             }
-            Value::OptionalTextComponent(_) => quote!(self.#field_name.as_ref()),
+            Value::OptionalTextComponent(_) | Value::ItemStack(_) => {
+                quote!(self.#field_name.as_ref())
+            }
             _ => quote!(self.#field_name),
         }
     }
@@ -168,7 +176,7 @@ impl Value {
                 assert!(t.is_none());
                 quote!(None)
             }
-            Value::ItemStack(_) => quote!(()), // TODO
+            Value::ItemStack(_) => quote!(None),
             Value::Boolean(b) => quote!(#b),
             Value::Rotation { pitch, yaw, roll } => quote! {
                 EulerAngle {
@@ -269,12 +277,9 @@ pub fn build() -> anyhow::Result<TokenStream> {
         }
     });
 
-    let concrete_entity_names = concrete_entities
-        .iter()
-        .map(|(k, _)| ident(k))
-        .collect::<Vec<_>>();
+    let concrete_entity_names = concrete_entities.keys().map(ident).collect::<Vec<_>>();
 
-    let concrete_entity_structs = concrete_entities.iter().map(|(struct_name, _)| {
+    let concrete_entity_structs = concrete_entities.keys().map(|struct_name| {
         let fields = collect_all_fields(struct_name, &entities);
         let struct_name = ident(struct_name);
 
@@ -431,6 +436,11 @@ pub fn build() -> anyhow::Result<TokenStream> {
         }
     });
 
+    let entity_kind_names = concrete_entities
+        .values()
+        .map(|e| e.typ.as_ref().unwrap())
+        .collect::<Vec<_>>();
+
     Ok(quote! {
         /// Contains a variant for each concrete entity type.
         #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
@@ -439,6 +449,23 @@ pub fn build() -> anyhow::Result<TokenStream> {
         }
 
         impl EntityKind {
+            /// Construct an entity kind from its snake_case name.
+            ///
+            /// Returns `None` if the name is invalid.
+            pub fn from_str(name: &str) -> Option<EntityKind> {
+                match name {
+                    #(#entity_kind_names => Some(Self::#concrete_entity_names),)*
+                    _ => None
+                }
+            }
+
+            /// Get the snake_case name of this entity kind.
+            pub const fn to_str(self) -> &'static str {
+                match self {
+                    #(Self::#concrete_entity_names => #entity_kind_names,)*
+                }
+            }
+
             pub fn translation_key(self) -> &'static str {
                 match self {
                     #(#translation_key_arms)*
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use heck::ToPascalCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
+
+use crate::ident;
+
+#[derive(Deserialize, Clone, Debug)]
+struct Item {
+    id: u16,
+    name: String,
+    translation_key: String,
+    max_stack: u8,
+    rarity: String,
+    block: Option<String>,
+}
+
+pub fn build() -> anyhow::Result<TokenStream> {
+    let items: Vec<Item> = serde_json::from_str(include_str!("../extracted/items.json"))?;
+
+    let item_count = items.len();
+
+    let variants = items
+        .iter()
+        .map(|i| ident(i.name.to_pascal_case()))
+        .collect::<Vec<_>>();
+
+    let kind_variants = items.iter().map(|i| {
+        let variant = ident(i.name.to_pascal_case());
+        let id = i.id as isize;
+        quote! {
+            #variant = #id,
+        }
+    });
+
+    let from_raw_arms = items.iter().map(|i| {
+        let id = i.id;
+        let variant = ident(i.name.to_pascal_case());
+        quote! {
+            #id => Some(Self::#variant),
+        }
+    });
+
+    let from_str_arms = items.iter().map(|i| {
+        let name = &i.name;
+        let variant = ident(name.to_pascal_case());
+        quote! {
+            #name => Some(Self::#variant),
+        }
+    });
+
+    let to_str_arms = items.iter().map(|i| {
+        let name = &i.name;
+        let variant = ident(name.to_pascal_case());
+        quote! {
+            Self::#variant => #name,
+        }
+    });
+
+    let translation_key_arms = items.iter().map(|i| {
+        let key = &i.translation_key;
+        let variant = ident(i.name.to_pascal_case());
+        quote! {
+            Self::#variant => #key,
+        }
+    });
+
+    let max_stack_arms = items.iter().filter(|i| i.max_stack != 64).map(|i| {
+        let max_stack = i.max_stack;
+        let variant = ident(i.name.to_pascal_case());
+        quote! {
+            Self::#variant => #max_stack,
+        }
+    });
+
+    let rarity_arms = items.iter().filter(|i| i.rarity != "common").map(|i| {
+        let rarity = ident(i.rarity.to_pascal_case());
+        let variant = ident(i.name.to_pascal_case());
+        quote! {
+            Self::#variant => Rarity::#rarity,
+        }
+    });
+
+    let to_block_kind_arms = items.iter().filter_map(|i| {
+        let block = ident(i.block.as_ref()?.to_pascal_case());
+        let variant = ident(i.name.to_pascal_case());
+        Some(quote! {
+            Self::#variant => Some(BlockKind::#block),
+        })
+    });
+
+    // Blocks are mapped to the first item that places them.
+    let mut block_items = BTreeMap::new();
+    for i in &items {
+        if let Some(block) = &i.block {
+            block_items.entry(block).or_insert(&i.name);
+        }
+    }
+
+    let to_item_kind_arms = block_items.iter().map(|(block, item)| {
+        let block = ident(block.to_pascal_case());
+        let item = ident(item.to_pascal_case());
+        quote! {
+            Self::#block => Some(ItemKind::#item),
+        }
+    });
+
+    Ok(quote! {
+        /// An item type, such as `diamond_pickaxe`.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum ItemKind {
+            #(#kind_variants)*
+        }
+
+        impl ItemKind {
+            /// Constructs an item kind from its protocol ID.
+            ///
+            /// Returns `None` if the ID is invalid.
+            pub const fn from_raw(id: u16) -> Option<Self> {
+                match id {
+                    #(#from_raw_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the protocol ID of this item kind.
+            pub const fn to_raw(self) -> u16 {
+                self as u16
+            }
+
+            /// Constructs an item kind from its name without the `minecraft:`
+            /// namespace, such as `diamond_pickaxe`.
+            ///
+            /// Returns `None` if the name is invalid.
+            pub fn from_str(name: &str) -> Option<Self> {
+                match name {
+                    #(#from_str_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Gets the name of this item kind without the `minecraft:`
+            /// namespace.
+            pub const fn to_str(self) -> &'static str {
+                match self {
+                    #(#to_str_arms)*
+                }
+            }
+
+            /// Returns the translation key of this item kind, such as
+            /// `item.minecraft.diamond_pickaxe`.
+            pub const fn translation_key(self) -> &'static str {
+                match self {
+                    #(#translation_key_arms)*
+                }
+            }
+
+            /// Returns the maximum number of items of this kind that fit in a
+            /// single stack.
+            pub const fn max_stack(self) -> u8 {
+                match self {
+                    #(#max_stack_arms)*
+                    _ => 64,
+                }
+            }
+
+            /// Returns the rarity of this item kind, which determines the
+            /// color of its name.
+            pub const fn rarity(self) -> Rarity {
+                match self {
+                    #(#rarity_arms)*
+                    _ => Rarity::Common,
+                }
+            }
+
+            /// Returns the kind of block placed by this item, if any.
+            pub const fn to_block_kind(self) -> Option<BlockKind> {
+                match self {
+                    #(#to_block_kind_arms)*
+                    _ => None,
+                }
+            }
+
+            /// An array of all item kinds.
+            pub const ALL: [Self; #item_count] = [#(Self::#variants,)*];
+        }
+
+        impl BlockKind {
+            /// Returns the kind of item that places this block, if any.
+            pub const fn to_item_kind(self) -> Option<ItemKind> {
+                match self {
+                    #(#to_item_kind_arms)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
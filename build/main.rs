@@ -8,6 +8,10 @@ use proc_macro2::{Ident, Span};
 mod block;
 mod entity;
 mod entity_event;
+mod item;
+mod particle;
+mod sound;
+mod tag;
 
 pub fn main() -> anyhow::Result<()> {
     println!("cargo:rerun-if-changed=extracted/");
@@ -16,6 +20,10 @@ pub fn main() -> anyhow::Result<()> {
         (entity::build as fn() -> _, "entity.rs"),
         (entity_event::build, "entity_event.rs"),
         (block::build, "block.rs"),
+        (item::build, "item.rs"),
+        (particle::build, "particle.rs"),
+        (sound::build, "sound.rs"),
+        (tag::build, "tag.rs"),
     ];
 
     let out_dir = env::var_os("OUT_DIR").context("can't get OUT_DIR env var")?;
@@ -0,0 +1,142 @@
+use heck::ToPascalCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
+
+use crate::ident;
+
+#[derive(Deserialize, Clone, Debug)]
+struct Particle {
+    id: i32,
+    name: String,
+    data: Option<String>,
+}
+
+pub fn build() -> anyhow::Result<TokenStream> {
+    let particles: Vec<Particle> =
+        serde_json::from_str(include_str!("../extracted/particles.json"))?;
+
+    let variants = particles
+        .iter()
+        .map(|p| {
+            let name = ident(p.name.to_pascal_case());
+
+            let fields = match p.data.as_deref() {
+                None => quote!(),
+                Some("block_state") => quote!((BlockState)),
+                Some("dust") => quote!({ rgb: Vec3<f32>, scale: f32 }),
+                Some("dust_color_transition") => quote! {
+                    { from_rgb: Vec3<f32>, scale: f32, to_rgb: Vec3<f32> }
+                },
+                Some("item") => quote!((ItemStack)),
+                Some("vibration") => quote!({ destination: VibrationDestination, ticks: i32 }),
+                Some("sculk_charge") => quote!({ roll: f32 }),
+                Some("shriek") => quote!({ delay: i32 }),
+                Some(other) => anyhow::bail!("unknown particle data \"{other}\""),
+            };
+
+            Ok(quote! {
+                #name #fields,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let pattern = |p: &Particle| {
+        let name = ident(p.name.to_pascal_case());
+
+        match p.data.as_deref() {
+            None => quote!(Self::#name),
+            Some("block_state" | "item") => quote!(Self::#name(..)),
+            Some(_) => quote!(Self::#name { .. }),
+        }
+    };
+
+    let id_arms = particles.iter().map(|p| {
+        let pat = pattern(p);
+        let id = p.id;
+        quote! {
+            #pat => #id,
+        }
+    });
+
+    let name_arms = particles.iter().map(|p| {
+        let pat = pattern(p);
+        let name = &p.name;
+        quote! {
+            #pat => #name,
+        }
+    });
+
+    let encode_data_arms = particles.iter().filter_map(|p| {
+        let name = ident(p.name.to_pascal_case());
+
+        Some(match p.data.as_deref()? {
+            "block_state" => quote! {
+                Self::#name(block) => VarInt(block.to_raw() as i32).encode(w),
+            },
+            "dust" => quote! {
+                Self::#name { rgb, scale } => {
+                    rgb.encode(w)?;
+                    scale.encode(w)
+                }
+            },
+            "dust_color_transition" => quote! {
+                Self::#name { from_rgb, scale, to_rgb } => {
+                    from_rgb.encode(w)?;
+                    scale.encode(w)?;
+                    to_rgb.encode(w)
+                }
+            },
+            "item" => quote! {
+                Self::#name(stack) => Some(stack).encode(w),
+            },
+            "vibration" => quote! {
+                Self::#name { destination, ticks } => {
+                    destination.encode(w)?;
+                    VarInt(*ticks).encode(w)
+                }
+            },
+            "sculk_charge" => quote! {
+                Self::#name { roll } => roll.encode(w),
+            },
+            "shriek" => quote! {
+                Self::#name { delay } => VarInt(*delay).encode(w),
+            },
+            _ => unreachable!(),
+        })
+    });
+
+    Ok(quote! {
+        /// A particle effect along with the extra data it needs.
+        #[derive(Clone, PartialEq, Debug)]
+        pub enum Particle {
+            #(#variants)*
+        }
+
+        impl Particle {
+            /// Returns the protocol ID of this particle's type.
+            pub const fn id(&self) -> i32 {
+                match self {
+                    #(#id_arms)*
+                }
+            }
+
+            /// Returns the name of this particle's type without the
+            /// `minecraft:` namespace.
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
+
+            /// Encodes the extra data of this particle, which follows the
+            /// particle ID in the protocol.
+            pub(crate) fn encode_data(&self, w: &mut impl Write) -> anyhow::Result<()> {
+                match self {
+                    #(#encode_data_arms)*
+                    _ => Ok(()),
+                }
+            }
+        }
+    })
+}
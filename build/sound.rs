@@ -0,0 +1,69 @@
+use heck::ToPascalCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
+
+use crate::ident;
+
+#[derive(Deserialize, Clone, Debug)]
+struct Sound {
+    name: String,
+}
+
+pub fn build() -> anyhow::Result<TokenStream> {
+    let sounds: Vec<Sound> = serde_json::from_str(include_str!("../extracted/sounds.json"))?;
+
+    let sound_count = sounds.len();
+
+    let variants = sounds
+        .iter()
+        .map(|s| ident(s.name.to_pascal_case()))
+        .collect::<Vec<_>>();
+
+    let from_str_arms = sounds.iter().map(|s| {
+        let name = &s.name;
+        let variant = ident(name.to_pascal_case());
+        quote! {
+            #name => Some(Self::#variant),
+        }
+    });
+
+    let to_str_arms = sounds.iter().map(|s| {
+        let name = &s.name;
+        let variant = ident(name.to_pascal_case());
+        quote! {
+            Self::#variant => #name,
+        }
+    });
+
+    Ok(quote! {
+        /// A sound event built into the game.
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum Sound {
+            #(#variants,)*
+        }
+
+        impl Sound {
+            /// Constructs a sound from its name without the `minecraft:`
+            /// namespace, such as `block.note_block.harp`.
+            ///
+            /// Returns `None` if the name is invalid.
+            pub fn from_str(name: &str) -> Option<Self> {
+                match name {
+                    #(#from_str_arms)*
+                    _ => None,
+                }
+            }
+
+            /// Gets the name of this sound without the `minecraft:` namespace.
+            pub const fn to_str(self) -> &'static str {
+                match self {
+                    #(#to_str_arms)*
+                }
+            }
+
+            /// An array of all sounds.
+            pub const ALL: [Self; #sound_count] = [#(Self::#variants,)*];
+        }
+    })
+}
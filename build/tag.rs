@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use heck::ToPascalCase;
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde::Deserialize;
+
+use crate::ident;
+
+/// Maps tag names to the entries in the tag.
+type Tags = BTreeMap<String, Vec<Entry>>;
+
+#[derive(Deserialize, Clone, Debug)]
+struct Entry {
+    id: i32,
+    name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct Block {
+    name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct Blocks {
+    blocks: Vec<Block>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct Item {
+    name: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+struct Entity {
+    #[serde(rename = "type")]
+    typ: Option<String>,
+}
+
+pub fn build() -> anyhow::Result<TokenStream> {
+    // Maps registry names to the tags of the registry.
+    let registries: BTreeMap<String, Tags> =
+        serde_json::from_str(include_str!("../extracted/tags.json"))?;
+
+    let tags_of = |registry: &str| registries.get(registry).cloned().unwrap_or_default();
+
+    let blocks: Blocks = serde_json::from_str(include_str!("../extracted/blocks.json"))?;
+    let block_variants = blocks
+        .blocks
+        .iter()
+        .map(|b| (b.name.clone(), b.name.to_pascal_case()))
+        .collect::<BTreeMap<_, _>>();
+
+    let items: Vec<Item> = serde_json::from_str(include_str!("../extracted/items.json"))?;
+    let item_variants = items
+        .iter()
+        .map(|i| (i.name.clone(), i.name.to_pascal_case()))
+        .collect::<BTreeMap<_, _>>();
+
+    let entity_variants = serde_json::from_str::<BTreeMap<String, Entity>>(include_str!(
+        "../extracted/entities.json"
+    ))?
+    .into_iter()
+    .filter_map(|(class, e)| {
+        let variant = class.strip_suffix("Entity").unwrap_or(&class).to_owned();
+        Some((e.typ?, variant))
+    })
+    .collect::<BTreeMap<_, _>>();
+
+    let block_tag = tag_enum(
+        "BlockTag",
+        "A vanilla block tag.",
+        &tags_of("block"),
+        Some((quote!(BlockKind), &block_variants)),
+    )?;
+    let item_tag = tag_enum(
+        "ItemTag",
+        "A vanilla item tag.",
+        &tags_of("item"),
+        Some((quote!(ItemKind), &item_variants)),
+    )?;
+    let entity_tag = tag_enum(
+        "EntityTag",
+        "A vanilla entity type tag.",
+        &tags_of("entity_type"),
+        Some((quote!(EntityKind), &entity_variants)),
+    )?;
+    let fluid_tag = tag_enum("FluidTag", "A vanilla fluid tag.", &tags_of("fluid"), None)?;
+
+    let vanilla_tags = registries.iter().map(|(registry, tags)| {
+        let tags = tags.iter().map(|(name, entries)| {
+            let ids = entries.iter().map(|e| e.id);
+
+            quote! {
+                (#name, &[#(#ids),*]),
+            }
+        });
+
+        quote! {
+            (#registry, &[#(#tags)*]),
+        }
+    });
+
+    Ok(quote! {
+        #block_tag
+        #item_tag
+        #entity_tag
+        #fluid_tag
+
+        /// The protocol IDs of the entries in each vanilla tag, grouped by
+        /// registry.
+        pub(crate) const VANILLA_TAGS: &[(&str, &[(&str, &[i32])])] = &[#(#vanilla_tags)*];
+    })
+}
+
+/// Generates an enum with a variant for each of `tags`. If `kind` is given,
+/// `has_tag` is also generated on the kind type from the variant names of its
+/// entries.
+fn tag_enum(
+    name: &str,
+    doc: &str,
+    tags: &Tags,
+    kind: Option<(TokenStream, &BTreeMap<String, String>)>,
+) -> anyhow::Result<TokenStream> {
+    let name = ident(name);
+    let tag_count = tags.len();
+
+    let variants = tags
+        .keys()
+        .map(|t| ident(t.to_pascal_case()))
+        .collect::<Vec<_>>();
+
+    let tag_names = tags.keys().collect::<Vec<_>>();
+
+    let has_tag = match kind {
+        Some((kind, kind_variants)) => {
+            let arms = tags
+                .iter()
+                .map(|(tag, entries)| {
+                    let variant = ident(tag.to_pascal_case());
+
+                    if entries.is_empty() {
+                        return Ok(quote! {
+                            #name::#variant => false,
+                        });
+                    }
+
+                    let entries = entries
+                        .iter()
+                        .map(|e| {
+                            kind_variants.get(&e.name).map(ident).with_context(|| {
+                                format!("unknown entry \"{}\" in tag \"{tag}\"", e.name)
+                            })
+                        })
+                        .collect::<anyhow::Result<Vec<_>>>()?;
+
+                    Ok(quote! {
+                        #name::#variant => matches!(self, #(Self::#entries)|*),
+                    })
+                })
+                .collect::<anyhow::Result<TokenStream>>()?;
+
+            quote! {
+                impl #kind {
+                    /// Returns whether this is in the vanilla tag `tag`.
+                    pub const fn has_tag(self, tag: #name) -> bool {
+                        match tag {
+                            #arms
+                        }
+                    }
+                }
+            }
+        }
+        None => quote!(),
+    };
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        pub enum #name {
+            #(#variants,)*
+        }
+
+        impl #name {
+            /// Constructs a tag from its name without the `minecraft:`
+            /// namespace, such as `mineable/pickaxe`.
+            ///
+            /// Returns `None` if the name is invalid.
+            pub fn from_str(name: &str) -> Option<Self> {
+                match name {
+                    #(#tag_names => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+
+            /// Gets the name of this tag without the `minecraft:` namespace.
+            pub const fn to_str(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #tag_names,)*
+                }
+            }
+
+            /// An array of all tags.
+            pub const ALL: [Self; #tag_count] = [#(Self::#variants,)*];
+        }
+
+        #has_tag
+    })
+}
@@ -13,8 +13,8 @@ use valence::dimension::{Dimension, DimensionId};
 use valence::entity::types::Pose;
 use valence::entity::{EntityId, EntityKind, TrackedData};
 use valence::player_list::PlayerListId;
-use valence::protocol::packets::s2c::play::SoundCategory;
 use valence::server::{Server, SharedServer, ShutdownResult};
+use valence::sound::{Sound, SoundCategory};
 use valence::text::{Color, TextFormat};
 use valence::{async_trait, ident};
 use vek::Vec3;
@@ -109,7 +109,7 @@ impl Config for Game {
 
         for chunk_z in -2..Integer::div_ceil(&(SIZE_Z as i32), &16) + 2 {
             for chunk_x in -2..Integer::div_ceil(&(SIZE_X as i32), &16) + 2 {
-                world.chunks.insert((chunk_x as i32, chunk_z as i32), ());
+                world.chunks.insert((chunk_x, chunk_z), ());
             }
         }
     }
@@ -187,30 +187,29 @@ impl Config for Game {
 
             while let Some(event) = default_client_event(client, player) {
                 match event {
-                    ClientEvent::Digging { position, .. } => {
+                    ClientEvent::Digging { position, .. }
                         if (0..SIZE_X as i32).contains(&position.x)
                             && (0..SIZE_Z as i32).contains(&position.z)
-                            && position.y == BOARD_Y
-                        {
-                            let index = position.x as usize + position.z as usize * SIZE_X;
-
-                            if !server.state.board[index] {
-                                client.play_sound(
-                                    ident!("minecraft:block.note_block.banjo"),
-                                    SoundCategory::Block,
-                                    Vec3::<i32>::from(position).as_(),
-                                    0.5f32,
-                                    1f32,
-                                );
-                            }
-
-                            server.state.board[index] = true;
+                            && position.y == BOARD_Y =>
+                    {
+                        let index = position.x as usize + position.z as usize * SIZE_X;
+
+                        if !server.state.board[index] {
+                            client.play_sound(
+                                Sound::BlockNoteBlockBanjo,
+                                SoundCategory::Block,
+                                Vec3::<i32>::from(position).as_(),
+                                0.5f32,
+                                1f32,
+                            );
                         }
+
+                        server.state.board[index] = true;
                     }
-                    ClientEvent::InteractWithBlock { hand, .. } => {
-                        if hand == Hand::Main {
-                            client.send_message("I said left click, not right click!".italic());
-                        }
+                    ClientEvent::InteractWithBlock {
+                        hand: Hand::Main, ..
+                    } => {
+                        client.send_message("I said left click, not right click!".italic());
                     }
                     _ => {}
                 }
@@ -221,9 +220,9 @@ impl Config for Game {
                 if sneaking != server.state.paused {
                     server.state.paused = sneaking;
                     client.play_sound(
-                        ident!("minecraft:block.note_block.pling"),
+                        Sound::BlockNoteBlockPling,
                         SoundCategory::Block,
-                        client.position().into(),
+                        client.position(),
                         0.5f32,
                         if sneaking { 0.5f32 } else { 1f32 },
                     );
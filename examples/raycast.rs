@@ -13,6 +13,7 @@ use valence::server::{Server, SharedServer, ShutdownResult};
 use valence::spatial_index::RaycastHit;
 use valence::text::{Color, TextFormat};
 use valence::util::from_yaw_and_pitch;
+use valence::world::WorldRaycastHit;
 use vek::Vec3;
 
 pub fn main() -> ShutdownResult {
@@ -39,6 +40,8 @@ const SPAWN_POS: BlockPos = BlockPos::new(0, 100, -5);
 
 const PLAYER_EYE_HEIGHT: f64 = 1.62;
 
+const MAX_DISTANCE: f64 = 64.0;
+
 // TODO
 // const PLAYER_SNEAKING_EYE_HEIGHT: f64 = 1.495;
 
@@ -84,6 +87,13 @@ impl Config for Game {
 
         world.chunks.set_block_state(SPAWN_POS, BlockState::BEDROCK);
 
+        // A glass wall that blocks the view of some of the sheep.
+        world.chunks.fill(
+            [-7, SPAWN_POS.y + 1, -2],
+            [-1, SPAWN_POS.y + 2, -2],
+            BlockState::GLASS,
+        );
+
         const SHEEP_COUNT: usize = 10;
         for i in 0..SHEEP_COUNT {
             let offset = (i as f64 - (SHEEP_COUNT - 1) as f64 / 2.0) * 1.25;
@@ -151,7 +161,7 @@ impl Config for Game {
                 client.send_message(
                     "Look at a sheep to change its ".italic()
                         + "color".italic().color(Color::GREEN)
-                        + ".",
+                        + ". The glass wall is in the way of some of them.",
                 );
             }
 
@@ -173,10 +183,12 @@ impl Config for Game {
                 server
                     .entities
                     .get(hit.entity)
-                    .map_or(false, |e| e.kind() == EntityKind::Sheep)
+                    .is_some_and(|e| e.kind() == EntityKind::Sheep)
             };
 
-            if let Some(hit) = world.spatial_index.raycast(origin, direction, only_sheep) {
+            if let Some(WorldRaycastHit::Entity(hit)) =
+                world.raycast(origin, direction, MAX_DISTANCE, |_, _| true, only_sheep)
+            {
                 if let Some(e) = server.entities.get_mut(hit.entity) {
                     e.state = true;
                 }
@@ -3,10 +3,9 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 use log::LevelFilter;
 use noise::{NoiseFn, Seedable, SuperSimplex};
-use rayon::iter::ParallelIterator;
 use valence::async_trait;
 use valence::block::{BlockState, PropName, PropValue};
-use valence::chunk::ChunkPos;
+use valence::chunk::{ChunkPos, ChunkProvider, UnloadedChunk};
 use valence::client::{default_client_event, GameMode};
 use valence::config::{Config, ServerListPing};
 use valence::dimension::DimensionId;
@@ -14,7 +13,6 @@ use valence::entity::{EntityId, EntityKind};
 use valence::player_list::PlayerListId;
 use valence::server::{Server, SharedServer, ShutdownResult};
 use valence::text::{Color, TextFormat};
-use valence::util::chunks_in_view_distance;
 use vek::Lerp;
 
 pub fn main() -> ShutdownResult {
@@ -28,11 +26,7 @@ pub fn main() -> ShutdownResult {
     valence::start_server(
         Game {
             player_count: AtomicUsize::new(0),
-            density_noise: SuperSimplex::new().set_seed(seed),
-            hilly_noise: SuperSimplex::new().set_seed(seed.wrapping_add(1)),
-            stone_noise: SuperSimplex::new().set_seed(seed.wrapping_add(2)),
-            gravel_noise: SuperSimplex::new().set_seed(seed.wrapping_add(3)),
-            grass_noise: SuperSimplex::new().set_seed(seed.wrapping_add(4)),
+            seed,
         },
         None,
     )
@@ -40,6 +34,10 @@ pub fn main() -> ShutdownResult {
 
 struct Game {
     player_count: AtomicUsize,
+    seed: u32,
+}
+
+struct TerrainGenerator {
     density_noise: SuperSimplex,
     hilly_noise: SuperSimplex,
     stone_noise: SuperSimplex,
@@ -55,8 +53,7 @@ impl Config for Game {
     type ClientState = EntityId;
     type EntityState = ();
     type WorldState = ();
-    /// If the chunk should stay loaded at the end of the tick.
-    type ChunkState = bool;
+    type ChunkState = ();
     type PlayerListState = ();
 
     fn max_connections(&self) -> usize {
@@ -79,12 +76,25 @@ impl Config for Game {
     }
 
     fn init(&self, server: &mut Server<Self>) {
-        server.worlds.insert(DimensionId::default(), ());
+        let (_, world) = server.worlds.insert(DimensionId::default(), ());
+
+        let seed = self.seed;
+        world.chunks.set_provider(TerrainGenerator {
+            density_noise: SuperSimplex::new().set_seed(seed),
+            hilly_noise: SuperSimplex::new().set_seed(seed.wrapping_add(1)),
+            stone_noise: SuperSimplex::new().set_seed(seed.wrapping_add(2)),
+            gravel_noise: SuperSimplex::new().set_seed(seed.wrapping_add(3)),
+            grass_noise: SuperSimplex::new().set_seed(seed.wrapping_add(4)),
+        });
+
+        // Keep the area around spawn loaded so that it is ready when players join.
+        world.chunks.add_ticket([0, 0], 3);
+
         server.state = Some(server.player_lists.insert(()).0);
     }
 
     fn update(&self, server: &mut Server<Self>) {
-        let (world_id, world) = server.worlds.iter_mut().next().unwrap();
+        let (world_id, _) = server.worlds.iter().next().unwrap();
 
         server.clients.retain(|_, client| {
             if client.created_this_tick() {
@@ -144,92 +154,68 @@ impl Config for Game {
                 while default_client_event(client, entity).is_some() {}
             }
 
-            let dist = client.view_distance();
-            let p = client.position();
-
-            for pos in chunks_in_view_distance(ChunkPos::at(p.x, p.z), dist) {
-                if let Some(chunk) = world.chunks.get_mut(pos) {
-                    chunk.state = true;
-                } else {
-                    world.chunks.insert(pos, true);
-                }
-            }
-
             true
         });
+    }
+}
 
-        // Remove chunks outside the view distance of players.
-        world.chunks.retain(|_, chunk| {
-            if chunk.state {
-                chunk.state = false;
-                true
-            } else {
-                false
-            }
-        });
-
-        // Generate chunk data for chunks created this tick.
-        world.chunks.par_iter_mut().for_each(|(pos, chunk)| {
-            if !chunk.created_this_tick() {
-                return;
-            }
+impl ChunkProvider<Game> for TerrainGenerator {
+    fn provide(&self, pos: ChunkPos, chunk: &mut UnloadedChunk) {
+        for z in 0..16 {
+            for x in 0..16 {
+                let block_x = x as i64 + pos.x as i64 * 16;
+                let block_z = z as i64 + pos.z as i64 * 16;
+
+                let mut in_terrain = false;
+                let mut depth = 0;
+
+                for y in (0..chunk.height()).rev() {
+                    let b = terrain_column(
+                        self,
+                        block_x,
+                        y as i64,
+                        block_z,
+                        &mut in_terrain,
+                        &mut depth,
+                    );
+                    chunk.set_block_state(x, y, z, b);
+                }
 
-            for z in 0..16 {
-                for x in 0..16 {
-                    let block_x = x as i64 + pos.x as i64 * 16;
-                    let block_z = z as i64 + pos.z as i64 * 16;
-
-                    let mut in_terrain = false;
-                    let mut depth = 0;
-
-                    for y in (0..chunk.height()).rev() {
-                        let b = terrain_column(
-                            self,
-                            block_x,
-                            y as i64,
-                            block_z,
-                            &mut in_terrain,
-                            &mut depth,
+                // Add grass
+                for y in (0..chunk.height()).rev() {
+                    if chunk.get_block_state(x, y, z).is_air()
+                        && chunk.get_block_state(x, y - 1, z) == BlockState::GRASS_BLOCK
+                    {
+                        let density = fbm(
+                            &self.grass_noise,
+                            [block_x, y as i64, block_z].map(|a| a as f64 / 5.0),
+                            4,
+                            2.0,
+                            0.7,
                         );
-                        chunk.set_block_state(x, y, z, b);
-                    }
 
-                    // Add grass
-                    for y in (0..chunk.height()).rev() {
-                        if chunk.get_block_state(x, y, z).is_air()
-                            && chunk.get_block_state(x, y - 1, z) == BlockState::GRASS_BLOCK
-                        {
-                            let density = fbm(
-                                &self.grass_noise,
-                                [block_x, y as i64, block_z].map(|a| a as f64 / 5.0),
-                                4,
-                                2.0,
-                                0.7,
-                            );
-
-                            if density > 0.55 {
-                                if density > 0.7 && chunk.get_block_state(x, y + 1, z).is_air() {
-                                    let upper = BlockState::TALL_GRASS
-                                        .set(PropName::Half, PropValue::Upper);
-                                    let lower = BlockState::TALL_GRASS
-                                        .set(PropName::Half, PropValue::Lower);
-
-                                    chunk.set_block_state(x, y + 1, z, upper);
-                                    chunk.set_block_state(x, y, z, lower);
-                                } else {
-                                    chunk.set_block_state(x, y, z, BlockState::GRASS);
-                                }
+                        if density > 0.55 {
+                            if density > 0.7 && chunk.get_block_state(x, y + 1, z).is_air() {
+                                let upper =
+                                    BlockState::TALL_GRASS.set(PropName::Half, PropValue::Upper);
+                                let lower =
+                                    BlockState::TALL_GRASS.set(PropName::Half, PropValue::Lower);
+
+                                chunk.set_block_state(x, y + 1, z, upper);
+                                chunk.set_block_state(x, y, z, lower);
+                            } else {
+                                chunk.set_block_state(x, y, z, BlockState::GRASS);
                             }
                         }
                     }
                 }
             }
-        });
+        }
     }
 }
 
 fn terrain_column(
-    g: &Game,
+    g: &TerrainGenerator,
     x: i64,
     y: i64,
     z: i64,
@@ -286,7 +272,7 @@ fn terrain_column(
     }
 }
 
-fn has_terrain_at(g: &Game, x: i64, y: i64, z: i64) -> bool {
+fn has_terrain_at(g: &TerrainGenerator, x: i64, y: i64, z: i64) -> bool {
     let hilly = Lerp::lerp_unclamped(
         0.1,
         1.0,
@@ -5,7 +5,11 @@ import com.google.gson.JsonElement;
 import dev._00a.valence_extractor.extractors.Blocks;
 import dev._00a.valence_extractor.extractors.Entities;
 import dev._00a.valence_extractor.extractors.EntityData;
+import dev._00a.valence_extractor.extractors.Items;
 import dev._00a.valence_extractor.extractors.Packets;
+import dev._00a.valence_extractor.extractors.Particles;
+import dev._00a.valence_extractor.extractors.Sounds;
+import dev._00a.valence_extractor.extractors.Tags;
 import net.fabricmc.api.ModInitializer;
 import org.slf4j.Logger;
 import org.slf4j.LoggerFactory;
@@ -40,7 +44,7 @@ public class Main implements ModInitializer {
     public void onInitialize() {
         LOGGER.info("Starting extractors...");
 
-        var extractors = new Extractor[]{new Blocks(), new Entities(), new EntityData(), new Packets()};
+        var extractors = new Extractor[]{new Blocks(), new Entities(), new EntityData(), new Items(), new Packets(), new Particles(), new Sounds(), new Tags()};
 
         Path outputDirectory;
         try {
@@ -4,6 +4,7 @@ import com.google.gson.JsonArray;
 import com.google.gson.JsonElement;
 import com.google.gson.JsonObject;
 import dev._00a.valence_extractor.Main;
+import net.minecraft.block.MapColor;
 import net.minecraft.util.math.BlockPos;
 import net.minecraft.util.registry.Registry;
 import net.minecraft.world.EmptyBlockView;
@@ -35,6 +36,15 @@ public class Blocks implements Main.Extractor {
             blockJson.addProperty("id", Registry.BLOCK.getRawId(block));
             blockJson.addProperty("name", Registry.BLOCK.getId(block).getPath());
             blockJson.addProperty("translation_key", block.getTranslationKey());
+            blockJson.addProperty("hardness", block.getHardness());
+            blockJson.addProperty("blast_resistance", block.getBlastResistance());
+            blockJson.addProperty("requires_tool", block.getDefaultState().isToolRequired());
+
+            var material = block.getDefaultState().getMaterial();
+            blockJson.addProperty("replaceable", material.isReplaceable());
+            blockJson.addProperty("solid", material.isSolid());
+            blockJson.addProperty("flammable", material.isBurnable());
+            blockJson.addProperty("map_color", block.getDefaultMapColor().id);
 //            blockJson.addProperty("min_state_id", stateIdCounter);
 //            blockJson.addProperty("max_state_id", stateIdCounter + block.getStateManager().getStates().size() - 1);
 
@@ -98,6 +108,22 @@ public class Blocks implements Main.Extractor {
         topLevelJson.add("shapes", shapesJson);
         topLevelJson.add("blocks", blocksJson);
 
+        var mapColorsJson = new JsonArray();
+        for (int i = 0; i < 64; i++) {
+            var mapColor = MapColor.get(i);
+            if (mapColor.id != i) {
+                // Unused IDs are filled with MapColor.CLEAR.
+                continue;
+            }
+
+            var mapColorJson = new JsonObject();
+            mapColorJson.addProperty("id", mapColor.id);
+            mapColorJson.addProperty("color", mapColor.color);
+            mapColorsJson.add(mapColorJson);
+        }
+
+        topLevelJson.add("map_colors", mapColorsJson);
+
         return topLevelJson;
     }
 
@@ -0,0 +1,44 @@
+package dev._00a.valence_extractor.extractors;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import dev._00a.valence_extractor.Main;
+import net.minecraft.item.BlockItem;
+import net.minecraft.util.registry.Registry;
+
+import java.util.Locale;
+
+public class Items implements Main.Extractor {
+    public Items() {
+    }
+
+    @Override
+    public String fileName() {
+        return "items.json";
+    }
+
+    @Override
+    public JsonElement extract() {
+        var itemsJson = new JsonArray();
+
+        for (var item : Registry.ITEM) {
+            var itemJson = new JsonObject();
+            itemJson.addProperty("id", Registry.ITEM.getRawId(item));
+            itemJson.addProperty("name", Registry.ITEM.getId(item).getPath());
+            itemJson.addProperty("translation_key", item.getTranslationKey());
+            itemJson.addProperty("max_stack", item.getMaxCount());
+            itemJson.addProperty("rarity", item.getRarity(item.getDefaultStack()).name().toLowerCase(Locale.ROOT));
+
+            if (item instanceof BlockItem blockItem) {
+                itemJson.addProperty("block", Registry.BLOCK.getId(blockItem.getBlock()).getPath());
+            } else {
+                itemJson.add("block", null);
+            }
+
+            itemsJson.add(itemJson);
+        }
+
+        return itemsJson;
+    }
+}
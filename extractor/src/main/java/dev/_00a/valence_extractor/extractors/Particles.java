@@ -0,0 +1,62 @@
+package dev._00a.valence_extractor.extractors;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import dev._00a.valence_extractor.Main;
+import net.minecraft.particle.*;
+import net.minecraft.util.registry.Registry;
+import org.jetbrains.annotations.Nullable;
+
+public class Particles implements Main.Extractor {
+    @Override
+    public String fileName() {
+        return "particles.json";
+    }
+
+    @Override
+    public JsonElement extract() {
+        var particlesJson = new JsonArray();
+
+        for (var type : Registry.PARTICLE_TYPE) {
+            var particleJson = new JsonObject();
+
+            particleJson.addProperty("id", Registry.PARTICLE_TYPE.getRawId(type));
+            particleJson.addProperty("name", Registry.PARTICLE_TYPE.getId(type).getPath());
+            particleJson.addProperty("data", dataName(type));
+
+            particlesJson.add(particleJson);
+        }
+
+        return particlesJson;
+    }
+
+    /**
+     * Returns the name of the extra data sent with the particle, or null if it has none.
+     */
+    private static @Nullable String dataName(ParticleType<?> type) {
+        if (type instanceof DefaultParticleType) {
+            return null;
+        }
+
+        var factory = type.getParametersFactory();
+
+        if (factory == BlockStateParticleEffect.PARAMETERS_FACTORY) {
+            return "block_state";
+        } else if (factory == DustParticleEffect.PARAMETERS_FACTORY) {
+            return "dust";
+        } else if (factory == DustColorTransitionParticleEffect.FACTORY) {
+            return "dust_color_transition";
+        } else if (factory == ItemStackParticleEffect.PARAMETERS_FACTORY) {
+            return "item";
+        } else if (factory == VibrationParticleEffect.PARAMETERS_FACTORY) {
+            return "vibration";
+        } else if (factory == SculkChargeParticleEffect.FACTORY) {
+            return "sculk_charge";
+        } else if (factory == ShriekParticleEffect.FACTORY) {
+            return "shriek";
+        }
+
+        throw new IllegalArgumentException("Unknown particle data for " + Registry.PARTICLE_TYPE.getId(type));
+    }
+}
@@ -0,0 +1,30 @@
+package dev._00a.valence_extractor.extractors;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import dev._00a.valence_extractor.Main;
+import net.minecraft.util.registry.Registry;
+
+public class Sounds implements Main.Extractor {
+    @Override
+    public String fileName() {
+        return "sounds.json";
+    }
+
+    @Override
+    public JsonElement extract() {
+        var soundsJson = new JsonArray();
+
+        for (var sound : Registry.SOUND_EVENT) {
+            var soundJson = new JsonObject();
+
+            soundJson.addProperty("id", Registry.SOUND_EVENT.getRawId(sound));
+            soundJson.addProperty("name", Registry.SOUND_EVENT.getId(sound).getPath());
+
+            soundsJson.add(soundJson);
+        }
+
+        return soundsJson;
+    }
+}
@@ -0,0 +1,76 @@
+package dev._00a.valence_extractor.extractors;
+
+import com.google.gson.JsonArray;
+import com.google.gson.JsonElement;
+import com.google.gson.JsonObject;
+import dev._00a.valence_extractor.Main;
+import net.minecraft.resource.LifecycledResourceManagerImpl;
+import net.minecraft.resource.ResourceManager;
+import net.minecraft.resource.ResourceType;
+import net.minecraft.resource.VanillaDataPackProvider;
+import net.minecraft.tag.TagGroupLoader;
+import net.minecraft.tag.TagManagerLoader;
+import net.minecraft.util.registry.BuiltinRegistries;
+import net.minecraft.util.registry.Registry;
+
+import java.util.List;
+import java.util.Map;
+import java.util.TreeMap;
+
+public class Tags implements Main.Extractor {
+    @Override
+    public String fileName() {
+        return "tags.json";
+    }
+
+    @Override
+    public JsonElement extract() {
+        // Tags are only bound to the registries once a server loads its data packs, so read them from the vanilla
+        // data pack instead.
+        try (var resourceManager = new LifecycledResourceManagerImpl(ResourceType.SERVER_DATA, List.of(VanillaDataPackProvider.createDefaultPack()))) {
+            var registries = new TreeMap<String, JsonObject>();
+
+            // Clients receive the tags of both the static and the dynamic registries.
+            Registry.REGISTRIES.forEach(registry -> addTags(registries, resourceManager, registry));
+            BuiltinRegistries.REGISTRIES.forEach(registry -> addTags(registries, resourceManager, registry));
+
+            var tagsJson = new JsonObject();
+            registries.forEach(tagsJson::add);
+            return tagsJson;
+        }
+    }
+
+    /**
+     * Adds the tags of a registry to {@code registries} if it has any.
+     */
+    private static <T> void addTags(Map<String, JsonObject> registries, ResourceManager resourceManager, Registry<T> registry) {
+        var tagsJson = extractTags(resourceManager, registry);
+
+        if (tagsJson.size() > 0) {
+            registries.put(registry.getKey().getValue().getPath(), tagsJson);
+        }
+    }
+
+    /**
+     * Loads the tags of a registry with nested tags resolved to their entries.
+     */
+    private static <T> JsonObject extractTags(ResourceManager resourceManager, Registry<T> registry) {
+        var loader = new TagGroupLoader<>(registry::getOrEmpty, TagManagerLoader.getPath(registry.getKey()));
+
+        var tags = new TreeMap<String, JsonArray>();
+        loader.load(resourceManager).forEach((id, entries) -> {
+            var valuesJson = new JsonArray();
+            for (var entry : entries) {
+                var entryJson = new JsonObject();
+                entryJson.addProperty("id", registry.getRawId(entry));
+                entryJson.addProperty("name", registry.getId(entry).getPath());
+                valuesJson.add(entryJson);
+            }
+            tags.put(id.getPath(), valuesJson);
+        });
+
+        var tagsJson = new JsonObject();
+        tags.forEach(tagsJson::add);
+        return tagsJson;
+    }
+}
@@ -2,11 +2,18 @@
 
 #![allow(clippy::all, missing_docs)]
 
+use std::collections::BTreeMap;
 use std::fmt::{self, Display};
 use std::io::{Read, Write};
 use std::iter::FusedIterator;
+use std::str::FromStr;
 
 use anyhow::Context;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_nbt::{Compound, Value};
+use thiserror::Error;
 
 pub use crate::block_pos::BlockPos;
 use crate::protocol::{Decode, Encode, VarInt};
@@ -51,6 +58,427 @@ fn fmt_block_state(bs: BlockState, f: &mut fmt::Formatter) -> fmt::Result {
     }
 }
 
+/// A rotation around the Y axis in steps of 90 degrees, as seen from above.
+///
+/// Used to transform block states and structures.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Counterclockwise90,
+}
+
+impl Rotation {
+    /// Returns the number of clockwise quarter turns in this rotation.
+    const fn quarter_turns(self) -> u8 {
+        match self {
+            Rotation::None => 0,
+            Rotation::Clockwise90 => 1,
+            Rotation::Clockwise180 => 2,
+            Rotation::Counterclockwise90 => 3,
+        }
+    }
+
+    /// Rotates a block position around the origin.
+    pub const fn rotate_pos(self, pos: BlockPos) -> BlockPos {
+        let BlockPos { x, y, z } = pos;
+        match self {
+            Rotation::None => BlockPos::new(x, y, z),
+            Rotation::Clockwise90 => BlockPos::new(-z, y, x),
+            Rotation::Clockwise180 => BlockPos::new(-x, y, -z),
+            Rotation::Counterclockwise90 => BlockPos::new(z, y, -x),
+        }
+    }
+
+    fn rotate_word(self, word: &str) -> &str {
+        const DIRS: [&str; 4] = ["north", "east", "south", "west"];
+
+        match DIRS.iter().position(|&d| d == word) {
+            Some(i) => DIRS[(i + self.quarter_turns() as usize) % 4],
+            None => word,
+        }
+    }
+}
+
+/// A reflection across a vertical plane.
+///
+/// Used to transform block states and structures.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Mirror {
+    #[default]
+    None,
+    /// Flips the Z axis, swapping north and south.
+    LeftRight,
+    /// Flips the X axis, swapping east and west.
+    FrontBack,
+}
+
+impl Mirror {
+    /// Mirrors a block position across the plane through the origin.
+    pub const fn mirror_pos(self, pos: BlockPos) -> BlockPos {
+        let BlockPos { x, y, z } = pos;
+        match self {
+            Mirror::None => BlockPos::new(x, y, z),
+            Mirror::LeftRight => BlockPos::new(x, y, -z),
+            Mirror::FrontBack => BlockPos::new(-x, y, z),
+        }
+    }
+
+    fn mirror_word(self, word: &str) -> &str {
+        match (self, word) {
+            (Mirror::None, w) => w,
+            (Mirror::LeftRight, "north") => "south",
+            (Mirror::LeftRight, "south") => "north",
+            (Mirror::FrontBack, "east") => "west",
+            (Mirror::FrontBack, "west") => "east",
+            (_, "left") => "right",
+            (_, "right") => "left",
+            (_, w) => w,
+        }
+    }
+}
+
+/// The properties whose values contain directions or handedness, such as
+/// `facing=north`, `shape=ascending_east` or `hinge=left`.
+const DIRECTIONAL_PROPS: [PropName; 5] = [
+    PropName::Facing,
+    PropName::Shape,
+    PropName::Orientation,
+    PropName::Hinge,
+    PropName::Type,
+];
+
+/// Applies `f` to every word of a property value. Values whose words end up
+/// out of order (e.g. `east_north` for rails) are tried in reverse.
+fn map_value_words<'a>(val: PropValue, f: impl Fn(&'a str) -> &'a str) -> Option<PropValue> {
+    let words: Vec<_> = val.to_str().split('_').map(f).collect();
+
+    PropValue::from_str(&words.join("_")).or_else(|| {
+        let reversed: Vec<_> = words.iter().rev().copied().collect();
+        PropValue::from_str(&reversed.join("_"))
+    })
+}
+
+impl BlockState {
+    /// Rotates this block state around the Y axis, as if the block was placed
+    /// in a rotated structure.
+    ///
+    /// Properties such as `facing`, `axis`, `rotation`, rail shapes, and the
+    /// `north`/`east`/`south`/`west` connections of fences and walls are
+    /// updated. Other properties are unchanged.
+    #[must_use]
+    pub fn rotate(self, rot: Rotation) -> Self {
+        if rot == Rotation::None {
+            return self;
+        }
+
+        let mut res = self;
+
+        for prop in DIRECTIONAL_PROPS {
+            if let Some(val) = self.get(prop) {
+                if let Some(new_val) = map_value_words(val, |w| rot.rotate_word(w)) {
+                    res = res.set(prop, new_val);
+                }
+            }
+        }
+
+        if rot.quarter_turns() % 2 == 1 {
+            match self.get(PropName::Axis) {
+                Some(PropValue::X) => res = res.set(PropName::Axis, PropValue::Z),
+                Some(PropValue::Z) => res = res.set(PropName::Axis, PropValue::X),
+                _ => {}
+            }
+        }
+
+        if let Some(r) = self.get(PropName::Rotation).and_then(|v| v.to_u16()) {
+            let r = (r + rot.quarter_turns() as u16 * 4) % 16;
+            res = res.set(PropName::Rotation, PropValue::from_u16(r).unwrap());
+        }
+
+        const SIDES: [PropName; 4] = [
+            PropName::North,
+            PropName::East,
+            PropName::South,
+            PropName::West,
+        ];
+
+        for (i, side) in SIDES.into_iter().enumerate() {
+            if let Some(val) = self.get(side) {
+                res = res.set(SIDES[(i + rot.quarter_turns() as usize) % 4], val);
+            }
+        }
+
+        res
+    }
+
+    /// Mirrors this block state, as if the block was placed in a mirrored
+    /// structure.
+    ///
+    /// In addition to the properties changed by [`rotate`](Self::rotate),
+    /// left and right are swapped for stair shapes, door hinges and chest
+    /// types.
+    #[must_use]
+    pub fn mirror(self, mirror: Mirror) -> Self {
+        if mirror == Mirror::None {
+            return self;
+        }
+
+        let mut res = self;
+
+        for prop in DIRECTIONAL_PROPS {
+            if let Some(val) = self.get(prop) {
+                if let Some(new_val) = map_value_words(val, |w| mirror.mirror_word(w)) {
+                    res = res.set(prop, new_val);
+                }
+            }
+        }
+
+        if let Some(r) = self.get(PropName::Rotation).and_then(|v| v.to_u16()) {
+            let r = match mirror {
+                Mirror::None => r,
+                Mirror::LeftRight => (24 - r) % 16,
+                Mirror::FrontBack => (16 - r) % 16,
+            };
+            res = res.set(PropName::Rotation, PropValue::from_u16(r).unwrap());
+        }
+
+        let swapped = match mirror {
+            Mirror::None => None,
+            Mirror::LeftRight => Some((PropName::North, PropName::South)),
+            Mirror::FrontBack => Some((PropName::East, PropName::West)),
+        };
+
+        if let Some((a, b)) = swapped {
+            if let (Some(val_a), Some(val_b)) = (self.get(a), self.get(b)) {
+                res = res.set(a, val_b).set(b, val_a);
+            }
+        }
+
+        res
+    }
+}
+
+/// An error returned when a [`BlockState`] cannot be parsed from a string or
+/// NBT.
+#[derive(Clone, PartialEq, Eq, Debug, Error)]
+pub enum ParseBlockStateError {
+    /// The string is not of the form `name[prop=value,...]`.
+    #[error("invalid block state \"{0}\"")]
+    Syntax(String),
+    /// The NBT compound is missing the block name or has a property value that
+    /// is not a string.
+    #[error("invalid block state NBT")]
+    Nbt,
+    /// There is no block with the given name.
+    #[error("unknown block \"{0}\"")]
+    UnknownBlock(String),
+    /// The block does not have the given property.
+    #[error("unknown property \"{prop}\" for block \"{block}\"")]
+    UnknownProp { block: String, prop: String },
+    /// The property cannot have the given value.
+    #[error("invalid value \"{value}\" for property \"{prop}\" of block \"{block}\"")]
+    InvalidValue {
+        block: String,
+        prop: String,
+        value: String,
+    },
+}
+
+/// Creates a block state from a block name with an optional `minecraft:`
+/// namespace and a list of property names and values.
+///
+/// An error is returned if the block is unknown or a property is invalid for
+/// the block.
+pub(crate) fn block_state_from_parts<'a>(
+    name: &str,
+    props: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<BlockState, ParseBlockStateError> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let kind =
+        BlockKind::from_str(name).ok_or_else(|| ParseBlockStateError::UnknownBlock(name.into()))?;
+
+    let mut block = kind.to_state();
+
+    for (prop_name, val) in props {
+        let prop_name = PropName::from_str(prop_name)
+            .filter(|n| kind.props().contains(n))
+            .ok_or_else(|| ParseBlockStateError::UnknownProp {
+                block: name.into(),
+                prop: prop_name.into(),
+            })?;
+
+        let val = PropValue::from_str(val)
+            .filter(|&v| block.set(prop_name, v).get(prop_name) == Some(v))
+            .ok_or_else(|| ParseBlockStateError::InvalidValue {
+                block: name.into(),
+                prop: prop_name.to_str().into(),
+                value: val.into(),
+            })?;
+
+        block = block.set(prop_name, val);
+    }
+
+    Ok(block)
+}
+
+/// Formats a block state in the form `minecraft:name[prop=value,...]`, which is
+/// used by commands, schematic palettes and the serde representation.
+pub(crate) fn format_block_state(block: BlockState) -> String {
+    let kind = block.to_kind();
+    let mut s = format!("minecraft:{}", kind.to_str());
+
+    if !kind.props().is_empty() {
+        let props: Vec<_> = kind
+            .props()
+            .iter()
+            .map(|&p| format!("{}={}", p.to_str(), block.get(p).unwrap().to_str()))
+            .collect();
+
+        s.push('[');
+        s.push_str(&props.join(","));
+        s.push(']');
+    }
+
+    s
+}
+
+/// Parses a block state in the form `name[prop=value,...]`, where the name may
+/// have a `minecraft:` namespace. Properties that are left out keep their
+/// default values.
+///
+/// Both the [`Display`] output and the vanilla format used by commands are
+/// accepted.
+impl FromStr for BlockState {
+    type Err = ParseBlockStateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let syntax_err = || ParseBlockStateError::Syntax(s.into());
+
+        let (name, props) = match s.trim().split_once('[') {
+            Some((name, props)) => (name, props.strip_suffix(']').ok_or_else(syntax_err)?),
+            None => (s.trim(), ""),
+        };
+
+        let props = props
+            .split(',')
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| {
+                p.split_once('=')
+                    .map(|(k, v)| (k.trim(), v.trim()))
+                    .ok_or_else(syntax_err)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        block_state_from_parts(name.trim(), props)
+    }
+}
+
+impl BlockState {
+    /// Converts this block state to the NBT compound used by structure files
+    /// and chunk palettes, such as
+    /// `{Name: "minecraft:oak_log", Properties: {axis: "y"}}`.
+    pub fn to_nbt(self) -> Compound {
+        let kind = self.to_kind();
+
+        let mut nbt = Compound::from_iter([(
+            "Name".into(),
+            Value::String(format!("minecraft:{}", kind.to_str())),
+        )]);
+
+        if !kind.props().is_empty() {
+            let props = kind
+                .props()
+                .iter()
+                .map(|&p| {
+                    (
+                        p.to_str().into(),
+                        Value::String(self.get(p).unwrap().to_str().into()),
+                    )
+                })
+                .collect();
+
+            nbt.insert("Properties".into(), Value::Compound(props));
+        }
+
+        nbt
+    }
+
+    /// Reads a block state from an NBT compound in the format returned by
+    /// [`Self::to_nbt`]. Properties that are left out keep their default
+    /// values.
+    pub fn from_nbt(nbt: &Compound) -> Result<Self, ParseBlockStateError> {
+        let name = match nbt.get("Name") {
+            Some(Value::String(name)) => name,
+            _ => return Err(ParseBlockStateError::Nbt),
+        };
+
+        let mut props = Vec::new();
+
+        if let Some(Value::Compound(properties)) = nbt.get("Properties") {
+            for (prop, val) in properties {
+                match val {
+                    Value::String(val) => props.push((prop.as_str(), val.as_str())),
+                    _ => return Err(ParseBlockStateError::Nbt),
+                }
+            }
+        }
+
+        block_state_from_parts(name, props)
+    }
+}
+
+/// Block states are serialized as strings in the form
+/// `minecraft:name[prop=value,...]`.
+impl Serialize for BlockState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        format_block_state(*self).serialize(serializer)
+    }
+}
+
+/// Block states are deserialized from either a string accepted by
+/// [`BlockState::from_str`] or a map in the format of [`BlockState::to_nbt`].
+impl<'de> Deserialize<'de> for BlockState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BlockStateVisitor)
+    }
+}
+
+struct BlockStateVisitor;
+
+impl<'de> Visitor<'de> for BlockStateVisitor {
+    type Value = BlockState;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a block state string or compound")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        #[derive(Deserialize)]
+        struct Repr {
+            #[serde(rename = "Name")]
+            name: String,
+            #[serde(rename = "Properties", default)]
+            properties: BTreeMap<String, String>,
+        }
+
+        let repr = Repr::deserialize(MapAccessDeserializer::new(map))?;
+
+        block_state_from_parts(
+            &repr.name,
+            repr.properties
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        )
+        .map_err(de::Error::custom)
+    }
+}
+
 impl Encode for BlockState {
     fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
         VarInt(self.0 as i32).encode(w)
@@ -81,4 +509,147 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn rotate_and_mirror() {
+        let stairs = BlockState::OAK_STAIRS
+            .set(PropName::Facing, PropValue::North)
+            .set(PropName::Shape, PropValue::InnerLeft);
+
+        let rotated = stairs.rotate(Rotation::Clockwise90);
+        assert_eq!(rotated.get(PropName::Facing), Some(PropValue::East));
+        assert_eq!(rotated.get(PropName::Shape), Some(PropValue::InnerLeft));
+
+        let mirrored = stairs.mirror(Mirror::LeftRight);
+        assert_eq!(mirrored.get(PropName::Facing), Some(PropValue::South));
+        assert_eq!(mirrored.get(PropName::Shape), Some(PropValue::InnerRight));
+
+        let rail = BlockState::RAIL.set(PropName::Shape, PropValue::SouthEast);
+        assert_eq!(
+            rail.rotate(Rotation::Clockwise90).get(PropName::Shape),
+            Some(PropValue::SouthWest)
+        );
+
+        let log = BlockState::OAK_LOG.set(PropName::Axis, PropValue::X);
+        assert_eq!(
+            log.rotate(Rotation::Counterclockwise90).get(PropName::Axis),
+            Some(PropValue::Z)
+        );
+
+        let fence = BlockState::OAK_FENCE.set(PropName::North, PropValue::True);
+        let rotated = fence.rotate(Rotation::Clockwise180);
+        assert_eq!(rotated.get(PropName::North), Some(PropValue::False));
+        assert_eq!(rotated.get(PropName::South), Some(PropValue::True));
+
+        let sign = BlockState::OAK_SIGN.set(PropName::Rotation, PropValue::_0);
+        assert_eq!(
+            sign.mirror(Mirror::LeftRight).get(PropName::Rotation),
+            Some(PropValue::_8)
+        );
+
+        for kind in BlockKind::ALL {
+            let block = kind.to_state();
+            let cw = Rotation::Clockwise90;
+
+            assert_eq!(block.rotate(cw).rotate(cw).rotate(cw).rotate(cw), block);
+            assert_eq!(block.rotate(cw).rotate(Rotation::Counterclockwise90), block);
+            assert_eq!(
+                block.mirror(Mirror::FrontBack).mirror(Mirror::FrontBack),
+                block
+            );
+        }
+    }
+
+    #[test]
+    fn block_state_strings() {
+        for kind in BlockKind::ALL {
+            let block = kind.to_state();
+            assert_eq!(format_block_state(block).parse::<BlockState>(), Ok(block));
+            assert_eq!(block.to_string().parse::<BlockState>(), Ok(block));
+            assert_eq!(BlockState::from_nbt(&block.to_nbt()), Ok(block));
+        }
+
+        assert_eq!(
+            "oak_log[axis=z]".parse(),
+            Ok(BlockState::OAK_LOG.set(PropName::Axis, PropValue::Z))
+        );
+        assert_eq!(
+            "minecraft:oak_stairs[ facing=east ,half=top]".parse(),
+            Ok(BlockState::OAK_STAIRS
+                .set(PropName::Facing, PropValue::East)
+                .set(PropName::Half, PropValue::Top))
+        );
+        assert_eq!(
+            "minecraft:oak_log[axis=z".parse::<BlockState>(),
+            Err(ParseBlockStateError::Syntax(
+                "minecraft:oak_log[axis=z".into()
+            ))
+        );
+        assert_eq!(
+            "minecraft:oak_log[axis]".parse::<BlockState>(),
+            Err(ParseBlockStateError::Syntax(
+                "minecraft:oak_log[axis]".into()
+            ))
+        );
+        assert_eq!(
+            "minecraft:not_a_block".parse::<BlockState>(),
+            Err(ParseBlockStateError::UnknownBlock("not_a_block".into()))
+        );
+        assert_eq!(
+            "minecraft:stone[axis=z]".parse::<BlockState>(),
+            Err(ParseBlockStateError::UnknownProp {
+                block: "stone".into(),
+                prop: "axis".into()
+            })
+        );
+
+        let err = "minecraft:oak_log[axis=north]"
+            .parse::<BlockState>()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value \"north\" for property \"axis\" of block \"oak_log\""
+        );
+    }
+
+    #[test]
+    fn block_state_serde() {
+        let block = BlockState::OAK_LOG.set(PropName::Axis, PropValue::X);
+
+        let json = serde_json::to_string(&block).unwrap();
+        assert_eq!(json, r#""minecraft:oak_log[axis=x]""#);
+        assert_eq!(serde_json::from_str::<BlockState>(&json).unwrap(), block);
+
+        let map = r#"{"Name": "minecraft:oak_log", "Properties": {"axis": "x"}}"#;
+        assert_eq!(serde_json::from_str::<BlockState>(map).unwrap(), block);
+
+        let no_props = r#"{"Name": "stone"}"#;
+        assert_eq!(
+            serde_json::from_str::<BlockState>(no_props).unwrap(),
+            BlockState::STONE
+        );
+
+        assert!(serde_json::from_str::<BlockState>(r#""oak_log[axis=y""#).is_err());
+        assert!(serde_json::from_str::<BlockState>("5").is_err());
+    }
+
+    #[test]
+    fn block_kind_properties() {
+        assert_eq!(BlockKind::Stone.hardness(), 1.5);
+        assert_eq!(BlockKind::Bedrock.hardness(), -1.0);
+        assert!(BlockKind::Stone.requires_tool());
+        assert!(!BlockKind::Dirt.requires_tool());
+
+        assert!(BlockKind::Grass.is_replaceable());
+        assert!(BlockKind::Water.is_replaceable());
+        assert!(!BlockKind::Stone.is_replaceable());
+
+        assert!(BlockKind::Stone.is_solid());
+        assert!(!BlockKind::Torch.is_solid());
+        assert!(BlockKind::OakPlanks.is_flammable());
+        assert!(!BlockKind::Stone.is_flammable());
+
+        assert_eq!(BlockKind::Air.map_color().id(), 0);
+        assert_eq!(BlockKind::Stone.map_color().rgb(), 0x707070);
+    }
 }
@@ -4,38 +4,127 @@
 
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::io::Write;
 use std::iter::FusedIterator;
+use std::ops::Range;
+use std::sync::Arc;
 
 use bitvec::vec::BitVec;
+use bitvec::BitArr;
+use block_updates::BlockUpdates;
 use num::Integer;
+use paletted_container::PalettedContainer;
+use provider::ProviderState;
 use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use tickets::Tickets;
+use vek::Vec3;
 
 use crate::biome::BiomeId;
-use crate::block::BlockState;
+use crate::block::{BlockKind, BlockState, Mirror, Rotation};
 use crate::block_pos::BlockPos;
 pub use crate::chunk_pos::ChunkPos;
+use crate::client::Clients;
 use crate::config::Config;
 use crate::dimension::DimensionId;
 use crate::protocol::packets::s2c::play::{
-    BlockUpdate, ChunkData, ChunkDataHeightmaps, ChunkSectionUpdate, S2cPlayPacket,
+    BlockBreakingProgress, BlockEvent, BlockUpdate, ChunkData, ChunkDataHeightmaps,
+    ChunkSectionUpdate, S2cPlayPacket,
 };
 use crate::protocol::{Encode, NbtBridge, VarInt, VarLong};
+use crate::schematic::Schematic;
 use crate::server::SharedServer;
+use crate::world::WorldId;
+use crate::Ticks;
+
+mod block_updates;
+mod collision;
+mod direction;
+mod falling;
+mod fluid;
+mod paletted_container;
+mod provider;
+mod raycast;
+mod redstone;
+mod tickets;
+
+pub use block_updates::{BlockBehavior, TickPriority};
+pub use collision::MoveResult;
+pub(crate) use falling::is_concrete_powder;
+pub use provider::ChunkProvider;
+pub use raycast::BlockRaycastHit;
+pub use tickets::TicketId;
 
 /// A container for all [`Chunk`]s in a [`World`](crate::world::World).
+///
+/// # Tickets
+///
+/// Tickets are used to keep track of the chunks that need to be loaded. A chunk
+/// is ticketed if it is covered by at least one of the following:
+/// - **Player tickets**: Every client in the world has a ticket for the chunks
+///   within its view distance, plus a small margin. Player tickets are updated
+///   automatically every tick after [`Config::update`].
+/// - **Forced tickets**: Added with [`add_ticket`](Self::add_ticket) and kept
+///   until removed with [`remove_ticket`](Self::remove_ticket).
+/// - **Temporary tickets**: Added with
+///   [`add_temporary_ticket`](Self::add_temporary_ticket) and removed
+///   automatically once they expire.
+///
+/// If a [`ChunkProvider`] is set or automatic unloading is enabled with
+/// [`enable_auto_unload`](Self::enable_auto_unload), chunks without a ticket are
+/// removed every tick when the tickets are updated.
+///
+/// # Block Updates
+///
+/// Blocks can react to changes around them and schedule ticks for themselves
+/// by registering a [`BlockBehavior`] for their [`BlockKind`] with
+/// [`set_behavior`](Self::set_behavior).
+///
+/// Whenever a block is changed with [`set_block_state`](Self::set_block_state),
+/// the behavior of the new block is notified with
+/// [`BlockBehavior::state_changed`] and a _neighbor update_ is queued for the six
+/// blocks adjacent to it. If the kind of block changed, the behavior of the old
+/// block is notified with [`BlockBehavior::replaced`] first. Ticks can be
+/// scheduled for a later time with [`schedule_tick`](Self::schedule_tick), and
+/// blocks are also picked for [random ticks](Self::set_random_tick_speed).
+///
+/// Every tick after [`Config::update`], right after tickets are updated, the
+/// scheduled ticks that are due are run, followed by the random ticks and the
+/// queued neighbor updates. Neighbor updates queued by behaviors in the process
+/// are run in the same tick, and so are the neighbor updates caused by changes
+/// made in [`Config::update`].
+///
+/// Blocks changed through [`Chunk::set_block_state`] or the bulk editing
+/// functions such as [`fill`](Self::fill) do not cause neighbor updates.
+/// Scheduled ticks for blocks in unloaded chunks are discarded.
 pub struct Chunks<C: Config> {
     chunks: HashMap<ChunkPos, Chunk<C>>,
     shared: SharedServer<C>,
     dimension: DimensionId,
+    tickets: Tickets,
+    provider: Option<ProviderState<C>>,
+    on_unload: Option<UnloadCallback<C>>,
+    updates: BlockUpdates<C>,
+    /// Blocks that started falling and have not been spawned as entities yet.
+    falling_blocks: Vec<(BlockPos, BlockState)>,
+    /// Creates the state of falling block entities. Set while falling blocks
+    /// are enabled.
+    falling_block_state: Option<FallingBlockState<C>>,
 }
 
+type UnloadCallback<C> = Box<dyn FnMut(ChunkPos, Chunk<C>) + Send + Sync>;
+type FallingBlockState<C> = Box<dyn FnMut() -> <C as Config>::EntityState + Send + Sync>;
+
 impl<C: Config> Chunks<C> {
     pub(crate) fn new(shared: SharedServer<C>, dimension: DimensionId) -> Self {
         Self {
             chunks: HashMap::new(),
             shared,
             dimension,
+            tickets: Tickets::default(),
+            provider: None,
+            on_unload: None,
+            updates: BlockUpdates::default(),
+            falling_blocks: Vec::new(),
+            falling_block_state: None,
         }
     }
 
@@ -51,8 +140,31 @@ impl<C: Config> Chunks<C> {
     /// [`spawn`](crate::client::Client::spawn).
     pub fn insert(&mut self, pos: impl Into<ChunkPos>, state: C::ChunkState) -> &mut Chunk<C> {
         let section_count = (self.shared.dimension(self.dimension).height / 16) as u32;
-        let biome_registry_len = self.shared.biomes().len();
-        let chunk = Chunk::new(section_count, biome_registry_len, state);
+        let chunk = Chunk::new(section_count, state);
+
+        match self.chunks.entry(pos.into()) {
+            Entry::Occupied(mut oe) => {
+                oe.insert(chunk);
+                oe.into_mut()
+            }
+            Entry::Vacant(ve) => ve.insert(chunk),
+        }
+    }
+
+    /// Inserts an [`UnloadedChunk`] at the provided position and returns a
+    /// mutable reference to the new chunk.
+    ///
+    /// If the height of the unloaded chunk does not match the height of this
+    /// world's dimension, then it is resized to fit. If a chunk at the position
+    /// already exists, then the old chunk is overwritten.
+    pub fn insert_unloaded(
+        &mut self,
+        pos: impl Into<ChunkPos>,
+        mut chunk: UnloadedChunk,
+        state: C::ChunkState,
+    ) -> &mut Chunk<C> {
+        chunk.resize(self.shared.dimension(self.dimension).height as usize);
+        let chunk = Chunk::from_sections(chunk.sections.into(), state);
 
         match self.chunks.entry(pos.into()) {
             Entry::Occupied(mut oe) => {
@@ -63,6 +175,79 @@ impl<C: Config> Chunks<C> {
         }
     }
 
+    /// Sets the [`ChunkProvider`] used to generate and load chunks in this
+    /// world, replacing the previous one.
+    ///
+    /// Once a provider is set, ticketed chunks that are not loaded are
+    /// requested from the provider and chunks without a ticket are unloaded.
+    /// Requests that are still in progress for a previous provider are
+    /// discarded.
+    pub fn set_provider(&mut self, provider: impl ChunkProvider<C>) {
+        self.provider = Some(ProviderState::new(Arc::new(provider)));
+    }
+
+    /// Removes the [`ChunkProvider`] from this world. Chunks that are already
+    /// loaded are kept.
+    pub fn clear_provider(&mut self) {
+        self.provider = None;
+    }
+
+    /// Enables automatic unloading of chunks without a ticket.
+    ///
+    /// Every chunk without a ticket is removed at the start of the tick and
+    /// passed to `on_unload` along with its position. The chunk's
+    /// `ChunkState` can be saved from there.
+    ///
+    /// If a [`ChunkProvider`] is set, chunks without a ticket are always
+    /// unloaded and passed to [`ChunkProvider::unload`] instead.
+    pub fn enable_auto_unload(
+        &mut self,
+        on_unload: impl FnMut(ChunkPos, Chunk<C>) + Send + Sync + 'static,
+    ) {
+        self.on_unload = Some(Box::new(on_unload));
+    }
+
+    /// Disables automatic unloading of chunks enabled with
+    /// [`enable_auto_unload`](Self::enable_auto_unload).
+    pub fn disable_auto_unload(&mut self) {
+        self.on_unload = None;
+    }
+
+    /// Adds a forced ticket for all chunks within a square of `radius` chunks
+    /// around `center`. The chunks stay ticketed until the ticket is removed
+    /// with [`remove_ticket`](Self::remove_ticket).
+    pub fn add_ticket(&mut self, center: impl Into<ChunkPos>, radius: u32) -> TicketId {
+        self.tickets.add(center.into(), radius, None)
+    }
+
+    /// Adds a temporary ticket for all chunks within a square of `radius`
+    /// chunks around `center`. The ticket is removed automatically after
+    /// `duration` ticks have passed.
+    pub fn add_temporary_ticket(
+        &mut self,
+        center: impl Into<ChunkPos>,
+        radius: u32,
+        duration: Ticks,
+    ) -> TicketId {
+        let expires = self.shared.current_tick() + duration;
+        self.tickets.add(center.into(), radius, Some(expires))
+    }
+
+    /// Removes a forced or temporary ticket. Returns `true` if the ticket
+    /// existed.
+    pub fn remove_ticket(&mut self, id: TicketId) -> bool {
+        self.tickets.remove(id)
+    }
+
+    /// Returns `true` if the chunk at the provided position is covered by
+    /// at least one ticket.
+    ///
+    /// Player tickets are only updated every tick after [`Config::update`], so
+    /// they do not reflect client movement during the current tick.
+    pub fn is_ticketed(&self, pos: impl Into<ChunkPos>) -> bool {
+        self.tickets.contains(pos.into())
+    }
+
     /// Removes a chunk at the provided position.
     ///
     /// If a chunk exists at the position, then it is deleted and its
@@ -167,6 +352,9 @@ impl<C: Config> Chunks<C> {
     /// If the position is inside of a chunk, then `true` is returned.
     /// Otherwise, `false` is returned.
     ///
+    /// If the block state changed, a neighbor update is queued for the
+    /// adjacent blocks. See [Block Updates](Self#block-updates).
+    ///
     /// Note: if you need to set a large number of blocks, it may be more
     /// efficient write to the chunks directly with
     /// [`Chunk::set_block_state`].
@@ -179,11 +367,68 @@ impl<C: Config> Chunks<C> {
 
             if let Some(y) = pos.y.checked_sub(min_y).and_then(|y| y.try_into().ok()) {
                 if y < chunk.height() {
-                    chunk.set_block_state(
-                        pos.x.rem_euclid(16) as usize,
-                        y,
-                        pos.z.rem_euclid(16) as usize,
-                        block,
+                    let x = pos.x.rem_euclid(16) as usize;
+                    let z = pos.z.rem_euclid(16) as usize;
+
+                    let old = chunk.get_block_state(x, y, z);
+
+                    if old != block {
+                        chunk.set_block_state(x, y, z, block);
+                        self.updates.queue_neighbor_update(pos, old);
+                    }
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Gets the biome at a block position.
+    ///
+    /// If the position is not inside of a chunk, then `None` is returned.
+    pub fn get_biome(&self, pos: impl Into<BlockPos>) -> Option<BiomeId> {
+        let pos = pos.into();
+        let chunk = self.get(ChunkPos::from(pos))?;
+
+        let min_y = self.shared.dimension(self.dimension).min_y;
+        let y: usize = pos.y.checked_sub(min_y)?.try_into().ok()?;
+
+        if y < chunk.height() {
+            Some(chunk.get_biome(
+                pos.x.rem_euclid(16) as usize / 4,
+                y / 4,
+                pos.z.rem_euclid(16) as usize / 4,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Sets the biome of the 4x4x4 segment containing a block position.
+    ///
+    /// Note: biome changes are not sent to clients that have already loaded
+    /// the chunk.
+    ///
+    /// If the position is inside of a chunk, then `true` is returned.
+    /// Otherwise, `false` is returned.
+    pub fn set_biome(&mut self, pos: impl Into<BlockPos>, biome: BiomeId) -> bool {
+        let pos = pos.into();
+        let min_y = self.shared.dimension(self.dimension).min_y;
+
+        if let Some(chunk) = self.chunks.get_mut(&ChunkPos::from(pos)) {
+            if let Some(y) = pos
+                .y
+                .checked_sub(min_y)
+                .and_then(|y| usize::try_from(y).ok())
+            {
+                if y < chunk.height() {
+                    chunk.set_biome(
+                        pos.x.rem_euclid(16) as usize / 4,
+                        y / 4,
+                        pos.z.rem_euclid(16) as usize / 4,
+                        biome,
                     );
                     return true;
                 }
@@ -193,23 +438,403 @@ impl<C: Config> Chunks<C> {
         false
     }
 
+    /// Registers the [`BlockBehavior`] for blocks of the given kind, replacing
+    /// the previous behavior for that kind.
+    pub fn set_behavior(&mut self, kind: BlockKind, behavior: impl BlockBehavior<C>) {
+        self.updates.set_behavior(kind, Arc::new(behavior));
+    }
+
+    /// Removes the [`BlockBehavior`] for blocks of the given kind.
+    pub fn clear_behavior(&mut self, kind: BlockKind) {
+        self.updates.clear_behavior(kind);
+    }
+
+    /// Queues a neighbor update for the blocks adjacent to `pos`, as if the
+    /// block at `pos` had changed.
+    pub fn update_neighbors(&mut self, pos: impl Into<BlockPos>) {
+        let pos = pos.into();
+        let block = self.get_block_state(pos).unwrap_or(BlockState::AIR);
+
+        self.updates.queue_neighbor_update(pos, block);
+    }
+
+    /// Schedules a tick for the block at `pos` to run `delay` ticks from now.
+    /// When the tick is due, [`BlockBehavior::scheduled_tick`] is called if
+    /// the block at `pos` is still of the same [`BlockKind`].
+    ///
+    /// Returns `false` if the position is not inside of a chunk or if a tick
+    /// is already scheduled for the block, in which case nothing is scheduled.
+    pub fn schedule_tick(
+        &mut self,
+        pos: impl Into<BlockPos>,
+        delay: Ticks,
+        priority: TickPriority,
+    ) -> bool {
+        let pos = pos.into();
+
+        match self.get_block_state(pos) {
+            Some(block) => self.updates.schedule(
+                pos,
+                block.to_kind(),
+                self.shared.current_tick() + delay.max(1),
+                priority,
+            ),
+            None => false,
+        }
+    }
+
+    /// Returns `true` if a tick is scheduled for the block at `pos`.
+    pub fn is_tick_scheduled(&self, pos: impl Into<BlockPos>) -> bool {
+        let pos = pos.into();
+
+        self.get_block_state(pos)
+            .is_some_and(|b| self.updates.is_scheduled(pos, b.to_kind()))
+    }
+
+    /// Returns the number of blocks picked for a random tick in every chunk
+    /// section each tick.
+    pub fn random_tick_speed(&self) -> u32 {
+        self.updates.random_tick_speed
+    }
+
+    /// Sets the number of blocks picked for a random tick in every chunk
+    /// section each tick. Use `0` to disable random ticks.
+    ///
+    /// The blocks are picked at random from every section that is not
+    /// entirely air. [`BlockBehavior::random_tick`] is called for those with
+    /// a behavior. Vanilla uses random ticks to grow crops, spread grass and
+    /// melt ice, among other things. Unlike vanilla, blocks in all loaded
+    /// chunks receive random ticks, not just those near players.
+    ///
+    /// The default random tick speed is `3`, the same as vanilla's.
+    pub fn set_random_tick_speed(&mut self, speed: u32) {
+        self.updates.random_tick_speed = speed;
+    }
+
+    /// Sets every block in the box between two corners (inclusive) to
+    /// `block`.
+    ///
+    /// Blocks in unloaded chunks or outside the height of the world are
+    /// skipped.
+    pub fn fill(
+        &mut self,
+        corner_a: impl Into<BlockPos>,
+        corner_b: impl Into<BlockPos>,
+        block: BlockState,
+    ) {
+        for (pos, sect_y, [xs, ys, zs]) in self.sections_in_box(corner_a.into(), corner_b.into()) {
+            let sect = &mut self.chunks.get_mut(&pos).unwrap().sections[sect_y];
+
+            if xs.len() == 16 && ys.len() == 16 && zs.len() == 16 {
+                sect.fill(block);
+                continue;
+            }
+
+            for y in ys {
+                for z in zs.clone() {
+                    for x in xs.clone() {
+                        sect.set_block_state(x + z * 16 + y * 16 * 16, block);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces blocks in the box between two corners (inclusive). `f` is
+    /// called with the existing block states and returns the block to replace
+    /// them with, or `None` to leave them unchanged.
+    ///
+    /// `f` may be called once for an entire section of identical blocks.
+    /// Blocks in unloaded chunks or outside the height of the world are
+    /// skipped.
+    pub fn replace(
+        &mut self,
+        corner_a: impl Into<BlockPos>,
+        corner_b: impl Into<BlockPos>,
+        mut f: impl FnMut(BlockState) -> Option<BlockState>,
+    ) {
+        for (pos, sect_y, [xs, ys, zs]) in self.sections_in_box(corner_a.into(), corner_b.into()) {
+            let sect = &mut self.chunks.get_mut(&pos).unwrap().sections[sect_y];
+
+            if let PalettedContainer::Single(old) = sect.block_states {
+                if xs.len() == 16 && ys.len() == 16 && zs.len() == 16 {
+                    if let Some(new) = f(old) {
+                        sect.fill(new);
+                    }
+                    continue;
+                }
+            }
+
+            for y in ys {
+                for z in zs.clone() {
+                    for x in xs.clone() {
+                        let idx = x + z * 16 + y * 16 * 16;
+                        if let Some(new) = f(sect.block_states.get(idx)) {
+                            sect.set_block_state(idx, new);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Copies the blocks and biomes in the box between two corners (inclusive)
+    /// into a new [`Schematic`], which can be used as a clipboard.
+    ///
+    /// Blocks in unloaded chunks or outside the height of the world are
+    /// copied as air.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the box is larger than `u16::MAX` blocks along any axis.
+    pub fn copy(&self, corner_a: impl Into<BlockPos>, corner_b: impl Into<BlockPos>) -> Schematic {
+        let (a, b) = (corner_a.into(), corner_b.into());
+        let min = BlockPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let max = BlockPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+
+        let size = Vec3::new(max.x - min.x, max.y - min.y, max.z - min.z)
+            .map(|n| u16::try_from(n as i64 + 1).expect("region is too large to copy"));
+
+        let mut schem = Schematic::new(size);
+        let min_y = self.shared.dimension(self.dimension).min_y;
+
+        // Maps biome IDs to indices in the schematic's biome palette.
+        let mut biome_indices = HashMap::new();
+
+        for (pos, sect_y, [xs, ys, zs]) in self.sections_in_box(min, max) {
+            let sect = &self.chunks[&pos].sections[sect_y];
+
+            let base_x = (pos.x * 16 - min.x) as isize;
+            let base_y = (sect_y as i32 * 16 + min_y - min.y) as isize;
+            let base_z = (pos.z * 16 - min.z) as isize;
+
+            for y in ys {
+                for z in zs.clone() {
+                    for x in xs.clone() {
+                        let idx = schem.index(
+                            (base_x + x as isize) as usize,
+                            (base_y + y as isize) as usize,
+                            (base_z + z as isize) as usize,
+                        );
+
+                        schem.blocks[idx] = sect.block_states.get(x + z * 16 + y * 16 * 16);
+
+                        let biome = sect.biomes.get(x / 4 + z / 4 * 4 + y / 4 * 4 * 4);
+                        let palette_idx = *biome_indices.entry(biome).or_insert_with(|| {
+                            schem
+                                .biome_palette
+                                .push(self.shared.biome(biome).name.clone());
+                            schem.biome_palette.len() as u16 - 1
+                        });
+
+                        if schem.biomes.is_empty() {
+                            schem.biomes = vec![palette_idx; schem.blocks.len()];
+                        }
+                        schem.biomes[idx] = palette_idx;
+                    }
+                }
+            }
+        }
+
+        schem
+    }
+
+    /// Places the blocks and biomes of a [`Schematic`] into this world.
+    ///
+    /// The block at offsets `(x, y, z)` in the schematic is placed at
+    /// `origin + rotation(mirror(schematic.offset + (x, y, z)))`. Block states
+    /// are transformed with [`BlockState::mirror`] and [`BlockState::rotate`].
+    ///
+    /// Blocks in unloaded chunks or outside the height of the world are
+    /// skipped. Biomes that are not registered on the server are also skipped.
+    pub fn paste(
+        &mut self,
+        schematic: &Schematic,
+        origin: impl Into<BlockPos>,
+        rotation: Rotation,
+        mirror: Mirror,
+    ) {
+        if rotation != Rotation::None || mirror != Mirror::None {
+            let mut transformed = schematic.clone();
+            transformed.mirror(mirror);
+            transformed.rotate(rotation);
+            return self.paste(&transformed, origin, Rotation::None, Mirror::None);
+        }
+
+        let origin = origin.into();
+        let min = BlockPos::new(
+            origin.x + schematic.offset.x,
+            origin.y + schematic.offset.y,
+            origin.z + schematic.offset.z,
+        );
+        let size = schematic.size().map(i32::from);
+
+        if size.product() == 0 {
+            return;
+        }
+
+        let max = BlockPos::new(min.x + size.x - 1, min.y + size.y - 1, min.z + size.z - 1);
+
+        let biome_ids: Vec<_> = schematic
+            .biome_palette
+            .iter()
+            .map(|name| {
+                self.shared
+                    .biomes()
+                    .find(|(_, b)| b.name == *name)
+                    .map(|(id, _)| id)
+            })
+            .collect();
+
+        let min_y = self.shared.dimension(self.dimension).min_y;
+
+        for (pos, sect_y, [xs, ys, zs]) in self.sections_in_box(min, max) {
+            let sect = &mut self.chunks.get_mut(&pos).unwrap().sections[sect_y];
+
+            let base_x = (pos.x * 16 - min.x) as isize;
+            let base_y = (sect_y as i32 * 16 + min_y - min.y) as isize;
+            let base_z = (pos.z * 16 - min.z) as isize;
+
+            for y in ys {
+                for z in zs.clone() {
+                    for x in xs.clone() {
+                        let idx = schematic.index(
+                            (base_x + x as isize) as usize,
+                            (base_y + y as isize) as usize,
+                            (base_z + z as isize) as usize,
+                        );
+
+                        sect.set_block_state(x + z * 16 + y * 16 * 16, schematic.blocks[idx]);
+
+                        if let Some(&palette_idx) = schematic.biomes.get(idx) {
+                            if let Some(biome) = biome_ids[palette_idx as usize] {
+                                sect.biomes.set(x / 4 + z / 4 * 4 + y / 4 * 4 * 4, biome);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the sections of loaded chunks that overlap the box between two
+    /// corners (inclusive), along with the ranges of block offsets in each
+    /// section that are inside of the box.
+    fn sections_in_box(
+        &self,
+        corner_a: BlockPos,
+        corner_b: BlockPos,
+    ) -> Vec<(ChunkPos, usize, [Range<usize>; 3])> {
+        let dim = self.shared.dimension(self.dimension);
+
+        let min = BlockPos::new(
+            corner_a.x.min(corner_b.x),
+            corner_a.y.min(corner_b.y).max(dim.min_y),
+            corner_a.z.min(corner_b.z),
+        );
+        let max = BlockPos::new(
+            corner_a.x.max(corner_b.x),
+            corner_a.y.max(corner_b.y).min(dim.min_y + dim.height - 1),
+            corner_a.z.max(corner_b.z),
+        );
+
+        let mut res = Vec::new();
+
+        if min.y > max.y {
+            return res;
+        }
+
+        // Returns the range of offsets in the 16 block segment starting at `start`
+        // that are between `min` and `max`.
+        let range = |start: i32, min: i32, max: i32| {
+            (min.max(start) - start) as usize..(max.min(start + 15) - start + 1) as usize
+        };
+
+        for chunk_z in min.z.div_euclid(16)..=max.z.div_euclid(16) {
+            for chunk_x in min.x.div_euclid(16)..=max.x.div_euclid(16) {
+                let pos = ChunkPos::new(chunk_x, chunk_z);
+
+                if !self.chunks.contains_key(&pos) {
+                    continue;
+                }
+
+                let xs = range(chunk_x * 16, min.x, max.x);
+                let zs = range(chunk_z * 16, min.z, max.z);
+
+                for sect_y in (min.y - dim.min_y) / 16..=(max.y - dim.min_y) / 16 {
+                    let ys = range(sect_y * 16 + dim.min_y, min.y, max.y);
+                    res.push((pos, sect_y as usize, [xs.clone(), ys, zs.clone()]));
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Updates tickets, inserts the chunks that the provider has finished,
+    /// unloads chunks without a ticket, and requests missing chunks from the
+    /// provider.
+    pub(crate) fn update_tickets(&mut self, world: WorldId, clients: &Clients<C>) {
+        self.tickets
+            .update(world, clients, self.shared.current_tick());
+
+        if let Some(provider) = &mut self.provider {
+            provider.receive(&mut self.chunks, &self.tickets);
+        }
+
+        if self.provider.is_some() || self.on_unload.is_some() {
+            let unloaded: Vec<_> = self
+                .chunks
+                .keys()
+                .copied()
+                .filter(|&pos| !self.tickets.contains(pos))
+                .collect();
+
+            for pos in unloaded {
+                let chunk = self.chunks.remove(&pos).unwrap();
+
+                if let Some(provider) = &self.provider {
+                    provider.unload(pos, chunk);
+                } else if let Some(on_unload) = &mut self.on_unload {
+                    on_unload(pos, chunk);
+                }
+            }
+        }
+
+        if let Some(provider) = &mut self.provider {
+            let height = self.shared.dimension(self.dimension).height as usize;
+            provider.request(&self.chunks, &self.tickets, &self.shared, height);
+        }
+    }
+
     /// Apply chunk modifications to only the chunks that were created this
     /// tick.
     pub(crate) fn update_created_this_tick(&mut self) {
-        let biome_registry_len = self.shared.biomes().len();
         self.chunks.par_iter_mut().for_each(|(_, chunk)| {
             if chunk.created_this_tick() {
-                chunk.apply_modifications(biome_registry_len);
+                chunk.apply_modifications();
             }
         });
     }
 
+    /// Queues a block breaking progress or block event packet to be sent to
+    /// the clients that have the chunk containing `pos` loaded. Returns `false`
+    /// if the position is not inside of a chunk.
+    pub(crate) fn push_block_effect(&mut self, pos: BlockPos, pkt: BlockChangePacket) -> bool {
+        match self.chunks.get_mut(&ChunkPos::from(pos)) {
+            Some(chunk) => {
+                chunk.block_effects.push(pkt);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Apply chunk modifications to all chunks and clear the created_this_tick
     /// flag.
     pub(crate) fn update(&mut self) {
-        let biome_registry_len = self.shared.biomes().len();
         self.chunks.par_iter_mut().for_each(|(_, chunk)| {
-            chunk.apply_modifications(biome_registry_len);
+            chunk.apply_modifications();
             chunk.created_this_tick = false;
         });
     }
@@ -227,26 +852,39 @@ pub struct Chunk<C: Config> {
     // TODO block_entities: HashMap<u32, BlockEntity>,
     /// The MOTION_BLOCKING heightmap
     heightmap: Vec<i64>,
+    /// Block breaking progress and block event packets queued this tick.
+    block_effects: Vec<BlockChangePacket>,
     created_this_tick: bool,
 }
 
 impl<C: Config> Chunk<C> {
-    fn new(section_count: u32, biome_registry_len: usize, data: C::ChunkState) -> Self {
-        let sect = ChunkSection {
-            blocks: [BlockState::AIR.to_raw(); 4096],
-            modified_count: 1, // Must be >0 so the chunk is initialized.
-            biomes: [BiomeId::default(); 64],
-            compact_data: Vec::new(),
-        };
+    fn new(section_count: u32, state: C::ChunkState) -> Self {
+        Self::from_sections(
+            vec![ChunkSection::default(); section_count as usize].into(),
+            state,
+        )
+    }
+
+    fn from_sections(mut sections: Box<[ChunkSection]>, state: C::ChunkState) -> Self {
+        // Modifications made before the chunk was created do not need to be sent as
+        // block change packets.
+        for sect in sections.iter_mut() {
+            if sect.modified_blocks_count > 0 {
+                sect.modified_blocks_count = 0;
+                sect.modified_blocks.fill(false);
+                sect.block_states.optimize();
+            }
+        }
 
         let mut chunk = Self {
-            state: data,
-            sections: vec![sect; section_count as usize].into(),
+            state,
+            sections,
             heightmap: Vec::new(),
+            block_effects: Vec::new(),
             created_this_tick: true,
         };
 
-        chunk.apply_modifications(biome_registry_len);
+        build_heightmap(&chunk.sections, &mut chunk.heightmap);
         chunk
     }
 
@@ -271,9 +909,9 @@ impl<C: Config> Chunk<C> {
             "chunk block offsets must be within bounds"
         );
 
-        BlockState::from_raw_unchecked(
-            self.sections[y / 16].blocks[x + z * 16 + y % 16 * 16 * 16] & BLOCK_STATE_MASK,
-        )
+        self.sections[y / 16]
+            .block_states
+            .get(x + z * 16 + y % 16 * 16 * 16)
     }
 
     /// Sets the block state at the provided offsets in the chunk.
@@ -287,19 +925,7 @@ impl<C: Config> Chunk<C> {
             "chunk block offsets must be within bounds"
         );
 
-        let sect = &mut self.sections[y / 16];
-        let idx = x + z * 16 + y % 16 * 16 * 16;
-
-        if block.to_raw() != sect.blocks[idx] & BLOCK_STATE_MASK {
-            if sect.blocks[idx] & !BLOCK_STATE_MASK == 0 {
-                sect.modified_count += 1;
-            }
-            sect.blocks[idx] = block.to_raw() | !BLOCK_STATE_MASK;
-
-            // TODO: if the block type was modified and the old block type
-            // could be a block entity, then the block entity at this
-            // position must be cleared.
-        }
+        self.sections[y / 16].set_block_state(x + z * 16 + y % 16 * 16 * 16, block);
     }
 
     /// Gets the biome at the provided biome offsets in the chunk.
@@ -316,7 +942,7 @@ impl<C: Config> Chunk<C> {
             "chunk biome offsets must be within bounds"
         );
 
-        self.sections[y / 4].biomes[x + z * 4 + y % 4 * 4 * 4]
+        self.sections[y / 4].biomes.get(x + z * 4 + y % 4 * 4 * 4)
     }
 
     /// Sets the biome at the provided biome offsets in the chunk.
@@ -333,16 +959,37 @@ impl<C: Config> Chunk<C> {
             "chunk biome offsets must be within bounds"
         );
 
-        self.sections[y / 4].biomes[x + z * 4 + y % 4 * 4 * 4] = b;
+        self.sections[y / 4]
+            .biomes
+            .set(x + z * 4 + y % 4 * 4 * 4, b);
     }
 
-    /// Gets the chunk data packet for this chunk with the given position. This
-    /// does not include unapplied changes.
-    pub(crate) fn chunk_data_packet(&self, pos: ChunkPos) -> ChunkData {
+    /// Gets the chunk data packet for this chunk with the given position.
+    pub(crate) fn chunk_data_packet(&self, pos: ChunkPos, biome_registry_len: usize) -> ChunkData {
         let mut blocks_and_biomes = Vec::new();
 
         for sect in self.sections.iter() {
-            blocks_and_biomes.extend_from_slice(&sect.compact_data);
+            sect.non_air_count.encode(&mut blocks_and_biomes).unwrap();
+
+            sect.block_states
+                .encode_mc_format(
+                    &mut blocks_and_biomes,
+                    |b| b.to_raw().into(),
+                    4,
+                    8,
+                    log2_ceil(BlockState::max_raw() as usize + 1),
+                )
+                .unwrap();
+
+            sect.biomes
+                .encode_mc_format(
+                    &mut blocks_and_biomes,
+                    |b| b.0.into(),
+                    0,
+                    3,
+                    log2_ceil(biome_registry_len),
+                )
+                .unwrap();
         }
 
         ChunkData {
@@ -374,38 +1021,35 @@ impl<C: Config> Chunk<C> {
         mut push_packet: impl FnMut(BlockChangePacket),
     ) {
         for (sect_y, sect) in self.sections.iter().enumerate() {
-            if sect.modified_count == 1 {
-                let (idx, &block) = sect
-                    .blocks
-                    .iter()
-                    .enumerate()
-                    .find(|&(_, &b)| b & !BLOCK_STATE_MASK != 0)
+            if sect.modified_blocks_count == 1 {
+                let idx = sect
+                    .modified_blocks
+                    .first_one()
                     .expect("invalid modified count");
 
+                let block = sect.block_states.get(idx);
+
                 let global_x = pos.x * 16 + (idx % 16) as i32;
                 let global_y = sect_y as i32 * 16 + (idx / (16 * 16)) as i32 + min_y;
                 let global_z = pos.z * 16 + (idx / 16 % 16) as i32;
 
                 push_packet(BlockChangePacket::Single(BlockUpdate {
                     location: BlockPos::new(global_x, global_y, global_z),
-                    block_id: VarInt((block & BLOCK_STATE_MASK).into()),
+                    block_id: VarInt(block.to_raw().into()),
                 }));
-            } else if sect.modified_count > 1 {
-                let mut blocks = Vec::new();
-                for y in 0..16 {
-                    for z in 0..16 {
-                        for x in 0..16 {
-                            let block =
-                                sect.blocks[x as usize + z as usize * 16 + y as usize * 16 * 16];
-
-                            if block & !BLOCK_STATE_MASK != 0 {
-                                blocks.push(VarLong(
-                                    ((block & BLOCK_STATE_MASK) as i64) << 12
-                                        | (x << 8 | z << 4 | y),
-                                ))
-                            }
-                        }
-                    }
+            } else if sect.modified_blocks_count > 1 {
+                let mut blocks = Vec::with_capacity(sect.modified_blocks_count.into());
+
+                for idx in sect.modified_blocks.iter_ones() {
+                    let block = sect.block_states.get(idx);
+
+                    let x = (idx % 16) as i64;
+                    let z = (idx / 16 % 16) as i64;
+                    let y = (idx / (16 * 16)) as i64;
+
+                    blocks.push(VarLong(
+                        (block.to_raw() as i64) << 12 | (x << 8 | z << 4 | y),
+                    ));
                 }
 
                 let chunk_section_position = (pos.x as i64) << 42
@@ -419,46 +1063,24 @@ impl<C: Config> Chunk<C> {
                 }));
             }
         }
+
+        for pkt in &self.block_effects {
+            push_packet(pkt.clone());
+        }
     }
 
-    fn apply_modifications(&mut self, biome_registry_len: usize) {
+    fn apply_modifications(&mut self) {
         let mut any_modified = false;
 
+        self.block_effects.clear();
+
         for sect in self.sections.iter_mut() {
-            if sect.modified_count > 0 {
-                sect.modified_count = 0;
+            if sect.modified_blocks_count > 0 {
+                sect.modified_blocks_count = 0;
+                sect.modified_blocks.fill(false);
                 any_modified = true;
 
-                sect.compact_data.clear();
-
-                let mut non_air_block_count: i16 = 0;
-
-                for b in &mut sect.blocks {
-                    *b &= BLOCK_STATE_MASK;
-                    if !BlockState::from_raw_unchecked(*b).is_air() {
-                        non_air_block_count += 1;
-                    }
-                }
-
-                non_air_block_count.encode(&mut sect.compact_data).unwrap();
-
-                encode_paletted_container(
-                    sect.blocks.iter().cloned(),
-                    4,
-                    9,
-                    log2_ceil(BlockState::max_raw() as usize),
-                    &mut sect.compact_data,
-                )
-                .unwrap();
-
-                encode_paletted_container(
-                    sect.biomes.iter().map(|b| b.0),
-                    0,
-                    4,
-                    log2_ceil(biome_registry_len),
-                    &mut sect.compact_data,
-                )
-                .unwrap();
+                sect.block_states.optimize();
             }
         }
 
@@ -468,10 +1090,113 @@ impl<C: Config> Chunk<C> {
     }
 }
 
+/// A chunk which is not part of any [`World`](crate::world::World).
+///
+/// Unloaded chunks have the same accessors for blocks and biomes as [`Chunk`],
+/// but can be created and modified anywhere, including on other threads. Use
+/// [`Chunks::insert_unloaded`] to add one to a world.
+#[derive(Clone, Default)]
+pub struct UnloadedChunk {
+    sections: Vec<ChunkSection>,
+}
+
+impl UnloadedChunk {
+    /// Creates a new empty chunk with the given height in blocks.
+    ///
+    /// The height is rounded up to the next multiple of 16.
+    pub fn new(height: usize) -> Self {
+        let mut chunk = Self::default();
+        chunk.resize(height);
+        chunk
+    }
+
+    /// Changes the height of this chunk in blocks.
+    ///
+    /// The height is rounded up to the next multiple of 16. New blocks are
+    /// air and blocks above the new height are discarded.
+    pub fn resize(&mut self, height: usize) {
+        self.sections
+            .resize(Integer::div_ceil(&height, &16), ChunkSection::default());
+    }
+
+    /// Returns the height of this chunk in blocks.
+    pub fn height(&self) -> usize {
+        self.sections.len() * 16
+    }
+
+    /// Gets the block state at the provided offsets in the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the chunk.
+    pub fn get_block_state(&self, x: usize, y: usize, z: usize) -> BlockState {
+        assert!(
+            x < 16 && y < self.height() && z < 16,
+            "chunk block offsets must be within bounds"
+        );
+
+        self.sections[y / 16]
+            .block_states
+            .get(x + z * 16 + y % 16 * 16 * 16)
+    }
+
+    /// Sets the block state at the provided offsets in the chunk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the chunk.
+    pub fn set_block_state(&mut self, x: usize, y: usize, z: usize, block: BlockState) {
+        assert!(
+            x < 16 && y < self.height() && z < 16,
+            "chunk block offsets must be within bounds"
+        );
+
+        self.sections[y / 16].set_block_state(x + z * 16 + y % 16 * 16 * 16, block);
+    }
+
+    /// Gets the biome at the provided biome offsets in the chunk.
+    ///
+    /// Note: the arguments are **not** block positions. Biomes are 4x4x4
+    /// segments of a chunk, so `x` and `z` are in `0..=4`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the chunk.
+    pub fn get_biome(&self, x: usize, y: usize, z: usize) -> BiomeId {
+        assert!(
+            x < 4 && y < self.height() / 4 && z < 4,
+            "chunk biome offsets must be within bounds"
+        );
+
+        self.sections[y / 4].biomes.get(x + z * 4 + y % 4 * 4 * 4)
+    }
+
+    /// Sets the biome at the provided biome offsets in the chunk.
+    ///
+    /// Note: the arguments are **not** block positions. Biomes are 4x4x4
+    /// segments of a chunk, so `x` and `z` are in `0..=4`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the chunk.
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, b: BiomeId) {
+        assert!(
+            x < 4 && y < self.height() / 4 && z < 4,
+            "chunk biome offsets must be within bounds"
+        );
+
+        self.sections[y / 4]
+            .biomes
+            .set(x + z * 4 + y % 4 * 4 * 4, b);
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) enum BlockChangePacket {
     Single(BlockUpdate),
     Multi(ChunkSectionUpdate),
+    BreakingProgress(BlockBreakingProgress),
+    Event(BlockEvent),
 }
 
 impl From<BlockChangePacket> for S2cPlayPacket {
@@ -479,31 +1204,70 @@ impl From<BlockChangePacket> for S2cPlayPacket {
         match p {
             BlockChangePacket::Single(p) => p.into(),
             BlockChangePacket::Multi(p) => p.into(),
+            BlockChangePacket::BreakingProgress(p) => p.into(),
+            BlockChangePacket::Event(p) => p.into(),
         }
     }
 }
 
 /// A 16x16x16 section of blocks, biomes, and light in a chunk.
-#[derive(Clone)]
+#[derive(Clone, Default)]
 struct ChunkSection {
     /// The block states in this section, stored in x, z, y order.
-    /// The most significant bit is used to indicate if this block has been
-    /// modified.
-    blocks: [u16; 4096],
-    /// The number of modified blocks
-    modified_count: u16,
-    biomes: [BiomeId; 64],
-    compact_data: Vec<u8>,
+    block_states: PalettedContainer<BlockState, SECTION_BLOCK_COUNT, { SECTION_BLOCK_COUNT / 2 }>,
+    /// A bitset of the blocks that were modified this tick.
+    modified_blocks: BitArr!(for SECTION_BLOCK_COUNT),
+    /// The number of blocks that were modified this tick.
+    modified_blocks_count: u16,
+    biomes: PalettedContainer<BiomeId, 64, 32>,
+    /// Number of non-air blocks in this section.
+    non_air_count: u16,
 }
 
-const BLOCK_STATE_MASK: u16 = 0x7fff;
+const SECTION_BLOCK_COUNT: usize = 4096;
+
+impl ChunkSection {
+    fn fill(&mut self, block: BlockState) {
+        if let PalettedContainer::Single(old) = self.block_states {
+            if old == block {
+                return;
+            }
+        }
+
+        for idx in 0..SECTION_BLOCK_COUNT {
+            if self.block_states.get(idx) != block && !self.modified_blocks.replace(idx, true) {
+                self.modified_blocks_count += 1;
+            }
+        }
+
+        self.block_states = PalettedContainer::Single(block);
+        self.non_air_count = if block.is_air() {
+            0
+        } else {
+            SECTION_BLOCK_COUNT as u16
+        };
+    }
 
-const _: () = assert!(
-    BlockState::max_raw() <= BLOCK_STATE_MASK,
-    "There is not enough space in the block state type to store the modified bit. A bit array \
-     separate from the block state array should be created to keep track of modified blocks in \
-     the chunk section."
-);
+    fn set_block_state(&mut self, idx: usize, block: BlockState) {
+        let old_block = self.block_states.set(idx, block);
+
+        if block != old_block {
+            match (block.is_air(), old_block.is_air()) {
+                (true, false) => self.non_air_count -= 1,
+                (false, true) => self.non_air_count += 1,
+                _ => {}
+            }
+
+            if !self.modified_blocks.replace(idx, true) {
+                self.modified_blocks_count += 1;
+            }
+
+            // TODO: if the block type was modified and the old block type
+            // could be a block entity, then the block entity at this
+            // position must be cleared.
+        }
+    }
+}
 
 /// Builds the MOTION_BLOCKING heightmap.
 fn build_heightmap(sections: &[ChunkSection], heightmap: &mut Vec<i64>) {
@@ -518,9 +1282,9 @@ fn build_heightmap(sections: &[ChunkSection], heightmap: &mut Vec<i64>) {
     for x in 0..16 {
         for z in 0..16 {
             for y in (0..height).rev() {
-                let block = BlockState::from_raw_unchecked(
-                    sections[y / 16].blocks[x + z * 16 + y % 16 * 16 * 16] & BLOCK_STATE_MASK,
-                );
+                let block = sections[y / 16]
+                    .block_states
+                    .get(x + z * 16 + y % 16 * 16 * 16);
 
                 // TODO: is_solid || is_fluid heuristic for motion blocking.
                 if !block.is_air() {
@@ -537,80 +1301,6 @@ fn build_heightmap(sections: &[ChunkSection], heightmap: &mut Vec<i64>) {
     }
 }
 
-fn encode_paletted_container(
-    mut entries: impl ExactSizeIterator<Item = u16> + Clone,
-    min_bits_per_idx: usize,
-    direct_threshold: usize,
-    direct_bits_per_idx: usize,
-    w: &mut impl Write,
-) -> anyhow::Result<()> {
-    let mut palette = Vec::new();
-
-    for entry in entries.clone() {
-        if !palette.contains(&entry) {
-            palette.push(entry);
-        }
-    }
-
-    let bits_per_idx = log2_ceil(palette.len());
-
-    (bits_per_idx as u8).encode(w)?;
-
-    if bits_per_idx == 0 {
-        // Single value case
-        debug_assert_eq!(palette.len(), 1);
-        VarInt(palette[0] as i32).encode(w)?;
-        VarInt(0).encode(w)?; // data array length
-    } else if bits_per_idx >= direct_threshold {
-        // Direct case
-        // Skip the palette
-        let idxs_per_u64 = 64 / direct_bits_per_idx;
-        let u64_count = Integer::div_ceil(&entries.len(), &idxs_per_u64);
-
-        VarInt(u64_count as i32).encode(w)?;
-
-        for _ in 0..idxs_per_u64 {
-            let mut val = 0u64;
-            for i in 0..idxs_per_u64 {
-                if let Some(entry) = entries.next() {
-                    val |= (entry as u64) << (i * direct_bits_per_idx);
-                }
-            }
-            val.encode(w)?;
-        }
-    } else {
-        // Indirect case
-        VarInt(palette.len() as i32).encode(w)?;
-        for &val in &palette {
-            VarInt(val as i32).encode(w)?;
-        }
-
-        let bits_per_idx = bits_per_idx.max(min_bits_per_idx);
-        let idxs_per_u64 = 64 / bits_per_idx;
-        let u64_count = Integer::div_ceil(&entries.len(), &idxs_per_u64);
-
-        VarInt(u64_count as i32).encode(w)?;
-
-        for _ in 0..u64_count {
-            let mut val = 0u64;
-            for i in 0..idxs_per_u64 {
-                if let Some(entry) = entries.next() {
-                    let palette_idx = palette
-                        .iter()
-                        .position(|&e| e == entry)
-                        .expect("entry should be in the palette")
-                        as u64;
-
-                    val |= palette_idx << (i * bits_per_idx);
-                }
-            }
-            val.encode(w)?;
-        }
-    }
-
-    Ok(())
-}
-
 /// Calculates the log base 2 rounded up.
 fn log2_ceil(n: usize) -> usize {
     debug_assert_ne!(n, 0);
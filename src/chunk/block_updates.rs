@@ -0,0 +1,405 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use rand::Rng;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+use crate::block::{BlockKind, BlockPos, BlockState};
+use crate::chunk::{ChunkPos, Chunks, SECTION_BLOCK_COUNT};
+use crate::config::Config;
+use crate::Ticks;
+
+/// The maximum number of neighbor updates processed in a single tick. Any
+/// remaining updates are processed on the next tick. This prevents behaviors
+/// that keep updating each other from stalling the server.
+const MAX_NEIGHBOR_UPDATES: usize = 1_000_000;
+
+/// The random tick speed used by vanilla.
+const DEFAULT_RANDOM_TICK_SPEED: u32 = 3;
+
+/// Defines how blocks of a particular [`BlockKind`] react to block updates.
+///
+/// Behaviors are registered per world with
+/// [`Chunks::set_behavior`](crate::chunk::Chunks::set_behavior). See
+/// [`Chunks`](crate::chunk::Chunks) for more information about block updates.
+#[allow(unused_variables)]
+pub trait BlockBehavior<C: Config>: Send + Sync + 'static {
+    /// Called when the block at `pos` was changed to `block` with
+    /// [`Chunks::set_block_state`](crate::chunk::Chunks::set_block_state),
+    /// right before its neighbors are updated.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn state_changed(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {}
+
+    /// Called when the block `old` at `pos` was replaced by a block of a
+    /// different [`BlockKind`], right before its neighbors are updated.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn replaced(&self, chunks: &mut Chunks<C>, pos: BlockPos, old: BlockState) {}
+
+    /// Called when the block at `neighbor`, which is adjacent to `pos`, has
+    /// changed. `block` is the current block state at `pos`.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn neighbor_update(
+        &self,
+        chunks: &mut Chunks<C>,
+        pos: BlockPos,
+        block: BlockState,
+        neighbor: BlockPos,
+    ) {
+    }
+
+    /// Called when a tick scheduled with
+    /// [`Chunks::schedule_tick`](crate::chunk::Chunks::schedule_tick) for the
+    /// block at `pos` is due. `block` is the current block state at `pos`.
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn scheduled_tick(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {}
+
+    /// Called when the block at `pos` is picked for a random tick. See
+    /// [`Chunks::set_random_tick_speed`](crate::chunk::Chunks::set_random_tick_speed).
+    ///
+    /// # Default Implementation
+    ///
+    /// Does nothing.
+    fn random_tick(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {}
+}
+
+/// The priority of a scheduled block tick. Ticks that are due on the same game
+/// tick run in order of priority, highest first, and then in the order they
+/// were scheduled.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub enum TickPriority {
+    ExtremelyHigh = -3,
+    VeryHigh = -2,
+    High = -1,
+    #[default]
+    Normal = 0,
+    Low = 1,
+    VeryLow = 2,
+    ExtremelyLow = 3,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct ScheduledTick {
+    tick: Ticks,
+    priority: TickPriority,
+    /// Used to break ties in the order ticks were scheduled.
+    seq: u64,
+    pos: (i32, i32, i32),
+    kind: BlockKind,
+}
+
+pub(super) struct BlockUpdates<C: Config> {
+    behaviors: HashMap<BlockKind, Arc<dyn BlockBehavior<C>>>,
+    /// Positions and previous states of changed blocks whose neighbors have
+    /// not been notified yet.
+    neighbor_updates: VecDeque<(BlockPos, BlockState)>,
+    scheduled: BinaryHeap<Reverse<ScheduledTick>>,
+    /// The positions and block kinds in `scheduled`, used to avoid scheduling
+    /// the same tick twice.
+    scheduled_set: HashSet<(BlockPos, BlockKind)>,
+    next_seq: u64,
+    /// The number of blocks picked in every chunk section each tick.
+    pub random_tick_speed: u32,
+}
+
+impl<C: Config> Default for BlockUpdates<C> {
+    fn default() -> Self {
+        Self {
+            behaviors: HashMap::new(),
+            neighbor_updates: VecDeque::new(),
+            scheduled: BinaryHeap::new(),
+            scheduled_set: HashSet::new(),
+            next_seq: 0,
+            random_tick_speed: DEFAULT_RANDOM_TICK_SPEED,
+        }
+    }
+}
+
+impl<C: Config> BlockUpdates<C> {
+    pub fn set_behavior(&mut self, kind: BlockKind, behavior: Arc<dyn BlockBehavior<C>>) {
+        self.behaviors.insert(kind, behavior);
+    }
+
+    pub fn clear_behavior(&mut self, kind: BlockKind) {
+        self.behaviors.remove(&kind);
+    }
+
+    pub fn behavior(&self, kind: BlockKind) -> Option<Arc<dyn BlockBehavior<C>>> {
+        self.behaviors.get(&kind).cloned()
+    }
+
+    pub fn has_behavior(&self, kind: BlockKind) -> bool {
+        self.behaviors.contains_key(&kind)
+    }
+
+    /// Queues a neighbor update around `pos`, where `old` is the block that
+    /// was at `pos` before. Nothing is queued if there are no behaviors that
+    /// could react to it.
+    pub fn queue_neighbor_update(&mut self, pos: BlockPos, old: BlockState) {
+        if !self.behaviors.is_empty() {
+            self.neighbor_updates.push_back((pos, old));
+        }
+    }
+
+    pub fn schedule(
+        &mut self,
+        pos: BlockPos,
+        kind: BlockKind,
+        tick: Ticks,
+        priority: TickPriority,
+    ) -> bool {
+        if !self.scheduled_set.insert((pos, kind)) {
+            return false;
+        }
+
+        self.scheduled.push(Reverse(ScheduledTick {
+            tick,
+            priority,
+            seq: self.next_seq,
+            pos: pos.into(),
+            kind,
+        }));
+        self.next_seq += 1;
+
+        true
+    }
+
+    pub fn is_scheduled(&self, pos: BlockPos, kind: BlockKind) -> bool {
+        self.scheduled_set.contains(&(pos, kind))
+    }
+
+    /// Removes and returns the next scheduled tick that is due at
+    /// `current_tick`.
+    pub fn pop_due(&mut self, current_tick: Ticks) -> Option<(BlockPos, BlockKind)> {
+        let Reverse(next) = self.scheduled.peek()?;
+
+        if next.tick > current_tick {
+            return None;
+        }
+
+        let Reverse(next) = self.scheduled.pop().unwrap();
+        let pos = BlockPos::from(next.pos);
+        self.scheduled_set.remove(&(pos, next.kind));
+
+        Some((pos, next.kind))
+    }
+
+    /// Takes the neighbor updates that are currently queued, up to `max`.
+    pub fn take_neighbor_updates(&mut self, max: usize) -> Vec<(BlockPos, BlockState)> {
+        let count = self.neighbor_updates.len().min(max);
+        self.neighbor_updates.drain(..count).collect()
+    }
+}
+
+impl<C: Config> Chunks<C> {
+    /// Runs the scheduled ticks that are due, the random ticks and the queued
+    /// neighbor updates.
+    pub(crate) fn update_blocks(&mut self) {
+        let current_tick = self.shared.current_tick();
+
+        while let Some((pos, kind)) = self.updates.pop_due(current_tick) {
+            let Some(block) = self.get_block_state(pos) else {
+                continue;
+            };
+
+            // The block could have changed since the tick was scheduled.
+            if block.to_kind() != kind {
+                continue;
+            }
+
+            if let Some(behavior) = self.updates.behavior(kind) {
+                behavior.scheduled_tick(self, pos, block);
+            }
+        }
+
+        for (pos, picked) in self.pick_random_ticks() {
+            // The block could have been changed by another random tick.
+            if self.get_block_state(pos) != Some(picked) {
+                continue;
+            }
+
+            if let Some(behavior) = self.updates.behavior(picked.to_kind()) {
+                behavior.random_tick(self, pos, picked);
+            }
+        }
+
+        let mut remaining = MAX_NEIGHBOR_UPDATES;
+
+        while remaining > 0 {
+            let updates = self.updates.take_neighbor_updates(remaining);
+            if updates.is_empty() {
+                break;
+            }
+            remaining -= updates.len();
+
+            for (changed, old) in updates {
+                if let Some(block) = self.get_block_state(changed) {
+                    if block.to_kind() != old.to_kind() {
+                        if let Some(behavior) = self.updates.behavior(old.to_kind()) {
+                            behavior.replaced(self, changed, old);
+                        }
+                    }
+
+                    if let Some(behavior) = self.updates.behavior(block.to_kind()) {
+                        behavior.state_changed(self, changed, block);
+                    }
+                }
+
+                for neighbor in neighbors(changed) {
+                    let Some(block) = self.get_block_state(neighbor) else {
+                        continue;
+                    };
+
+                    if let Some(behavior) = self.updates.behavior(block.to_kind()) {
+                        behavior.neighbor_update(self, neighbor, block, changed);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<C: Config> Chunks<C> {
+    /// Picks `random_tick_speed` random blocks in every section that is not
+    /// empty and returns those with a behavior. Chunks are visited in
+    /// parallel.
+    fn pick_random_ticks(&self) -> Vec<(BlockPos, BlockState)> {
+        let speed = self.updates.random_tick_speed;
+
+        if speed == 0 || self.updates.behaviors.is_empty() {
+            return Vec::new();
+        }
+
+        let min_y = self.shared.dimension(self.dimension).min_y;
+
+        self.chunks
+            .par_iter()
+            .flat_map_iter(|(&chunk_pos, chunk)| {
+                let mut rng = rand::thread_rng();
+                let mut picked = Vec::new();
+
+                for (sect_y, sect) in chunk.sections.iter().enumerate() {
+                    if sect.non_air_count == 0 {
+                        continue;
+                    }
+
+                    for _ in 0..speed {
+                        let idx = rng.gen_range(0..SECTION_BLOCK_COUNT);
+                        let block = sect.block_states.get(idx);
+
+                        if self.updates.has_behavior(block.to_kind()) {
+                            let pos = section_block_pos(chunk_pos, min_y, sect_y, idx);
+                            picked.push((pos, block));
+                        }
+                    }
+                }
+
+                picked
+            })
+            .collect()
+    }
+}
+
+/// Converts an index into the blocks of a chunk section to a block position.
+fn section_block_pos(chunk_pos: ChunkPos, min_y: i32, sect_y: usize, idx: usize) -> BlockPos {
+    BlockPos::new(
+        chunk_pos.x * 16 + (idx % 16) as i32,
+        min_y + (sect_y * 16 + idx / 256) as i32,
+        chunk_pos.z * 16 + (idx / 16 % 16) as i32,
+    )
+}
+
+/// Returns the positions adjacent to `pos` in the order vanilla updates them.
+pub(crate) fn neighbors(pos: BlockPos) -> [BlockPos; 6] {
+    let BlockPos { x, y, z } = pos;
+
+    [
+        BlockPos::new(x - 1, y, z),
+        BlockPos::new(x + 1, y, z),
+        BlockPos::new(x, y - 1, z),
+        BlockPos::new(x, y + 1, z),
+        BlockPos::new(x, y, z - 1),
+        BlockPos::new(x, y, z + 1),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::Server;
+
+    struct Nothing;
+
+    impl Config for Nothing {
+        type ServerState = ();
+        type ClientState = ();
+        type EntityState = ();
+        type WorldState = ();
+        type ChunkState = ();
+        type PlayerListState = ();
+
+        fn max_connections(&self) -> usize {
+            0
+        }
+
+        fn update(&self, _server: &mut Server<Self>) {}
+    }
+
+    #[test]
+    fn scheduled_tick_order() {
+        let mut updates = BlockUpdates::<Nothing>::default();
+
+        let a = BlockPos::new(0, 0, 0);
+        let b = BlockPos::new(1, 0, 0);
+        let c = BlockPos::new(2, 0, 0);
+
+        assert!(updates.schedule(a, BlockKind::Sand, 5, TickPriority::Normal));
+        assert!(updates.schedule(b, BlockKind::Sand, 5, TickPriority::High));
+        assert!(updates.schedule(c, BlockKind::Sand, 2, TickPriority::Low));
+        assert!(updates.schedule(c, BlockKind::Water, 5, TickPriority::Normal));
+
+        // Ticks for the same block are only scheduled once.
+        assert!(!updates.schedule(a, BlockKind::Sand, 1, TickPriority::Normal));
+        assert!(updates.is_scheduled(a, BlockKind::Sand));
+
+        assert_eq!(updates.pop_due(1), None);
+        assert_eq!(updates.pop_due(4), Some((c, BlockKind::Sand)));
+        assert_eq!(updates.pop_due(4), None);
+        assert_eq!(updates.pop_due(5), Some((b, BlockKind::Sand)));
+        assert_eq!(updates.pop_due(5), Some((a, BlockKind::Sand)));
+        assert_eq!(updates.pop_due(5), Some((c, BlockKind::Water)));
+        assert_eq!(updates.pop_due(100), None);
+
+        assert!(!updates.is_scheduled(a, BlockKind::Sand));
+    }
+
+    #[test]
+    fn random_tick_positions() {
+        let chunk_pos = ChunkPos::new(-1, 2);
+
+        assert_eq!(
+            section_block_pos(chunk_pos, -64, 0, 0),
+            BlockPos::new(-16, -64, 32)
+        );
+        assert_eq!(
+            section_block_pos(chunk_pos, -64, 5, 4095),
+            BlockPos::new(-1, 31, 47)
+        );
+        assert_eq!(
+            section_block_pos(chunk_pos, 0, 1, 1 + 2 * 16 + 3 * 256),
+            BlockPos::new(-15, 19, 34)
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use vek::{Aabb, Vec3};
+
+use crate::block::{BlockPos, BlockState};
+use crate::chunk::Chunks;
+use crate::config::Config;
+
+/// Used to avoid treating boxes that are only touching as overlapping.
+const EPSILON: f64 = 1e-7;
+
+/// The result of moving a bounding box with [`Chunks::move_and_slide`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MoveResult {
+    /// The offset the bounding box was actually moved by. This is the
+    /// requested movement clipped against the blocks in the way.
+    pub movement: Vec3<f64>,
+    /// If the movement was obstructed along the X or Z axis.
+    pub horizontal_collision: bool,
+    /// If the movement was obstructed along the Y axis.
+    pub vertical_collision: bool,
+    /// If the bounding box landed on top of a block. This is the case when a
+    /// downward movement was obstructed.
+    pub on_ground: bool,
+}
+
+impl<C: Config> Chunks<C> {
+    /// Returns the [collision shapes] of all blocks that intersect `bb`,
+    /// translated to world coordinates. Shapes that only touch `bb` are not
+    /// included.
+    ///
+    /// Unloaded chunks and the space above and below the world have no
+    /// collision.
+    ///
+    /// [collision shapes]: BlockState::collision_shapes
+    pub fn block_collisions(&self, bb: Aabb<f64>) -> Vec<Aabb<f64>> {
+        block_collisions(bb, |pos| self.get_block_state(pos))
+    }
+
+    /// Moves the bounding box `bb` by `movement`, stopping at blocks in the way
+    /// and sliding along their surfaces.
+    ///
+    /// This works the same way as vanilla entity movement. If the box is
+    /// stopped horizontally while on the ground, it will try to step up onto
+    /// blocks no higher than `step_height`. Entities such as players and most
+    /// mobs have a step height of `0.6`. Use `0.0` to disable stepping.
+    ///
+    /// The returned [`MoveResult`] contains the offset the box should actually
+    /// be moved by. An entity's [hitbox] can be used as the bounding box.
+    ///
+    /// [hitbox]: crate::entity::Entity::hitbox
+    pub fn move_and_slide(
+        &self,
+        bb: Aabb<f64>,
+        movement: Vec3<f64>,
+        step_height: f64,
+    ) -> MoveResult {
+        move_and_slide(bb, movement, step_height, |pos| self.get_block_state(pos))
+    }
+}
+
+fn block_collisions(
+    bb: Aabb<f64>,
+    mut get_block: impl FnMut(BlockPos) -> Option<BlockState>,
+) -> Vec<Aabb<f64>> {
+    let mut res = Vec::new();
+
+    let min = bb.min.map(|n| n.floor() as i32);
+    let max = bb.max.map(|n| n.ceil() as i32);
+
+    for y in min.y - 1..max.y {
+        for z in min.z..max.z {
+            for x in min.x..max.x {
+                let Some(block) = get_block(BlockPos::new(x, y, z)) else {
+                    continue;
+                };
+
+                let offset = Vec3::new(x, y, z).as_::<f64>();
+
+                for shape in block.collision_shapes() {
+                    let shape = Aabb {
+                        min: shape.min + offset,
+                        max: shape.max + offset,
+                    };
+
+                    // Some collision shapes such as fences extend into the
+                    // block above them, which is why the layer below the box is
+                    // checked too.
+                    if shape.collides_with_aabb(bb) {
+                        res.push(shape);
+                    }
+                }
+            }
+        }
+    }
+
+    res
+}
+
+fn move_and_slide(
+    bb: Aabb<f64>,
+    movement: Vec3<f64>,
+    step_height: f64,
+    mut get_block: impl FnMut(BlockPos) -> Option<BlockState>,
+) -> MoveResult {
+    if movement == Vec3::zero() {
+        return MoveResult {
+            movement,
+            horizontal_collision: false,
+            vertical_collision: false,
+            on_ground: false,
+        };
+    }
+
+    let collisions = block_collisions(expand(bb, movement), &mut get_block);
+    let mut res = collide(bb, movement, &collisions);
+
+    let on_ground = res.y != movement.y && movement.y < 0.0;
+    let horizontal_collision = res.x != movement.x || res.z != movement.z;
+
+    if step_height > 0.0 && horizontal_collision && on_ground {
+        // Try stepping up onto the obstacle, moving horizontally and then
+        // stepping back down.
+        let step_movement = Vec3::new(movement.x, step_height, movement.z);
+        let collisions = block_collisions(expand(bb, step_movement), &mut get_block);
+
+        let up = collide_axis(bb, Vec3::unit_y(), step_height, &collisions);
+        let raised = translate(bb, Vec3::new(0.0, up, 0.0));
+        let mut step = collide(raised, Vec3::new(movement.x, 0.0, movement.z), &collisions);
+        step.y = up;
+
+        let down = collide_axis(
+            translate(bb, step),
+            Vec3::unit_y(),
+            movement.y - up,
+            &collisions,
+        );
+        step.y += down;
+
+        if step.x * step.x + step.z * step.z > res.x * res.x + res.z * res.z {
+            res = step;
+        }
+    }
+
+    MoveResult {
+        movement: res,
+        horizontal_collision: res.x != movement.x || res.z != movement.z,
+        vertical_collision: res.y != movement.y,
+        on_ground: res.y != movement.y && movement.y < 0.0,
+    }
+}
+
+/// Clips `movement` against `collisions`, first along the Y axis and then
+/// along the horizontal axes, larger movement first.
+fn collide(bb: Aabb<f64>, movement: Vec3<f64>, collisions: &[Aabb<f64>]) -> Vec3<f64> {
+    let mut res = Vec3::zero();
+
+    res.y = collide_axis(bb, Vec3::unit_y(), movement.y, collisions);
+
+    let axes = if movement.x.abs() < movement.z.abs() {
+        [Vec3::unit_z(), Vec3::unit_x()]
+    } else {
+        [Vec3::unit_x(), Vec3::unit_z()]
+    };
+
+    for axis in axes {
+        let amount = collide_axis(translate(bb, res), axis, movement.dot(axis), collisions);
+        res += axis * amount;
+    }
+
+    res
+}
+
+/// Returns how far `bb` can move along `axis` (up to `amount`) before hitting
+/// one of `collisions`.
+fn collide_axis(bb: Aabb<f64>, axis: Vec3<f64>, mut amount: f64, collisions: &[Aabb<f64>]) -> f64 {
+    let i = if axis.x != 0.0 {
+        0
+    } else if axis.y != 0.0 {
+        1
+    } else {
+        2
+    };
+
+    for c in collisions {
+        let overlaps_others = (0..3)
+            .filter(|&j| j != i)
+            .all(|j| c.max[j] - EPSILON > bb.min[j] && c.min[j] + EPSILON < bb.max[j]);
+
+        if !overlaps_others {
+            continue;
+        }
+
+        if amount > 0.0 && c.min[i] >= bb.max[i] - EPSILON {
+            amount = amount.min(c.min[i] - bb.max[i]).max(0.0);
+        } else if amount < 0.0 && c.max[i] <= bb.min[i] + EPSILON {
+            amount = amount.max(c.max[i] - bb.min[i]).min(0.0);
+        }
+    }
+
+    amount
+}
+
+/// Grows `bb` to cover the space it passes through when moved by `movement`.
+fn expand(bb: Aabb<f64>, movement: Vec3<f64>) -> Aabb<f64> {
+    Aabb {
+        min: bb.min + movement.map(|n| n.min(0.0)),
+        max: bb.max + movement.map(|n| n.max(0.0)),
+    }
+}
+
+fn translate(bb: Aabb<f64>, offset: Vec3<f64>) -> Aabb<f64> {
+    Aabb {
+        min: bb.min + offset,
+        max: bb.max + offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    /// A stone floor at `y = 0` with a one block step at `x = 2` and a two
+    /// block wall at `x = -2`.
+    fn get_block(pos: BlockPos) -> Option<BlockState> {
+        Some(match (pos.x, pos.y) {
+            (_, 0) => BlockState::STONE,
+            (2, 1) => BlockState::STONE,
+            (-2, 1..=2) => BlockState::STONE,
+            _ => BlockState::AIR,
+        })
+    }
+
+    fn player_box(x: f64, y: f64) -> Aabb<f64> {
+        Aabb {
+            min: Vec3::new(x - 0.3, y, 0.2),
+            max: Vec3::new(x + 0.3, y + 1.8, 0.8),
+        }
+    }
+
+    #[test]
+    fn collisions_in_box() {
+        let c = block_collisions(player_box(0.5, 0.9), get_block);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c[0].min, Vec3::new(0.0, 0.0, 0.0));
+
+        // Touching a block is not intersecting it.
+        let c = block_collisions(player_box(0.5, 1.0), get_block);
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn falls_onto_floor() {
+        let res = move_and_slide(
+            player_box(0.5, 1.5),
+            Vec3::new(0.0, -1.0, 0.0),
+            0.6,
+            get_block,
+        );
+
+        assert_relative_eq!(res.movement.y, -0.5);
+        assert!(res.on_ground);
+        assert!(res.vertical_collision);
+        assert!(!res.horizontal_collision);
+    }
+
+    #[test]
+    fn slides_along_wall() {
+        let res = move_and_slide(
+            player_box(-0.5, 1.0),
+            Vec3::new(-1.0, -0.1, 0.5),
+            0.6,
+            get_block,
+        );
+
+        assert_relative_eq!(res.movement.x, -0.2);
+        assert_relative_eq!(res.movement.y, 0.0);
+        assert_relative_eq!(res.movement.z, 0.5);
+        assert!(res.horizontal_collision);
+        assert!(res.on_ground);
+    }
+
+    #[test]
+    fn steps_up() {
+        // Stepping is possible onto a full block only with a step height of at
+        // least one block.
+        let res = move_and_slide(
+            player_box(1.5, 1.0),
+            Vec3::new(0.5, -0.1, 0.0),
+            0.6,
+            get_block,
+        );
+        assert_relative_eq!(res.movement.x, 0.2);
+        assert!(res.horizontal_collision);
+
+        let res = move_and_slide(
+            player_box(1.5, 1.0),
+            Vec3::new(0.5, -0.1, 0.0),
+            1.0,
+            get_block,
+        );
+        assert_relative_eq!(res.movement.x, 0.5);
+        assert_relative_eq!(res.movement.y, 1.0);
+        assert!(!res.horizontal_collision);
+        assert!(res.vertical_collision);
+    }
+}
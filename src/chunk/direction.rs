@@ -0,0 +1,91 @@
+use crate::block::{BlockPos, PropValue};
+
+/// One of the six directions along the axes of the block grid.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(super) enum Direction {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    /// All directions in the order vanilla iterates them.
+    pub const ALL: [Self; 6] = [
+        Self::Down,
+        Self::Up,
+        Self::North,
+        Self::South,
+        Self::West,
+        Self::East,
+    ];
+
+    /// The horizontal directions in the order vanilla iterates them.
+    pub const HORIZONTAL: [Self; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub fn offset(self, pos: BlockPos) -> BlockPos {
+        let BlockPos { x, y, z } = pos;
+
+        match self {
+            Self::Down => BlockPos::new(x, y - 1, z),
+            Self::Up => BlockPos::new(x, y + 1, z),
+            Self::North => BlockPos::new(x, y, z - 1),
+            Self::South => BlockPos::new(x, y, z + 1),
+            Self::West => BlockPos::new(x - 1, y, z),
+            Self::East => BlockPos::new(x + 1, y, z),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Down => Self::Up,
+            Self::Up => Self::Down,
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+
+    /// Rotates a horizontal direction by 90 degrees clockwise when viewed from
+    /// above. Vertical directions are returned unchanged.
+    pub fn rotate_clockwise(self) -> Self {
+        match self {
+            Self::North => Self::East,
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+            dir => dir,
+        }
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !matches!(self, Self::Down | Self::Up)
+    }
+
+    /// Converts the value of a `facing` block property to a direction.
+    pub fn from_prop(value: PropValue) -> Option<Self> {
+        Some(match value {
+            PropValue::Down => Self::Down,
+            PropValue::Up => Self::Up,
+            PropValue::North => Self::North,
+            PropValue::South => Self::South,
+            PropValue::West => Self::West,
+            PropValue::East => Self::East,
+            _ => return None,
+        })
+    }
+
+    pub fn to_prop(self) -> PropValue {
+        match self {
+            Self::Down => PropValue::Down,
+            Self::Up => PropValue::Up,
+            Self::North => PropValue::North,
+            Self::South => PropValue::South,
+            Self::West => PropValue::West,
+            Self::East => PropValue::East,
+        }
+    }
+}
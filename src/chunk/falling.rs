@@ -0,0 +1,251 @@
+//! Blocks affected by gravity, such as sand and gravel.
+
+use crate::block::{BlockKind, BlockPos, BlockState};
+use crate::chunk::direction::Direction;
+use crate::chunk::{BlockBehavior, Chunks, TickPriority};
+use crate::config::Config;
+
+impl<C: Config> Chunks<C> {
+    /// Enables gravity for sand, gravel, concrete powder, anvils and dragon
+    /// eggs in this world.
+    ///
+    /// This registers a [`BlockBehavior`] for those kinds, replacing any
+    /// behaviors previously set for them. Two ticks after the block below one
+    /// of them is removed, the block is removed as well and starts falling.
+    /// Concrete powder next to water hardens into concrete.
+    ///
+    /// Falling blocks are spawned as [`FallingBlock`] entities every tick
+    /// after [`Config::update`]. `state` is called to create the state of each
+    /// new entity.
+    ///
+    /// [`FallingBlock`]: crate::entity::EntityKind::FallingBlock
+    pub fn enable_falling_blocks(
+        &mut self,
+        state: impl FnMut() -> C::EntityState + Send + Sync + 'static,
+    ) {
+        for kind in falling_kinds() {
+            self.set_behavior(kind, FallingBehavior);
+        }
+
+        self.falling_block_state = Some(Box::new(state));
+    }
+
+    /// Disables gravity for blocks enabled with
+    /// [`enable_falling_blocks`](Self::enable_falling_blocks).
+    pub fn disable_falling_blocks(&mut self) {
+        for kind in falling_kinds() {
+            self.clear_behavior(kind);
+        }
+
+        self.falling_block_state = None;
+    }
+
+    /// Removes the blocks that started falling and returns their positions
+    /// and states, along with the state of the entity to spawn for each.
+    pub(crate) fn take_falling_blocks(&mut self) -> Vec<(BlockPos, BlockState, C::EntityState)> {
+        let blocks = std::mem::take(&mut self.falling_blocks);
+
+        match &mut self.falling_block_state {
+            Some(state) => blocks
+                .into_iter()
+                .map(|(pos, block)| (pos, block, state()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Places a falling block that landed at `pos`. Returns `false` if the
+    /// space is occupied, in which case the block is destroyed.
+    pub(crate) fn land_falling_block(&mut self, pos: BlockPos, block: BlockState) -> bool {
+        if !self.get_block_state(pos).is_some_and(can_fall_through) {
+            return false;
+        }
+
+        let block = if hardens_at(self, pos, block, true) {
+            concrete_of(block)
+        } else {
+            block
+        };
+
+        self.set_block_state(pos, block)
+    }
+}
+
+fn falling_kinds() -> impl Iterator<Item = BlockKind> {
+    BlockKind::ALL.into_iter().filter(|&kind| {
+        matches!(
+            kind,
+            BlockKind::Sand
+                | BlockKind::RedSand
+                | BlockKind::Gravel
+                | BlockKind::Anvil
+                | BlockKind::ChippedAnvil
+                | BlockKind::DamagedAnvil
+                | BlockKind::DragonEgg
+        ) || is_concrete_powder(kind)
+    })
+}
+
+/// Returns `true` if `kind` is one of the concrete powders.
+pub(crate) fn is_concrete_powder(kind: BlockKind) -> bool {
+    matches!(
+        kind,
+        BlockKind::WhiteConcretePowder
+            | BlockKind::OrangeConcretePowder
+            | BlockKind::MagentaConcretePowder
+            | BlockKind::LightBlueConcretePowder
+            | BlockKind::YellowConcretePowder
+            | BlockKind::LimeConcretePowder
+            | BlockKind::PinkConcretePowder
+            | BlockKind::GrayConcretePowder
+            | BlockKind::LightGrayConcretePowder
+            | BlockKind::CyanConcretePowder
+            | BlockKind::PurpleConcretePowder
+            | BlockKind::BlueConcretePowder
+            | BlockKind::BrownConcretePowder
+            | BlockKind::GreenConcretePowder
+            | BlockKind::RedConcretePowder
+            | BlockKind::BlackConcretePowder
+    )
+}
+
+/// Returns the concrete that concrete powder hardens into.
+fn concrete_of(block: BlockState) -> BlockState {
+    let concrete = match block.to_kind() {
+        BlockKind::WhiteConcretePowder => BlockKind::WhiteConcrete,
+        BlockKind::OrangeConcretePowder => BlockKind::OrangeConcrete,
+        BlockKind::MagentaConcretePowder => BlockKind::MagentaConcrete,
+        BlockKind::LightBlueConcretePowder => BlockKind::LightBlueConcrete,
+        BlockKind::YellowConcretePowder => BlockKind::YellowConcrete,
+        BlockKind::LimeConcretePowder => BlockKind::LimeConcrete,
+        BlockKind::PinkConcretePowder => BlockKind::PinkConcrete,
+        BlockKind::GrayConcretePowder => BlockKind::GrayConcrete,
+        BlockKind::LightGrayConcretePowder => BlockKind::LightGrayConcrete,
+        BlockKind::CyanConcretePowder => BlockKind::CyanConcrete,
+        BlockKind::PurpleConcretePowder => BlockKind::PurpleConcrete,
+        BlockKind::BlueConcretePowder => BlockKind::BlueConcrete,
+        BlockKind::BrownConcretePowder => BlockKind::BrownConcrete,
+        BlockKind::GreenConcretePowder => BlockKind::GreenConcrete,
+        BlockKind::RedConcretePowder => BlockKind::RedConcrete,
+        BlockKind::BlackConcretePowder => BlockKind::BlackConcrete,
+        _ => return block,
+    };
+
+    concrete.to_state()
+}
+
+/// Returns `true` if falling blocks can fall through or land in the block.
+fn can_fall_through(block: BlockState) -> bool {
+    block.is_air()
+        || block.is_liquid()
+        || matches!(
+            block.to_kind(),
+            BlockKind::Fire
+                | BlockKind::SoulFire
+                | BlockKind::Grass
+                | BlockKind::Fern
+                | BlockKind::TallGrass
+                | BlockKind::LargeFern
+                | BlockKind::DeadBush
+                | BlockKind::Vine
+                | BlockKind::GlowLichen
+                | BlockKind::Snow
+                | BlockKind::Seagrass
+                | BlockKind::TallSeagrass
+                | BlockKind::NetherSprouts
+                | BlockKind::CrimsonRoots
+                | BlockKind::WarpedRoots
+                | BlockKind::HangingRoots
+                | BlockKind::StructureVoid
+                | BlockKind::BubbleColumn
+        )
+}
+
+/// Returns `true` if concrete powder at `pos` touches water. When `landing`
+/// is `true`, water at `pos` itself also counts.
+fn hardens_at<C: Config>(
+    chunks: &Chunks<C>,
+    pos: BlockPos,
+    block: BlockState,
+    landing: bool,
+) -> bool {
+    if !is_concrete_powder(block.to_kind()) {
+        return false;
+    }
+
+    let is_water = |pos| {
+        chunks
+            .get_block_state(pos)
+            .is_some_and(|b| b.to_kind() == BlockKind::Water)
+    };
+
+    (landing && is_water(pos))
+        || Direction::ALL
+            .into_iter()
+            .filter(|&dir| dir != Direction::Down)
+            .any(|dir| is_water(dir.offset(pos)))
+}
+
+struct FallingBehavior;
+
+impl<C: Config> BlockBehavior<C> for FallingBehavior {
+    fn state_changed(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        self.update(chunks, pos, block);
+    }
+
+    fn neighbor_update(
+        &self,
+        chunks: &mut Chunks<C>,
+        pos: BlockPos,
+        block: BlockState,
+        _neighbor: BlockPos,
+    ) {
+        self.update(chunks, pos, block);
+    }
+
+    fn scheduled_tick(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        let below = chunks.get_block_state(Direction::Down.offset(pos));
+
+        if below.is_some_and(can_fall_through) {
+            chunks.set_block_state(pos, BlockState::AIR);
+            chunks.falling_blocks.push((pos, block));
+        }
+    }
+}
+
+impl FallingBehavior {
+    fn update<C: Config>(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        if hardens_at(chunks, pos, block, false) {
+            chunks.set_block_state(pos, concrete_of(block));
+        } else {
+            chunks.schedule_tick(pos, 2, TickPriority::Normal);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concrete_powder_hardens() {
+        assert_eq!(
+            concrete_of(BlockState::LIGHT_BLUE_CONCRETE_POWDER),
+            BlockState::LIGHT_BLUE_CONCRETE
+        );
+        assert_eq!(concrete_of(BlockState::SAND), BlockState::SAND);
+
+        assert!(falling_kinds().any(|k| k == BlockKind::BlackConcretePowder));
+        assert!(!falling_kinds().any(|k| k == BlockKind::BlackConcrete));
+
+        let powders = BlockKind::ALL
+            .into_iter()
+            .filter(|&k| is_concrete_powder(k));
+        assert_eq!(powders.clone().count(), 16);
+
+        for powder in powders {
+            let concrete = concrete_of(powder.to_state()).to_kind();
+            assert_eq!(concrete.to_str(), powder.to_str().replace("_powder", ""));
+        }
+    }
+}
@@ -0,0 +1,805 @@
+//! Water and lava flow, following the behavior of vanilla's `FlowableFluid`.
+
+use std::collections::HashMap;
+
+use crate::block::{BlockKind, BlockPos, BlockState, PropName, PropValue};
+use crate::chunk::direction::Direction;
+use crate::chunk::{BlockBehavior, Chunks, TickPriority};
+use crate::config::Config;
+use crate::Ticks;
+
+impl<C: Config> Chunks<C> {
+    /// Enables the simulation of flowing water and lava in this world.
+    ///
+    /// This registers a [`BlockBehavior`] for water, lava and every block that
+    /// can be waterlogged, replacing any behaviors previously set for those
+    /// kinds. Fluids spread, fall and form new water sources in the same way
+    /// as in vanilla. Lava touching water turns into obsidian, cobblestone or
+    /// stone. In [ultrawarm] dimensions, lava flows faster and further.
+    ///
+    /// Only fluids that are placed or updated after this is called start
+    /// flowing. See [Block Updates](Self#block-updates) for more information.
+    ///
+    /// [ultrawarm]: crate::dimension::Dimension::ultrawarm
+    pub fn enable_fluids(&mut self) {
+        let ultrawarm = self.shared.dimension(self.dimension).ultrawarm;
+
+        for kind in fluid_kinds() {
+            self.set_behavior(kind, FluidBehavior { ultrawarm });
+        }
+    }
+
+    /// Stops the simulation of flowing water and lava enabled with
+    /// [`enable_fluids`](Self::enable_fluids).
+    pub fn disable_fluids(&mut self) {
+        for kind in fluid_kinds() {
+            self.clear_behavior(kind);
+        }
+    }
+}
+
+/// Returns the block kinds that can contain a fluid.
+fn fluid_kinds() -> impl Iterator<Item = BlockKind> {
+    BlockKind::ALL.into_iter().filter(|&kind| {
+        matches!(kind, BlockKind::Water | BlockKind::Lava)
+            || is_water_plant(kind)
+            || kind.props().contains(&PropName::Waterlogged)
+    })
+}
+
+fn is_water_plant(kind: BlockKind) -> bool {
+    matches!(
+        kind,
+        BlockKind::Seagrass
+            | BlockKind::TallSeagrass
+            | BlockKind::Kelp
+            | BlockKind::KelpPlant
+            | BlockKind::BubbleColumn
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum FluidKind {
+    Water,
+    Lava,
+}
+
+/// The fluid contained in a block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Fluid {
+    kind: FluidKind,
+    /// The amount of fluid from 1 to 8.
+    amount: u8,
+    /// If this is a source block.
+    still: bool,
+    falling: bool,
+}
+
+impl Fluid {
+    fn still(kind: FluidKind) -> Self {
+        Self {
+            kind,
+            amount: 8,
+            still: true,
+            falling: false,
+        }
+    }
+
+    fn flowing(kind: FluidKind, amount: u8, falling: bool) -> Self {
+        Self {
+            kind,
+            amount,
+            still: false,
+            falling,
+        }
+    }
+
+    /// Returns the fluid contained in `block`, if any.
+    fn of(block: BlockState) -> Option<Self> {
+        let kind = match block.to_kind() {
+            BlockKind::Water => FluidKind::Water,
+            BlockKind::Lava => FluidKind::Lava,
+            kind if is_water_plant(kind) => return Some(Self::still(FluidKind::Water)),
+            _ => {
+                return (block.get(PropName::Waterlogged) == Some(PropValue::True))
+                    .then(|| Self::still(FluidKind::Water))
+            }
+        };
+
+        let level = block
+            .get(PropName::Level)
+            .and_then(PropValue::to_u16)
+            .unwrap_or(0) as u8;
+
+        Some(match level {
+            0 => Self::still(kind),
+            1..=7 => Self::flowing(kind, 8 - level, false),
+            _ => Self::flowing(kind, 8, true),
+        })
+    }
+
+    /// Returns the water or lava block containing this fluid.
+    fn to_block(self) -> BlockState {
+        let level = if self.still {
+            0
+        } else {
+            8 - self.amount.min(8) as u16 + if self.falling { 8 } else { 0 }
+        };
+
+        let block = match self.kind {
+            FluidKind::Water => BlockState::WATER,
+            FluidKind::Lava => BlockState::LAVA,
+        };
+
+        block.set(PropName::Level, PropValue::from_u16(level).unwrap())
+    }
+}
+
+/// Read and write access to blocks. This is implemented for [`Chunks`] and
+/// allows the flow logic to be tested in isolation.
+trait FluidWorld {
+    fn block(&self, pos: BlockPos) -> Option<BlockState>;
+
+    fn set_block(&mut self, pos: BlockPos, block: BlockState);
+
+    fn fluid(&self, pos: BlockPos) -> Option<Fluid> {
+        self.block(pos).and_then(Fluid::of)
+    }
+}
+
+impl<C: Config> FluidWorld for Chunks<C> {
+    fn block(&self, pos: BlockPos) -> Option<BlockState> {
+        self.get_block_state(pos)
+    }
+
+    fn set_block(&mut self, pos: BlockPos, block: BlockState) {
+        self.set_block_state(pos, block);
+    }
+}
+
+struct FluidBehavior {
+    ultrawarm: bool,
+}
+
+impl<C: Config> BlockBehavior<C> for FluidBehavior {
+    fn state_changed(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        self.schedule_if_needed(chunks, pos, block);
+    }
+
+    fn neighbor_update(
+        &self,
+        chunks: &mut Chunks<C>,
+        pos: BlockPos,
+        block: BlockState,
+        _neighbor: BlockPos,
+    ) {
+        self.schedule_if_needed(chunks, pos, block);
+    }
+
+    fn scheduled_tick(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        let Some(fluid) = Fluid::of(block) else {
+            return;
+        };
+
+        let rules = self.rules(fluid.kind);
+
+        if let Some((fluid, delay)) = rules.update(chunks, pos, fluid) {
+            if let Some(delay) = delay {
+                chunks.schedule_tick(pos, delay, TickPriority::Normal);
+            }
+            rules.try_flow(chunks, pos, fluid);
+        }
+    }
+}
+
+impl FluidBehavior {
+    fn rules(&self, kind: FluidKind) -> Rules {
+        Rules {
+            kind,
+            ultrawarm: self.ultrawarm,
+        }
+    }
+
+    fn schedule_if_needed<C: Config>(
+        &self,
+        chunks: &mut Chunks<C>,
+        pos: BlockPos,
+        block: BlockState,
+    ) {
+        let Some(fluid) = Fluid::of(block) else {
+            return;
+        };
+
+        let rules = self.rules(fluid.kind);
+
+        if rules.receive_neighbor_fluids(chunks, pos, fluid) {
+            chunks.schedule_tick(pos, rules.tick_delay(), TickPriority::Normal);
+        }
+    }
+}
+
+/// The flow rules for one kind of fluid.
+struct Rules {
+    kind: FluidKind,
+    ultrawarm: bool,
+}
+
+impl Rules {
+    fn tick_delay(&self) -> Ticks {
+        match (self.kind, self.ultrawarm) {
+            (FluidKind::Water, _) => 5,
+            (FluidKind::Lava, false) => 30,
+            (FluidKind::Lava, true) => 10,
+        }
+    }
+
+    fn level_decrease(&self) -> u8 {
+        match (self.kind, self.ultrawarm) {
+            (FluidKind::Water, _) | (FluidKind::Lava, true) => 1,
+            (FluidKind::Lava, false) => 2,
+        }
+    }
+
+    /// The maximum distance searched for a hole to flow towards.
+    fn slope_distance(&self) -> u8 {
+        match (self.kind, self.ultrawarm) {
+            (FluidKind::Water, _) | (FluidKind::Lava, true) => 4,
+            (FluidKind::Lava, false) => 2,
+        }
+    }
+
+    fn is_infinite(&self) -> bool {
+        self.kind == FluidKind::Water
+    }
+
+    fn flowing(&self) -> Fluid {
+        Fluid::flowing(self.kind, 1, false)
+    }
+
+    fn next_tick_delay(&self, old: Fluid, new: Fluid) -> Ticks {
+        let delay = self.tick_delay();
+
+        if self.kind == FluidKind::Lava
+            && !old.falling
+            && !new.falling
+            && new.amount > old.amount
+            && !rand::random::<u8>().is_multiple_of(4)
+        {
+            delay * 4
+        } else {
+            delay
+        }
+    }
+
+    /// Recomputes the flowing fluid at `pos` from its surroundings. Returns the
+    /// new fluid and the delay of the next tick to schedule, or `None` if the
+    /// fluid disappeared.
+    fn update(
+        &self,
+        world: &mut impl FluidWorld,
+        pos: BlockPos,
+        fluid: Fluid,
+    ) -> Option<(Fluid, Option<Ticks>)> {
+        if fluid.still {
+            return Some((fluid, None));
+        }
+
+        match self.updated_state(world, pos) {
+            None => {
+                world.set_block(pos, BlockState::AIR);
+                None
+            }
+            Some(new) if new != fluid => {
+                world.set_block(pos, new.to_block());
+                Some((new, Some(self.next_tick_delay(fluid, new))))
+            }
+            Some(_) => Some((fluid, None)),
+        }
+    }
+
+    /// Computes the fluid that should be at `pos` based on the fluids around
+    /// it.
+    fn updated_state(&self, world: &impl FluidWorld, pos: BlockPos) -> Option<Fluid> {
+        let mut max_amount = 0;
+        let mut sources = 0;
+
+        for dir in Direction::HORIZONTAL {
+            if let Some(neighbor) = world.fluid(dir.offset(pos)) {
+                if neighbor.kind == self.kind {
+                    if neighbor.still {
+                        sources += 1;
+                    }
+                    max_amount = max_amount.max(neighbor.amount);
+                }
+            }
+        }
+
+        if self.is_infinite() && sources >= 2 {
+            let below = Direction::Down.offset(pos);
+
+            if world.block(below).is_some_and(is_solid) || self.is_matching_and_still(world, below)
+            {
+                return Some(Fluid::still(self.kind));
+            }
+        }
+
+        let above = BlockPos::new(pos.x, pos.y + 1, pos.z);
+        if world.fluid(above).is_some_and(|f| f.kind == self.kind) {
+            return Some(Fluid::flowing(self.kind, 8, true));
+        }
+
+        let amount = max_amount.checked_sub(self.level_decrease())?;
+        (amount > 0).then(|| Fluid::flowing(self.kind, amount, false))
+    }
+
+    fn is_matching_and_still(&self, world: &impl FluidWorld, pos: BlockPos) -> bool {
+        world
+            .fluid(pos)
+            .is_some_and(|f| f.kind == self.kind && f.still)
+    }
+
+    /// Lets the fluid at `pos` flow down or to the sides.
+    fn try_flow(&self, world: &mut impl FluidWorld, pos: BlockPos, fluid: Fluid) {
+        let down = Direction::Down.offset(pos);
+        let down_block = world.block(down);
+
+        let updated = self.updated_state(world, down);
+
+        if let Some(updated) = updated.filter(|&u| self.can_flow(world, Direction::Down, down, u)) {
+            self.flow(world, down, Direction::Down, updated);
+
+            if self.count_neighboring_sources(world, pos) >= 3 {
+                self.flow_to_sides(world, pos, fluid);
+            }
+        } else if fluid.still || !self.can_flow_down_to(world, down, down_block, self.flowing()) {
+            self.flow_to_sides(world, pos, fluid);
+        }
+    }
+
+    fn count_neighboring_sources(&self, world: &impl FluidWorld, pos: BlockPos) -> usize {
+        Direction::HORIZONTAL
+            .into_iter()
+            .filter(|dir| self.is_matching_and_still(world, dir.offset(pos)))
+            .count()
+    }
+
+    fn flow_to_sides(&self, world: &mut impl FluidWorld, pos: BlockPos, fluid: Fluid) {
+        let amount = if fluid.falling {
+            7
+        } else {
+            fluid.amount.saturating_sub(self.level_decrease())
+        };
+
+        if amount == 0 {
+            return;
+        }
+
+        for (dir, new) in self.spread(world, pos) {
+            let to = dir.offset(pos);
+            if self.can_flow(world, dir, to, new) {
+                self.flow(world, to, dir, new);
+            }
+        }
+    }
+
+    /// Returns the directions the fluid at `pos` should spread in, which are
+    /// the directions with the shortest path to a hole, and the fluid that
+    /// would be placed there.
+    fn spread(&self, world: &impl FluidWorld, pos: BlockPos) -> Vec<(Direction, Fluid)> {
+        let mut res = Vec::new();
+        let mut shortest = u8::MAX;
+
+        // Avoids checking the same positions repeatedly in the path search.
+        let mut cache = HashMap::new();
+
+        for dir in Direction::HORIZONTAL {
+            let to = dir.offset(pos);
+
+            if !self.can_flow_through(world, to, self.flowing()) {
+                continue;
+            }
+
+            let Some(new) = self.updated_state(world, to) else {
+                continue;
+            };
+
+            if !self.can_flow_through(world, to, new) {
+                continue;
+            }
+
+            let down = Direction::Down.offset(to);
+            let distance = if self.can_flow_down_to(world, down, world.block(down), self.flowing())
+            {
+                0
+            } else {
+                self.distance_to_hole(world, to, 1, Some(dir.opposite()), &mut cache)
+            };
+
+            if distance < shortest {
+                res.clear();
+            }
+
+            if distance <= shortest {
+                res.push((dir, new));
+                shortest = distance;
+            }
+        }
+
+        res
+    }
+
+    /// Searches for the closest position the fluid can flow down from,
+    /// starting at `pos`. Returns `u8::MAX` if none is found within the slope
+    /// distance.
+    fn distance_to_hole(
+        &self,
+        world: &impl FluidWorld,
+        pos: BlockPos,
+        distance: u8,
+        from: Option<Direction>,
+        cache: &mut HashMap<BlockPos, bool>,
+    ) -> u8 {
+        let mut shortest = u8::MAX;
+
+        for dir in Direction::HORIZONTAL {
+            if Some(dir) == from {
+                continue;
+            }
+
+            let to = dir.offset(pos);
+
+            if !self.can_flow_through(world, to, self.flowing()) {
+                continue;
+            }
+
+            let is_hole = *cache.entry(to).or_insert_with(|| {
+                let down = Direction::Down.offset(to);
+                self.can_flow_down_to(world, down, world.block(down), self.flowing())
+            });
+
+            if is_hole {
+                return distance;
+            }
+
+            if distance < self.slope_distance() {
+                let d = self.distance_to_hole(world, to, distance + 1, Some(dir.opposite()), cache);
+                shortest = shortest.min(d);
+            }
+        }
+
+        shortest
+    }
+
+    fn can_flow_through(&self, world: &impl FluidWorld, to: BlockPos, fluid: Fluid) -> bool {
+        !self.is_matching_and_still(world, to) && can_fill(world.block(to), fluid)
+    }
+
+    fn can_flow_down_to(
+        &self,
+        world: &impl FluidWorld,
+        down: BlockPos,
+        down_block: Option<BlockState>,
+        fluid: Fluid,
+    ) -> bool {
+        world.fluid(down).is_some_and(|f| f.kind == self.kind) || can_fill(down_block, fluid)
+    }
+
+    fn can_flow(
+        &self,
+        world: &impl FluidWorld,
+        dir: Direction,
+        to: BlockPos,
+        fluid: Fluid,
+    ) -> bool {
+        let replaceable = match world.fluid(to) {
+            None => true,
+            Some(existing) => match existing.kind {
+                FluidKind::Water => dir == Direction::Down && fluid.kind != FluidKind::Water,
+                // Lava at least 4/9 blocks high.
+                FluidKind::Lava => existing.amount >= 4 && fluid.kind == FluidKind::Water,
+            },
+        };
+
+        replaceable && can_fill(world.block(to), fluid)
+    }
+
+    /// Places `fluid` at `pos`.
+    fn flow(&self, world: &mut impl FluidWorld, pos: BlockPos, dir: Direction, fluid: Fluid) {
+        let Some(block) = world.block(pos) else {
+            return;
+        };
+
+        if self.kind == FluidKind::Lava
+            && dir == Direction::Down
+            && world.fluid(pos).is_some_and(|f| f.kind == FluidKind::Water)
+        {
+            if block.to_kind() == BlockKind::Water {
+                world.set_block(pos, BlockState::STONE);
+            }
+            return;
+        }
+
+        if block.to_kind().props().contains(&PropName::Waterlogged) {
+            world.set_block(pos, block.set(PropName::Waterlogged, PropValue::True));
+        } else {
+            world.set_block(pos, fluid.to_block());
+        }
+    }
+
+    /// Turns lava that touches water into obsidian or cobblestone, and lava
+    /// above soul soil that touches blue ice into basalt. Returns `false` if
+    /// the lava was replaced.
+    fn receive_neighbor_fluids(
+        &self,
+        world: &mut impl FluidWorld,
+        pos: BlockPos,
+        fluid: Fluid,
+    ) -> bool {
+        if self.kind != FluidKind::Lava {
+            return true;
+        }
+
+        let above_soul_soil = world
+            .block(Direction::Down.offset(pos))
+            .is_some_and(|b| b.to_kind() == BlockKind::SoulSoil);
+
+        let BlockPos { x, y, z } = pos;
+        let neighbors = [
+            BlockPos::new(x, y + 1, z),
+            BlockPos::new(x, y, z - 1),
+            BlockPos::new(x, y, z + 1),
+            BlockPos::new(x - 1, y, z),
+            BlockPos::new(x + 1, y, z),
+        ];
+
+        for neighbor in neighbors {
+            if world
+                .fluid(neighbor)
+                .is_some_and(|f| f.kind == FluidKind::Water)
+            {
+                let block = if fluid.still {
+                    BlockState::OBSIDIAN
+                } else {
+                    BlockState::COBBLESTONE
+                };
+                world.set_block(pos, block);
+                return false;
+            }
+
+            if above_soul_soil
+                && world
+                    .block(neighbor)
+                    .is_some_and(|b| b.to_kind() == BlockKind::BlueIce)
+            {
+                world.set_block(pos, BlockState::BASALT);
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Returns `true` if the block counts as solid ground for forming new water
+/// sources.
+fn is_solid(block: BlockState) -> bool {
+    block.collision_shapes().len() > 0
+}
+
+/// Returns `true` if `fluid` can be placed into `block`.
+fn can_fill(block: Option<BlockState>, fluid: Fluid) -> bool {
+    let Some(block) = block else {
+        // Fluids do not flow into unloaded chunks.
+        return false;
+    };
+
+    let kind = block.to_kind();
+
+    if kind.props().contains(&PropName::Waterlogged) {
+        // Only water sources can waterlog blocks.
+        return fluid.kind == FluidKind::Water
+            && fluid.still
+            && block.get(PropName::Waterlogged) == Some(PropValue::False);
+    }
+
+    if is_water_plant(kind)
+        || matches!(
+            kind,
+            BlockKind::Ladder
+                | BlockKind::SugarCane
+                | BlockKind::NetherPortal
+                | BlockKind::EndPortal
+                | BlockKind::EndGateway
+                | BlockKind::StructureVoid
+                | BlockKind::OakDoor
+                | BlockKind::IronDoor
+                | BlockKind::SpruceDoor
+                | BlockKind::BirchDoor
+                | BlockKind::JungleDoor
+                | BlockKind::AcaciaDoor
+                | BlockKind::DarkOakDoor
+                | BlockKind::MangroveDoor
+                | BlockKind::CrimsonDoor
+                | BlockKind::WarpedDoor
+                | BlockKind::OakSign
+                | BlockKind::SpruceSign
+                | BlockKind::BirchSign
+                | BlockKind::AcaciaSign
+                | BlockKind::JungleSign
+                | BlockKind::DarkOakSign
+                | BlockKind::MangroveSign
+                | BlockKind::CrimsonSign
+                | BlockKind::WarpedSign
+                | BlockKind::OakWallSign
+                | BlockKind::SpruceWallSign
+                | BlockKind::BirchWallSign
+                | BlockKind::AcaciaWallSign
+                | BlockKind::JungleWallSign
+                | BlockKind::DarkOakWallSign
+                | BlockKind::MangroveWallSign
+                | BlockKind::CrimsonWallSign
+                | BlockKind::WarpedWallSign
+        )
+    {
+        return false;
+    }
+
+    block.collision_shapes().len() == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestWorld(HashMap<BlockPos, BlockState>);
+
+    impl FluidWorld for TestWorld {
+        fn block(&self, pos: BlockPos) -> Option<BlockState> {
+            Some(if pos.y < 0 {
+                BlockState::STONE
+            } else {
+                self.0.get(&pos).copied().unwrap_or(BlockState::AIR)
+            })
+        }
+
+        fn set_block(&mut self, pos: BlockPos, block: BlockState) {
+            self.0.insert(pos, block);
+        }
+    }
+
+    const WATER: Rules = Rules {
+        kind: FluidKind::Water,
+        ultrawarm: false,
+    };
+
+    const LAVA: Rules = Rules {
+        kind: FluidKind::Lava,
+        ultrawarm: false,
+    };
+
+    #[test]
+    fn fluid_block_round_trip() {
+        for level in 0..16 {
+            let block = BlockState::WATER.set(PropName::Level, PropValue::from_u16(level).unwrap());
+            let fluid = Fluid::of(block).unwrap();
+
+            if level < 8 {
+                assert_eq!(fluid.to_block(), block);
+            } else {
+                assert!(fluid.falling);
+            }
+        }
+
+        let stairs = BlockState::OAK_STAIRS.set(PropName::Waterlogged, PropValue::True);
+        assert_eq!(Fluid::of(stairs), Some(Fluid::still(FluidKind::Water)));
+        assert_eq!(Fluid::of(BlockState::OAK_STAIRS), None);
+    }
+
+    #[test]
+    fn fluids_cannot_fill_doors() {
+        let water = Fluid::still(FluidKind::Water);
+
+        assert!(can_fill(Some(BlockState::AIR), water));
+        assert!(!can_fill(Some(BlockState::OAK_DOOR), water));
+        assert!(!can_fill(Some(BlockState::IRON_DOOR), water));
+        assert!(!can_fill(Some(BlockState::NETHER_PORTAL), water));
+        assert!(!can_fill(None, water));
+    }
+
+    #[test]
+    fn water_spreads_and_forms_sources() {
+        let mut world = TestWorld::default();
+        let origin = BlockPos::new(0, 0, 0);
+        world.set_block(origin, BlockState::WATER);
+
+        let east = BlockPos::new(1, 0, 0);
+        assert_eq!(
+            WATER.updated_state(&world, east),
+            Some(Fluid::flowing(FluidKind::Water, 7, false))
+        );
+
+        // A source forms between two sources on solid ground.
+        world.set_block(BlockPos::new(2, 0, 0), BlockState::WATER);
+        assert_eq!(
+            WATER.updated_state(&world, east),
+            Some(Fluid::still(FluidKind::Water))
+        );
+
+        // Lava does not form new sources.
+        world.set_block(origin, BlockState::LAVA);
+        world.set_block(BlockPos::new(2, 0, 0), BlockState::LAVA);
+        assert_eq!(
+            LAVA.updated_state(&world, east),
+            Some(Fluid::flowing(FluidKind::Lava, 6, false))
+        );
+
+        // Fluid below another fluid is falling.
+        let below = BlockPos::new(5, 0, 0);
+        world.set_block(BlockPos::new(5, 1, 0), BlockState::WATER);
+        assert_eq!(
+            WATER.updated_state(&world, below),
+            Some(Fluid::flowing(FluidKind::Water, 8, true))
+        );
+    }
+
+    #[test]
+    fn water_flows_towards_holes() {
+        let mut world = TestWorld::default();
+
+        // A floor at y = 1 with a hole two blocks west of the water.
+        for x in -5..=5 {
+            for z in -5..=5 {
+                world.set_block(BlockPos::new(x, 1, z), BlockState::STONE);
+            }
+        }
+        world.set_block(BlockPos::new(-2, 1, 0), BlockState::AIR);
+
+        let pos = BlockPos::new(0, 2, 0);
+        world.set_block(pos, BlockState::WATER);
+
+        let spread = WATER.spread(&world, pos);
+        assert_eq!(spread.len(), 1);
+        assert_eq!(spread[0].0, Direction::West);
+
+        WATER.try_flow(&mut world, pos, Fluid::still(FluidKind::Water));
+        assert_eq!(
+            world.fluid(BlockPos::new(-1, 2, 0)),
+            Some(Fluid::flowing(FluidKind::Water, 7, false))
+        );
+        assert_eq!(world.fluid(BlockPos::new(1, 2, 0)), None);
+
+        // Without a hole, water spreads in every direction.
+        world.set_block(BlockPos::new(-2, 1, 0), BlockState::STONE);
+        assert_eq!(WATER.spread(&world, pos).len(), 4);
+    }
+
+    #[test]
+    fn lava_meets_water() {
+        let mut world = TestWorld::default();
+
+        let source = BlockPos::new(0, 0, 0);
+        world.set_block(source, BlockState::LAVA);
+        world.set_block(BlockPos::new(1, 0, 0), BlockState::WATER);
+        assert!(!LAVA.receive_neighbor_fluids(&mut world, source, Fluid::still(FluidKind::Lava)));
+        assert_eq!(world.block(source), Some(BlockState::OBSIDIAN));
+
+        let flowing = BlockPos::new(0, 0, 5);
+        let fluid = Fluid::flowing(FluidKind::Lava, 6, false);
+        world.set_block(flowing, fluid.to_block());
+        world.set_block(BlockPos::new(0, 1, 5), BlockState::WATER);
+        assert!(!LAVA.receive_neighbor_fluids(&mut world, flowing, fluid));
+        assert_eq!(world.block(flowing), Some(BlockState::COBBLESTONE));
+
+        // Lava flowing down into water turns it into stone.
+        let water = BlockPos::new(0, 0, 10);
+        world.set_block(water, BlockState::WATER);
+        LAVA.flow(
+            &mut world,
+            water,
+            Direction::Down,
+            Fluid::flowing(FluidKind::Lava, 8, true),
+        );
+        assert_eq!(world.block(water), Some(BlockState::STONE));
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use flume::{Receiver, Sender};
+
+use crate::chunk::tickets::Tickets;
+use crate::chunk::{Chunk, ChunkPos, UnloadedChunk};
+use crate::config::Config;
+use crate::server::SharedServer;
+
+/// Generates or loads the chunks of a world on demand.
+///
+/// A provider is attached to a world with
+/// [`Chunks::set_provider`](crate::chunk::Chunks::set_provider). Every tick,
+/// ticketed chunks that are not loaded yet are requested from the provider,
+/// starting with the chunks closest to clients. Requests are handled on a pool
+/// of worker threads (see [`Config::chunk_worker_threads`]) and the finished
+/// chunks are inserted into the world at the start of a later tick.
+///
+/// Chunks without a ticket are removed and passed to
+/// [`unload`](Self::unload). See [`Chunks`](crate::chunk::Chunks) for more
+/// information about tickets.
+#[allow(unused_variables)]
+pub trait ChunkProvider<C: Config>: Send + Sync + 'static {
+    /// Generates or loads the chunk at `pos` and returns its `ChunkState`.
+    ///
+    /// `chunk` is initially filled with air and has the height of the world's
+    /// dimension.
+    ///
+    /// This is called from a worker thread, so it is acceptable to block here.
+    /// If this panics, the panic is logged and the chunk is not requested
+    /// again until all of its tickets are removed and added back.
+    fn provide(&self, pos: ChunkPos, chunk: &mut UnloadedChunk) -> C::ChunkState;
+
+    /// Called after a chunk was unloaded because it had no tickets. This is the
+    /// place to save the chunk if needed.
+    ///
+    /// Chunks removed manually with
+    /// [`Chunks::remove`](crate::chunk::Chunks::remove) are not passed to this
+    /// method.
+    ///
+    /// # Default Implementation
+    ///
+    /// The chunk is dropped.
+    fn unload(&self, pos: ChunkPos, chunk: Chunk<C>) {}
+}
+
+pub(super) struct ProviderState<C: Config> {
+    provider: Arc<dyn ChunkProvider<C>>,
+    /// Chunks that were requested but have not been received yet.
+    pending: HashSet<ChunkPos>,
+    /// Chunks the provider panicked on, which are not requested again while
+    /// they stay ticketed.
+    failed: HashSet<ChunkPos>,
+    finished_tx: Sender<Finished<C>>,
+    finished_rx: Receiver<Finished<C>>,
+}
+
+/// A requested chunk, or `None` if the provider panicked.
+type Finished<C> = (ChunkPos, Option<(UnloadedChunk, <C as Config>::ChunkState)>);
+
+impl<C: Config> ProviderState<C> {
+    pub fn new(provider: Arc<dyn ChunkProvider<C>>) -> Self {
+        let (finished_tx, finished_rx) = flume::unbounded();
+
+        Self {
+            provider,
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            finished_tx,
+            finished_rx,
+        }
+    }
+
+    /// Inserts the chunks that finished since the last tick. Chunks that are
+    /// no longer ticketed are discarded.
+    pub fn receive(&mut self, chunks: &mut HashMap<ChunkPos, Chunk<C>>, tickets: &Tickets) {
+        for (pos, res) in self.finished_rx.try_iter() {
+            if !self.pending.remove(&pos) || !tickets.contains(pos) {
+                continue;
+            }
+
+            match res {
+                Some((chunk, state)) => {
+                    chunks.insert(pos, Chunk::from_sections(chunk.sections.into(), state));
+                }
+                None => {
+                    self.failed.insert(pos);
+                }
+            }
+        }
+    }
+
+    /// Requests all ticketed chunks that are not loaded or pending.
+    pub fn request(
+        &mut self,
+        chunks: &HashMap<ChunkPos, Chunk<C>>,
+        tickets: &Tickets,
+        shared: &SharedServer<C>,
+        height: usize,
+    ) {
+        self.pending.retain(|&pos| tickets.contains(pos));
+        self.failed.retain(|&pos| tickets.contains(pos));
+
+        for pos in tickets.wanted() {
+            if chunks.contains_key(&pos) || self.failed.contains(&pos) || !self.pending.insert(pos)
+            {
+                continue;
+            }
+
+            let provider = self.provider.clone();
+            let finished_tx = self.finished_tx.clone();
+
+            shared.chunk_workers().spawn(move || {
+                let res = provide(&*provider, pos, height);
+
+                // The receiver is gone if the provider was replaced or the world was
+                // deleted.
+                let _ = finished_tx.send((pos, res));
+            });
+        }
+    }
+
+    pub fn unload(&self, pos: ChunkPos, chunk: Chunk<C>) {
+        self.provider.unload(pos, chunk);
+    }
+}
+
+/// Runs the provider for the chunk at `pos`. Returns `None` if the provider
+/// panicked.
+fn provide<C: Config>(
+    provider: &dyn ChunkProvider<C>,
+    pos: ChunkPos,
+    height: usize,
+) -> Option<(UnloadedChunk, C::ChunkState)> {
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut chunk = UnloadedChunk::new(height);
+        let state = provider.provide(pos, &mut chunk);
+        (chunk, state)
+    }));
+
+    match res {
+        Ok(res) => Some(res),
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("unknown panic");
+
+            log::error!("chunk provider panicked at {pos:?}: {msg}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::Server;
+
+    struct Nothing;
+
+    impl Config for Nothing {
+        type ServerState = ();
+        type ClientState = ();
+        type EntityState = ();
+        type WorldState = ();
+        type ChunkState = ();
+        type PlayerListState = ();
+
+        fn max_connections(&self) -> usize {
+            0
+        }
+
+        fn update(&self, _server: &mut Server<Self>) {}
+    }
+
+    struct PanickingProvider;
+
+    impl ChunkProvider<Nothing> for PanickingProvider {
+        fn provide(&self, pos: ChunkPos, _chunk: &mut UnloadedChunk) {
+            if pos.x < 0 {
+                panic!("bad chunk");
+            }
+        }
+    }
+
+    #[test]
+    fn provider_panic_is_contained() {
+        let good = ChunkPos::new(0, 0);
+        let bad = ChunkPos::new(-1, 0);
+
+        let mut state = ProviderState::new(Arc::new(PanickingProvider));
+        let mut tickets = Tickets::default();
+        tickets.add(ChunkPos::new(0, 0), 1, None);
+
+        for pos in [good, bad] {
+            state.pending.insert(pos);
+            let res = provide(&*state.provider, pos, 16);
+            assert_eq!(res.is_some(), pos == good);
+            state.finished_tx.send((pos, res)).unwrap();
+        }
+
+        let mut chunks = HashMap::new();
+        state.receive(&mut chunks, &tickets);
+
+        assert!(chunks.contains_key(&good));
+        assert!(!chunks.contains_key(&bad));
+        assert!(state.pending.is_empty());
+        assert!(state.failed.contains(&bad));
+    }
+}
@@ -0,0 +1,298 @@
+use vek::{Aabb, Vec3};
+
+use crate::block::{BlockPos, BlockState};
+use crate::chunk::Chunks;
+use crate::client::BlockFace;
+use crate::config::Config;
+use crate::util::ray_box_intersect;
+
+/// The result of a successful raycast against the blocks in a world. See
+/// [`Chunks::raycast`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BlockRaycastHit {
+    /// The position of the block that was hit.
+    pub pos: BlockPos,
+    /// The block state at `pos`.
+    pub block: BlockState,
+    /// The face of the block's collision shape that the ray entered through.
+    pub face: BlockFace,
+    /// The exact point where the ray intersected the block.
+    pub point: Vec3<f64>,
+    /// The distance from the ray origin to `point`. If the origin of the ray
+    /// is inside the block, then this will be zero.
+    pub near: f64,
+}
+
+impl<C: Config> Chunks<C> {
+    /// Casts a ray defined by `origin` and `direction` through the blocks in
+    /// these chunks and returns the closest intersection with a block's
+    /// [collision shapes] for which `f` returns `true`.
+    ///
+    /// `f` is called at most once with the position and state of every block
+    /// the ray intersects. Blocks are passed to `f` in the order the ray
+    /// reaches them, except that collision shapes reaching into the block
+    /// above, such as those of fences, are tested along with that block.
+    /// Blocks without collision shapes such as air are never passed to `f`.
+    ///
+    /// The ray travels at most `max_distance` blocks. Unloaded chunks and the
+    /// space above and below the world are treated as empty. The given ray
+    /// direction must be normalized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is not finite.
+    ///
+    /// [collision shapes]: BlockState::collision_shapes
+    pub fn raycast<F>(
+        &self,
+        origin: Vec3<f64>,
+        direction: Vec3<f64>,
+        max_distance: f64,
+        f: F,
+    ) -> Option<BlockRaycastHit>
+    where
+        F: FnMut(BlockPos, BlockState) -> bool,
+    {
+        raycast_blocks(
+            origin,
+            direction,
+            max_distance,
+            |pos| self.get_block_state(pos),
+            f,
+        )
+    }
+}
+
+/// Walks the voxels along the ray with a DDA and tests the collision shapes of
+/// the blocks returned by `get_block`.
+fn raycast_blocks(
+    origin: Vec3<f64>,
+    direction: Vec3<f64>,
+    max_distance: f64,
+    mut get_block: impl FnMut(BlockPos) -> Option<BlockState>,
+    mut f: impl FnMut(BlockPos, BlockState) -> bool,
+) -> Option<BlockRaycastHit> {
+    debug_assert!(
+        direction.is_normalized(),
+        "the ray direction must be normalized"
+    );
+    // The ray would never end if it missed, since it can't tell whether
+    // anything remains ahead of it.
+    assert!(max_distance.is_finite(), "the max distance must be finite");
+
+    let mut pos = BlockPos::at(origin);
+
+    let step = direction.map(|d| d.signum() as i32);
+    // Distance along the ray between voxel boundaries on each axis.
+    let delta = direction.map(|d| (1.0 / d).abs());
+    // Distance along the ray to the next voxel boundary on each axis.
+    let mut next = Vec3::new(
+        boundary_distance(origin.x, direction.x, pos.x),
+        boundary_distance(origin.y, direction.y, pos.y),
+        boundary_distance(origin.z, direction.z, pos.z),
+    );
+
+    let mut best: Option<BlockRaycastHit> = None;
+    // Distance along the ray at which the current voxel is entered.
+    let mut entered = 0.0;
+    // The block and the block below it tested in the previous voxel.
+    let mut prev: Option<(BlockPos, BlockPos)> = None;
+
+    while entered <= max_distance {
+        if let Some(best) = best {
+            if best.near <= entered {
+                break;
+            }
+        }
+
+        // Some collision shapes such as fences and walls extend into the block
+        // above them. When the ray moves vertically, one of the two blocks was
+        // already tested in the previous voxel.
+        let below = BlockPos::new(pos.x, pos.y - 1, pos.z);
+
+        if prev.is_none_or(|(_, prev_below)| prev_below != pos) {
+            test_block(origin, direction, pos, &mut get_block, &mut f, &mut best);
+        }
+
+        if prev.is_none_or(|(prev_pos, _)| prev_pos != below) {
+            test_block(origin, direction, below, &mut get_block, &mut f, &mut best);
+        }
+
+        prev = Some((pos, below));
+
+        if next.x < next.y && next.x < next.z {
+            entered = next.x;
+            next.x += delta.x;
+            pos.x += step.x;
+        } else if next.y < next.z {
+            entered = next.y;
+            next.y += delta.y;
+            pos.y += step.y;
+        } else {
+            entered = next.z;
+            next.z += delta.z;
+            pos.z += step.z;
+        }
+    }
+
+    best.filter(|hit| hit.near <= max_distance)
+}
+
+fn boundary_distance(origin: f64, direction: f64, voxel: i32) -> f64 {
+    if direction > 0.0 {
+        (voxel as f64 + 1.0 - origin) / direction
+    } else if direction < 0.0 {
+        (voxel as f64 - origin) / direction
+    } else {
+        f64::INFINITY
+    }
+}
+
+fn test_block(
+    origin: Vec3<f64>,
+    direction: Vec3<f64>,
+    pos: BlockPos,
+    get_block: &mut impl FnMut(BlockPos) -> Option<BlockState>,
+    f: &mut impl FnMut(BlockPos, BlockState) -> bool,
+    best: &mut Option<BlockRaycastHit>,
+) {
+    let Some(block) = get_block(pos) else {
+        return;
+    };
+
+    let offset = Vec3::new(pos.x, pos.y, pos.z).as_::<f64>();
+
+    let mut closest: Option<(f64, Aabb<f64>)> = None;
+
+    for shape in block.collision_shapes() {
+        let bb = Aabb {
+            min: shape.min + offset,
+            max: shape.max + offset,
+        };
+
+        if let Some((near, _)) = ray_box_intersect(origin, direction, bb) {
+            if closest.is_none_or(|(n, _)| near < n) {
+                closest = Some((near, bb));
+            }
+        }
+    }
+
+    let Some((near, bb)) = closest else {
+        return;
+    };
+
+    if best.is_some_and(|b| b.near <= near) || !f(pos, block) {
+        return;
+    }
+
+    *best = Some(BlockRaycastHit {
+        pos,
+        block,
+        face: entry_face(origin, direction, bb),
+        point: origin + direction * near,
+        near,
+    });
+}
+
+/// Returns the face of `bb` that the ray enters through.
+fn entry_face(origin: Vec3<f64>, direction: Vec3<f64>, bb: Aabb<f64>) -> BlockFace {
+    let mut axis = 0;
+    let mut entry = -f64::INFINITY;
+
+    for i in 0..3 {
+        let t0 = (bb.min[i] - origin[i]) / direction[i];
+        let t1 = (bb.max[i] - origin[i]) / direction[i];
+        let t = t0.min(t1);
+
+        if t > entry {
+            entry = t;
+            axis = i;
+        }
+    }
+
+    match (axis, direction[axis] > 0.0) {
+        (0, true) => BlockFace::West,
+        (0, false) => BlockFace::East,
+        (1, true) => BlockFace::Bottom,
+        (1, false) => BlockFace::Top,
+        (_, true) => BlockFace::North,
+        (_, false) => BlockFace::South,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn get_block(pos: BlockPos) -> Option<BlockState> {
+        match (pos.x, pos.y, pos.z) {
+            (5, 0, 0) => Some(BlockState::STONE),
+            (-3, 1, 2) => Some(BlockState::OAK_FENCE),
+            (_, 0.., _) => Some(BlockState::AIR),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn raycast_hits_nearest_block() {
+        let origin = Vec3::new(0.5, 0.5, 0.5);
+
+        let hit = raycast_blocks(origin, Vec3::unit_x(), 10.0, get_block, |_, _| true).unwrap();
+        assert_eq!(hit.pos, BlockPos::new(5, 0, 0));
+        assert_eq!(hit.block, BlockState::STONE);
+        assert_eq!(hit.face, BlockFace::West);
+        assert_relative_eq!(hit.point, Vec3::new(5.0, 0.5, 0.5));
+        assert_relative_eq!(hit.near, 4.5);
+
+        assert!(raycast_blocks(origin, Vec3::unit_x(), 4.0, get_block, |_, _| true).is_none());
+        assert!(raycast_blocks(origin, -Vec3::unit_x(), 10.0, get_block, |_, _| true).is_none());
+        assert!(raycast_blocks(origin, Vec3::unit_x(), 10.0, get_block, |_, _| false).is_none());
+    }
+
+    #[test]
+    fn raycast_hits_tall_collision_shapes() {
+        // The top of the fence at (-3, 1, 2) is inside of the block above it.
+        let origin = Vec3::new(-0.5, 2.25, 2.5);
+        let hit = raycast_blocks(origin, -Vec3::unit_x(), 10.0, get_block, |_, _| true).unwrap();
+
+        assert_eq!(hit.pos, BlockPos::new(-3, 1, 2));
+        assert_eq!(hit.face, BlockFace::East);
+        assert_relative_eq!(hit.point.y, 2.25);
+
+        let origin = Vec3::new(-2.0, 3.0, 2.5);
+        let direction = Vec3::new(-1.0, -1.0, 0.0).normalized();
+        let hit = raycast_blocks(origin, direction, 10.0, get_block, |_, _| true).unwrap();
+
+        assert_eq!(hit.pos, BlockPos::new(-3, 1, 2));
+        assert_eq!(hit.face, BlockFace::Top);
+    }
+
+    #[test]
+    fn raycast_tests_blocks_once() {
+        // The fence is reached both as the block below y = 2 and at y = 1.
+        let origin = Vec3::new(-2.5, 5.0, 2.5);
+        let mut calls = 0;
+
+        let hit = raycast_blocks(origin, -Vec3::unit_y(), 10.0, get_block, |_, _| {
+            calls += 1;
+            false
+        });
+
+        assert!(hit.is_none());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn raycast_rejects_infinite_distance() {
+        raycast_blocks(
+            Vec3::new(0.5, 0.5, 0.5),
+            Vec3::unit_z(),
+            f64::INFINITY,
+            get_block,
+            |_, _| true,
+        );
+    }
+}
@@ -0,0 +1,1244 @@
+//! Redstone components, following the behavior of their vanilla counterparts.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use vek::{Aabb, Vec3};
+
+use crate::block::{BlockKind, BlockPos, BlockState, PropName, PropValue};
+use crate::chunk::direction::Direction;
+use crate::chunk::{BlockBehavior, Chunks, TickPriority};
+use crate::config::Config;
+use crate::Ticks;
+
+/// The maximum number of blocks a piston can push.
+const MAX_PUSHED_BLOCKS: usize = 12;
+
+impl<C: Config> Chunks<C> {
+    /// Enables the simulation of redstone in this world.
+    ///
+    /// This registers a [`BlockBehavior`] for redstone dust, redstone torches,
+    /// repeaters, comparators, levers, buttons, pressure plates, pistons and
+    /// redstone lamps, replacing any behaviors previously set for those kinds.
+    /// The components react to power and update their block states with the
+    /// same delays as in vanilla.
+    ///
+    /// Levers, buttons and pressure plates are not activated automatically.
+    /// Use [`toggle_lever`](Self::toggle_lever),
+    /// [`press_button`](Self::press_button) and
+    /// [`set_pressure_plate`](Self::set_pressure_plate) in response to players
+    /// and entities.
+    ///
+    /// Pistons move blocks instantly and do not push entities. Slime and honey
+    /// blocks do not stick to the blocks around them, and comparators do not
+    /// read the contents of containers.
+    pub fn enable_redstone(&mut self) {
+        let redstone = Arc::new(Redstone::default());
+
+        for kind in redstone_kinds() {
+            self.set_behavior(kind, RedstoneBehavior(redstone.clone()));
+        }
+    }
+
+    /// Stops the simulation of redstone enabled with
+    /// [`enable_redstone`](Self::enable_redstone).
+    pub fn disable_redstone(&mut self) {
+        for kind in redstone_kinds() {
+            self.clear_behavior(kind);
+        }
+    }
+
+    /// Flips the lever at `pos` on or off.
+    ///
+    /// Returns `false` if the block at `pos` is not a lever.
+    pub fn toggle_lever(&mut self, pos: impl Into<BlockPos>) -> bool {
+        let pos = pos.into();
+
+        match self.get_block_state(pos) {
+            Some(block) if block.to_kind() == BlockKind::Lever => {
+                let powered = is_on(block, PropName::Powered);
+                self.set_block_state(
+                    pos,
+                    block.set(PropName::Powered, PropValue::from_bool(!powered)),
+                )
+            }
+            _ => false,
+        }
+    }
+
+    /// Presses the button at `pos`. It is released automatically after 20
+    /// ticks for stone buttons and 30 ticks for wooden buttons.
+    ///
+    /// Returns `false` if the block at `pos` is not a button or if it is
+    /// already pressed.
+    pub fn press_button(&mut self, pos: impl Into<BlockPos>) -> bool {
+        let pos = pos.into();
+
+        let Some(block) = self.get_block_state(pos) else {
+            return false;
+        };
+
+        let Some(delay) = button_delay(block.to_kind()) else {
+            return false;
+        };
+
+        if is_on(block, PropName::Powered) {
+            return false;
+        }
+
+        self.set_block_state(pos, block.set(PropName::Powered, PropValue::True));
+        self.schedule_tick(pos, delay, TickPriority::Normal);
+
+        true
+    }
+
+    /// Updates the pressure plate at `pos` for the number of entities that
+    /// are currently on it. Use `0` to release the plate.
+    ///
+    /// Which entities can activate a pressure plate is up to the caller. In
+    /// vanilla, stone pressure plates only count mobs and players while other
+    /// plates count all entities. Weighted pressure plates output a signal
+    /// strength depending on the number of entities.
+    ///
+    /// Returns `false` if the block at `pos` is not a pressure plate.
+    pub fn set_pressure_plate(&mut self, pos: impl Into<BlockPos>, entities: usize) -> bool {
+        let pos = pos.into();
+
+        let Some(block) = self.get_block_state(pos) else {
+            return false;
+        };
+
+        let new_block = match block.to_kind() {
+            BlockKind::LightWeightedPressurePlate => block.set(
+                PropName::Power,
+                PropValue::from_u16(entities.min(15) as u16).unwrap(),
+            ),
+            BlockKind::HeavyWeightedPressurePlate => block.set(
+                PropName::Power,
+                PropValue::from_u16(entities.min(150).div_ceil(10) as u16).unwrap(),
+            ),
+            kind if is_pressure_plate(kind) => {
+                block.set(PropName::Powered, PropValue::from_bool(entities > 0))
+            }
+            _ => return false,
+        };
+
+        self.set_block_state(pos, new_block)
+    }
+}
+
+/// Returns the block kinds with redstone behavior.
+fn redstone_kinds() -> impl Iterator<Item = BlockKind> {
+    BlockKind::ALL.into_iter().filter(|&kind| {
+        matches!(
+            kind,
+            BlockKind::RedstoneWire
+                | BlockKind::RedstoneTorch
+                | BlockKind::RedstoneWallTorch
+                | BlockKind::Repeater
+                | BlockKind::Comparator
+                | BlockKind::Lever
+                | BlockKind::RedstoneLamp
+                | BlockKind::Piston
+                | BlockKind::StickyPiston
+        ) || button_delay(kind).is_some()
+            || is_pressure_plate(kind)
+    })
+}
+
+/// Returns the number of ticks a button stays pressed, or `None` if the block
+/// is not a button.
+fn button_delay(kind: BlockKind) -> Option<Ticks> {
+    match kind {
+        BlockKind::StoneButton | BlockKind::PolishedBlackstoneButton => Some(20),
+        _ if kind.to_str().ends_with("_button") => Some(30),
+        _ => None,
+    }
+}
+
+fn is_pressure_plate(kind: BlockKind) -> bool {
+    kind.to_str().ends_with("_pressure_plate")
+}
+
+fn is_gate(kind: BlockKind) -> bool {
+    matches!(kind, BlockKind::Repeater | BlockKind::Comparator)
+}
+
+fn is_on(block: BlockState, name: PropName) -> bool {
+    block.get(name).and_then(|v| v.to_bool()).unwrap_or(false)
+}
+
+fn level(block: BlockState, name: PropName) -> u8 {
+    block.get(name).and_then(|v| v.to_u16()).unwrap_or(0) as u8
+}
+
+fn facing(block: BlockState) -> Option<Direction> {
+    Direction::from_prop(block.get(PropName::Facing)?)
+}
+
+/// Returns the power level of `block` if it is redstone dust.
+fn wire_power(block: Option<BlockState>) -> u8 {
+    match block {
+        Some(b) if b.to_kind() == BlockKind::RedstoneWire => level(b, PropName::Power),
+        _ => 0,
+    }
+}
+
+/// Returns `true` if redstone conducts through the block. These are opaque
+/// full cubes.
+fn is_solid(block: BlockState) -> bool {
+    let full = Aabb {
+        min: Vec3::zero(),
+        max: Vec3::one(),
+    };
+
+    block.is_opaque() && block.collision_shapes().eq([full])
+}
+
+/// Returns the direction from a lever or button towards the block it is
+/// attached to.
+fn attached_direction(block: BlockState) -> Option<Direction> {
+    match block.get(PropName::Face)? {
+        PropValue::Floor => Some(Direction::Down),
+        PropValue::Ceiling => Some(Direction::Up),
+        _ => Some(facing(block)?.opposite()),
+    }
+}
+
+/// Returns `true` if the block is a source of redstone power that redstone
+/// dust connects to.
+fn emits_power(kind: BlockKind) -> bool {
+    matches!(
+        kind,
+        BlockKind::RedstoneWire
+            | BlockKind::RedstoneTorch
+            | BlockKind::RedstoneWallTorch
+            | BlockKind::RedstoneBlock
+            | BlockKind::Repeater
+            | BlockKind::Comparator
+            | BlockKind::Lever
+            | BlockKind::Observer
+            | BlockKind::Target
+            | BlockKind::DaylightDetector
+            | BlockKind::DetectorRail
+            | BlockKind::TrappedChest
+            | BlockKind::TripwireHook
+            | BlockKind::Lectern
+            | BlockKind::SculkSensor
+    ) || button_delay(kind).is_some()
+        || is_pressure_plate(kind)
+}
+
+/// Read access to blocks. This is implemented for [`Chunks`] and allows the
+/// redstone logic to be tested in isolation.
+trait RedstoneWorld {
+    fn block(&self, pos: BlockPos) -> Option<BlockState>;
+}
+
+impl<C: Config> RedstoneWorld for Chunks<C> {
+    fn block(&self, pos: BlockPos) -> Option<BlockState> {
+        self.get_block_state(pos)
+    }
+}
+
+/// The state shared by the redstone behaviors of a world.
+#[derive(Default)]
+struct Redstone {
+    /// The signal strength of every comparator, which is not part of its block
+    /// state.
+    comparator_outputs: Mutex<HashMap<BlockPos, u8>>,
+}
+
+/// The power levels below are queried in the same way as in vanilla, where
+/// `dir` always points from the block receiving power towards the block
+/// emitting it. `wires` is `false` when redstone dust should be ignored, which
+/// is the case when dust calculates its own power level.
+impl Redstone {
+    fn weak_power(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+        dir: Direction,
+        wires: bool,
+    ) -> u8 {
+        let kind = block.to_kind();
+
+        match kind {
+            BlockKind::RedstoneWire => {
+                let connected = dir == Direction::Up
+                    || wire_side(block, dir.opposite()).is_some_and(|s| s != PropValue::None);
+
+                if wires && dir != Direction::Down && connected {
+                    level(block, PropName::Power)
+                } else {
+                    0
+                }
+            }
+            BlockKind::RedstoneTorch if is_on(block, PropName::Lit) && dir != Direction::Up => 15,
+            BlockKind::RedstoneWallTorch
+                if is_on(block, PropName::Lit) && facing(block) != Some(dir) =>
+            {
+                15
+            }
+            BlockKind::RedstoneBlock => 15,
+            BlockKind::Repeater
+                if is_on(block, PropName::Powered) && facing(block) == Some(dir) =>
+            {
+                15
+            }
+            BlockKind::Comparator if facing(block) == Some(dir) => {
+                self.comparator_output(world, pos, block)
+            }
+            BlockKind::LightWeightedPressurePlate | BlockKind::HeavyWeightedPressurePlate => {
+                level(block, PropName::Power)
+            }
+            _ if (kind == BlockKind::Lever
+                || button_delay(kind).is_some()
+                || is_pressure_plate(kind))
+                && is_on(block, PropName::Powered) =>
+            {
+                15
+            }
+            _ => 0,
+        }
+    }
+
+    fn strong_power(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+        dir: Direction,
+        wires: bool,
+    ) -> u8 {
+        let kind = block.to_kind();
+
+        let strong = match kind {
+            BlockKind::RedstoneWire => wires,
+            BlockKind::RedstoneTorch | BlockKind::RedstoneWallTorch => dir == Direction::Down,
+            BlockKind::Repeater | BlockKind::Comparator => true,
+            _ if is_pressure_plate(kind) => dir == Direction::Up,
+            _ if kind == BlockKind::Lever || button_delay(kind).is_some() => {
+                attached_direction(block).map(Direction::opposite) == Some(dir)
+            }
+            _ => false,
+        };
+
+        if strong {
+            self.weak_power(world, pos, block, dir, wires)
+        } else {
+            0
+        }
+    }
+
+    /// Returns the power the block at `pos` provides to the adjacent block in
+    /// the opposite direction of `dir`. Solid blocks pass on the strong power
+    /// they receive.
+    fn emitted_power(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        dir: Direction,
+        wires: bool,
+    ) -> u8 {
+        let Some(block) = world.block(pos) else {
+            return 0;
+        };
+
+        let weak = self.weak_power(world, pos, block, dir, wires);
+
+        if is_solid(block) {
+            weak.max(self.received_strong_power(world, pos, wires))
+        } else {
+            weak
+        }
+    }
+
+    fn received_strong_power(&self, world: &impl RedstoneWorld, pos: BlockPos, wires: bool) -> u8 {
+        Direction::ALL
+            .into_iter()
+            .filter_map(|dir| {
+                let neighbor = dir.offset(pos);
+                let block = world.block(neighbor)?;
+                Some(self.strong_power(world, neighbor, block, dir, wires))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the highest power level the block at `pos` receives from any
+    /// direction.
+    fn received_power(&self, world: &impl RedstoneWorld, pos: BlockPos, wires: bool) -> u8 {
+        Direction::ALL
+            .into_iter()
+            .map(|dir| self.emitted_power(world, dir.offset(pos), dir, wires))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the power level redstone dust at `pos` should have.
+    fn wire_power(&self, world: &impl RedstoneWorld, pos: BlockPos) -> u8 {
+        let received = self.received_power(world, pos, false);
+
+        if received >= 15 {
+            return received;
+        }
+
+        let above_solid = world.block(Direction::Up.offset(pos)).is_some_and(is_solid);
+
+        let mut from_wires = 0;
+
+        for dir in Direction::HORIZONTAL {
+            let neighbor = dir.offset(pos);
+            let block = world.block(neighbor);
+
+            from_wires = from_wires.max(wire_power(block));
+
+            if block.is_some_and(is_solid) {
+                if !above_solid {
+                    from_wires =
+                        from_wires.max(wire_power(world.block(Direction::Up.offset(neighbor))));
+                }
+            } else {
+                from_wires =
+                    from_wires.max(wire_power(world.block(Direction::Down.offset(neighbor))));
+            }
+        }
+
+        received.max(from_wires.saturating_sub(1))
+    }
+
+    /// Returns `true` if a redstone torch should be lit. Torches turn off when
+    /// the block they are attached to is powered.
+    fn torch_lit(&self, world: &impl RedstoneWorld, pos: BlockPos, block: BlockState) -> bool {
+        let dir = match block.to_kind() {
+            BlockKind::RedstoneWallTorch => match facing(block) {
+                Some(f) => f.opposite(),
+                None => return true,
+            },
+            _ => Direction::Down,
+        };
+
+        self.emitted_power(world, dir.offset(pos), dir, true) == 0
+    }
+
+    /// Returns the power a repeater or comparator facing `facing` receives
+    /// from behind.
+    fn gate_input(&self, world: &impl RedstoneWorld, pos: BlockPos, facing: Direction) -> u8 {
+        let behind = facing.offset(pos);
+        let power = self.emitted_power(world, behind, facing, true);
+
+        if power >= 15 {
+            power
+        } else {
+            power.max(wire_power(world.block(behind)))
+        }
+    }
+
+    /// Returns the highest power a comparator or repeater facing `facing`
+    /// receives from its sides. When `gates_only` is `true`, only power from
+    /// other repeaters and comparators is considered, which is what locks
+    /// repeaters.
+    fn side_input(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        facing: Direction,
+        gates_only: bool,
+    ) -> u8 {
+        let right = facing.rotate_clockwise();
+
+        [right, right.opposite()]
+            .into_iter()
+            .filter_map(|dir| {
+                let side = dir.offset(pos);
+                let block = world.block(side)?;
+                let kind = block.to_kind();
+
+                Some(match kind {
+                    _ if gates_only && !is_gate(kind) => 0,
+                    BlockKind::RedstoneBlock => 15,
+                    BlockKind::RedstoneWire => level(block, PropName::Power),
+                    _ if emits_power(kind) => self.strong_power(world, side, block, dir, true),
+                    _ => 0,
+                })
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Calculates the signal strength a comparator should output from its
+    /// inputs.
+    fn calculate_comparator_output(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+    ) -> u8 {
+        let Some(facing) = facing(block) else {
+            return 0;
+        };
+
+        let rear = self.gate_input(world, pos, facing);
+
+        if rear == 0 {
+            return 0;
+        }
+
+        let side = self.side_input(world, pos, facing, false);
+
+        if block.get(PropName::Mode) == Some(PropValue::Subtract) {
+            rear.saturating_sub(side)
+        } else if side > rear {
+            0
+        } else {
+            rear
+        }
+    }
+
+    /// Returns the signal strength a comparator currently outputs.
+    fn comparator_output(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+    ) -> u8 {
+        let stored = self.comparator_outputs.lock().unwrap().get(&pos).copied();
+
+        match stored {
+            Some(output) => output,
+            // The output of comparators that existed before redstone was
+            // enabled is unknown.
+            None if is_on(block, PropName::Powered) => {
+                self.calculate_comparator_output(world, pos, block)
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the block state redstone dust at `pos` should have, with its
+    /// connections and power level updated.
+    fn updated_wire(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+    ) -> BlockState {
+        let above_solid = world.block(Direction::Up.offset(pos)).is_some_and(is_solid);
+
+        let mut sides = Direction::HORIZONTAL.map(|dir| {
+            let neighbor = dir.offset(pos);
+            let neighbor_block = world.block(neighbor);
+            let neighbor_solid = neighbor_block.is_some_and(is_solid);
+            let is_wire =
+                |b: Option<BlockState>| b.is_some_and(|b| b.to_kind() == BlockKind::RedstoneWire);
+
+            if !above_solid
+                && neighbor_solid
+                && is_wire(world.block(Direction::Up.offset(neighbor)))
+            {
+                PropValue::Up
+            } else if neighbor_block.is_some_and(|b| connects_to(b, dir))
+                || !neighbor_solid && is_wire(world.block(Direction::Down.offset(neighbor)))
+            {
+                PropValue::Side
+            } else {
+                PropValue::None
+            }
+        });
+
+        // Dust connected on one side extends to the opposite side, and dust
+        // without connections forms a cross.
+        let connected = sides.map(|s| s != PropValue::None);
+
+        if !connected.contains(&true) {
+            sides = [PropValue::Side; 4];
+        } else {
+            for i in 0..4 {
+                let opposite = (i + 2) % 4;
+                let perpendicular = [(i + 1) % 4, (i + 3) % 4];
+
+                if connected[i]
+                    && !connected[opposite]
+                    && !perpendicular.iter().any(|&j| connected[j])
+                {
+                    sides[opposite] = PropValue::Side;
+                }
+            }
+        }
+
+        let mut block = block;
+
+        for (dir, side) in Direction::HORIZONTAL.into_iter().zip(sides) {
+            block = block.set(wire_side_name(dir), side);
+        }
+
+        block.set(
+            PropName::Power,
+            PropValue::from_u16(self.wire_power(world, pos) as u16).unwrap(),
+        )
+    }
+
+    /// Returns the block state a piston should have after being powered or
+    /// unpowered, along with the blocks it moves. Returns `None` if nothing
+    /// changes.
+    fn piston_move(
+        &self,
+        world: &impl RedstoneWorld,
+        pos: BlockPos,
+        block: BlockState,
+    ) -> Option<PistonMove> {
+        let facing = facing(block)?;
+        let extended = is_on(block, PropName::Extended);
+        let extend = self.piston_powered(world, pos, facing);
+
+        if extend && !extended {
+            push_plan(world, pos, facing).map(PistonMove::Extend)
+        } else if !extend && extended {
+            Some(PistonMove::Retract)
+        } else {
+            None
+        }
+    }
+
+    fn piston_powered(&self, world: &impl RedstoneWorld, pos: BlockPos, facing: Direction) -> bool {
+        let powered = |pos: BlockPos, except: Direction| {
+            Direction::ALL
+                .into_iter()
+                .filter(|&dir| dir != except)
+                .any(|dir| self.emitted_power(world, dir.offset(pos), dir, true) > 0)
+        };
+
+        // Pistons can also be powered through the space above them, which is
+        // known as quasi-connectivity.
+        powered(pos, facing) || powered(Direction::Up.offset(pos), Direction::Down)
+    }
+}
+
+fn wire_side_name(dir: Direction) -> PropName {
+    match dir {
+        Direction::North => PropName::North,
+        Direction::South => PropName::South,
+        Direction::West => PropName::West,
+        _ => PropName::East,
+    }
+}
+
+fn wire_side(block: BlockState, dir: Direction) -> Option<PropValue> {
+    if dir.is_horizontal() {
+        block.get(wire_side_name(dir))
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if redstone dust connects to `block` in the direction `dir`.
+fn connects_to(block: BlockState, dir: Direction) -> bool {
+    match block.to_kind() {
+        BlockKind::RedstoneWire => true,
+        BlockKind::Repeater => facing(block).is_some_and(|f| f == dir || f == dir.opposite()),
+        BlockKind::Observer => facing(block) == Some(dir),
+        kind => emits_power(kind),
+    }
+}
+
+/// How a block reacts to being pushed by a piston.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PistonBehavior {
+    /// The block is moved.
+    Normal,
+    /// The block is broken.
+    Destroy,
+    /// The block cannot be moved.
+    Block,
+    /// The block can be pushed, but not pulled.
+    PushOnly,
+    /// The space is empty.
+    Empty,
+}
+
+fn piston_behavior(block: BlockState) -> PistonBehavior {
+    let kind = block.to_kind();
+    let name = kind.to_str();
+
+    if block.is_air() {
+        return PistonBehavior::Empty;
+    }
+
+    if matches!(kind, BlockKind::Piston | BlockKind::StickyPiston) {
+        return if is_on(block, PropName::Extended) {
+            PistonBehavior::Block
+        } else {
+            PistonBehavior::Normal
+        };
+    }
+
+    let immovable = matches!(
+        kind,
+        BlockKind::Obsidian
+            | BlockKind::CryingObsidian
+            | BlockKind::RespawnAnchor
+            | BlockKind::Bedrock
+            | BlockKind::Barrier
+            | BlockKind::Light
+            | BlockKind::EndPortalFrame
+            | BlockKind::EndPortal
+            | BlockKind::EndGateway
+            | BlockKind::NetherPortal
+            | BlockKind::ReinforcedDeepslate
+            | BlockKind::PistonHead
+            | BlockKind::MovingPiston
+            | BlockKind::StructureBlock
+            | BlockKind::Jigsaw
+            | BlockKind::EnchantingTable
+            | BlockKind::Beacon
+            | BlockKind::Spawner
+            | BlockKind::BrewingStand
+            | BlockKind::Jukebox
+            | BlockKind::Lectern
+            | BlockKind::Barrel
+            | BlockKind::Furnace
+            | BlockKind::BlastFurnace
+            | BlockKind::Smoker
+            | BlockKind::Hopper
+            | BlockKind::Dispenser
+            | BlockKind::Dropper
+            | BlockKind::DaylightDetector
+            | BlockKind::Beehive
+            | BlockKind::BeeNest
+            | BlockKind::Conduit
+            | BlockKind::Bell
+            | BlockKind::SculkSensor
+            | BlockKind::SculkCatalyst
+            | BlockKind::SculkShrieker
+    ) || name.ends_with("command_block")
+        || name.ends_with("chest")
+        || name.ends_with("_sign")
+        || name.ends_with("campfire");
+
+    if immovable {
+        return PistonBehavior::Block;
+    }
+
+    if name.ends_with("_glazed_terracotta") {
+        return PistonBehavior::PushOnly;
+    }
+
+    let destroyed = block.is_liquid()
+        || block.collision_shapes().len() == 0
+        || is_gate(kind)
+        || name.ends_with("_door")
+        || name.ends_with("_bed")
+        || name.ends_with("shulker_box")
+        || name.ends_with("_head")
+        || name.ends_with("_skull")
+        || name.ends_with("_banner")
+        || name.ends_with("flower_pot")
+        || name.starts_with("potted_")
+        || matches!(
+            kind,
+            BlockKind::Cactus
+                | BlockKind::Pumpkin
+                | BlockKind::CarvedPumpkin
+                | BlockKind::JackOLantern
+                | BlockKind::Melon
+                | BlockKind::Cake
+                | BlockKind::DragonEgg
+                | BlockKind::Bamboo
+                | BlockKind::Cocoa
+                | BlockKind::ChorusPlant
+                | BlockKind::ChorusFlower
+                | BlockKind::Scaffolding
+                | BlockKind::Lantern
+                | BlockKind::SoulLantern
+                | BlockKind::TurtleEgg
+                | BlockKind::SeaPickle
+        );
+
+    if destroyed {
+        PistonBehavior::Destroy
+    } else {
+        PistonBehavior::Normal
+    }
+}
+
+/// The blocks affected when a piston extends.
+#[derive(Clone, PartialEq, Eq, Debug)]
+struct PushPlan {
+    /// The positions of the blocks to move, nearest to the piston first.
+    moved: Vec<BlockPos>,
+    /// The position of a block broken by the push.
+    destroyed: Option<BlockPos>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum PistonMove {
+    Extend(PushPlan),
+    Retract,
+}
+
+/// Returns the blocks a piston at `pos` facing `facing` would push, or `None`
+/// if it cannot extend.
+fn push_plan(world: &impl RedstoneWorld, pos: BlockPos, facing: Direction) -> Option<PushPlan> {
+    let mut moved = Vec::new();
+    let mut next = facing.offset(pos);
+
+    loop {
+        // Blocks cannot be pushed into unloaded chunks or out of the world.
+        let block = world.block(next)?;
+
+        match piston_behavior(block) {
+            PistonBehavior::Empty => {
+                return Some(PushPlan {
+                    moved,
+                    destroyed: None,
+                })
+            }
+            PistonBehavior::Destroy => {
+                return Some(PushPlan {
+                    moved,
+                    destroyed: Some(next),
+                })
+            }
+            PistonBehavior::Block => return None,
+            PistonBehavior::Normal | PistonBehavior::PushOnly => {
+                if moved.len() == MAX_PUSHED_BLOCKS {
+                    return None;
+                }
+                moved.push(next);
+                next = facing.offset(next);
+            }
+        }
+    }
+}
+
+struct RedstoneBehavior(Arc<Redstone>);
+
+impl<C: Config> BlockBehavior<C> for RedstoneBehavior {
+    fn state_changed(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        self.update(chunks, pos, block);
+
+        if emits_power(block.to_kind()) {
+            // Blocks powered through solid blocks need to be updated too.
+            update_around(chunks, pos);
+        }
+    }
+
+    fn replaced(&self, chunks: &mut Chunks<C>, pos: BlockPos, old: BlockState) {
+        let kind = old.to_kind();
+
+        if kind == BlockKind::Comparator {
+            self.0.comparator_outputs.lock().unwrap().remove(&pos);
+        }
+
+        if matches!(kind, BlockKind::Piston | BlockKind::StickyPiston)
+            && is_on(old, PropName::Extended)
+        {
+            if let Some(facing) = facing(old) {
+                let head = facing.offset(pos);
+
+                if chunks.get_block_state(head).map(|b| b.to_kind()) == Some(BlockKind::PistonHead)
+                {
+                    chunks.set_block_state(head, BlockState::AIR);
+                }
+            }
+        }
+
+        if emits_power(kind) {
+            update_around(chunks, pos);
+        }
+    }
+
+    fn neighbor_update(
+        &self,
+        chunks: &mut Chunks<C>,
+        pos: BlockPos,
+        block: BlockState,
+        _neighbor: BlockPos,
+    ) {
+        self.update(chunks, pos, block);
+    }
+
+    fn scheduled_tick(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        let redstone = &*self.0;
+
+        match block.to_kind() {
+            BlockKind::RedstoneTorch | BlockKind::RedstoneWallTorch => {
+                let lit = redstone.torch_lit(chunks, pos, block);
+                chunks.set_block_state(pos, block.set(PropName::Lit, PropValue::from_bool(lit)));
+            }
+            BlockKind::RedstoneLamp if redstone.received_power(chunks, pos, true) == 0 => {
+                chunks.set_block_state(pos, block.set(PropName::Lit, PropValue::False));
+            }
+            BlockKind::Repeater => {
+                let Some(facing) = facing(block) else {
+                    return;
+                };
+
+                if is_on(block, PropName::Locked) {
+                    return;
+                }
+
+                let has_power = redstone.gate_input(chunks, pos, facing) > 0;
+
+                if is_on(block, PropName::Powered) {
+                    if !has_power {
+                        chunks.set_block_state(pos, block.set(PropName::Powered, PropValue::False));
+                    }
+                } else {
+                    chunks.set_block_state(pos, block.set(PropName::Powered, PropValue::True));
+
+                    // Pulses shorter than the delay are extended.
+                    if !has_power {
+                        chunks.schedule_tick(pos, repeater_delay(block), TickPriority::VeryHigh);
+                    }
+                }
+            }
+            BlockKind::Comparator => {
+                let output = redstone.calculate_comparator_output(chunks, pos, block);
+                let old = redstone
+                    .comparator_outputs
+                    .lock()
+                    .unwrap()
+                    .insert(pos, output);
+
+                let new_block = block.set(PropName::Powered, PropValue::from_bool(output > 0));
+
+                if new_block != block {
+                    chunks.set_block_state(pos, new_block);
+                } else if old != Some(output) {
+                    update_around(chunks, pos);
+                }
+            }
+            kind if button_delay(kind).is_some() => {
+                chunks.set_block_state(pos, block.set(PropName::Powered, PropValue::False));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl RedstoneBehavior {
+    /// Reacts to a change of the block at `pos` or around it.
+    fn update<C: Config>(&self, chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState) {
+        let redstone = &*self.0;
+
+        match block.to_kind() {
+            BlockKind::RedstoneWire => {
+                let new_block = redstone.updated_wire(chunks, pos, block);
+
+                if new_block != block {
+                    chunks.set_block_state(pos, new_block);
+                }
+            }
+            BlockKind::RedstoneTorch | BlockKind::RedstoneWallTorch
+                if is_on(block, PropName::Lit) != redstone.torch_lit(chunks, pos, block) =>
+            {
+                chunks.schedule_tick(pos, 2, TickPriority::Normal);
+            }
+            BlockKind::RedstoneLamp => {
+                let powered = redstone.received_power(chunks, pos, true) > 0;
+                let lit = is_on(block, PropName::Lit);
+
+                if lit && !powered {
+                    chunks.schedule_tick(pos, 4, TickPriority::Normal);
+                } else if !lit && powered {
+                    chunks.set_block_state(pos, block.set(PropName::Lit, PropValue::True));
+                }
+            }
+            BlockKind::Repeater => {
+                let Some(facing) = facing(block) else {
+                    return;
+                };
+
+                let locked = redstone.side_input(chunks, pos, facing, true) > 0;
+
+                if locked != is_on(block, PropName::Locked) {
+                    chunks.set_block_state(
+                        pos,
+                        block.set(PropName::Locked, PropValue::from_bool(locked)),
+                    );
+                    return;
+                }
+
+                let powered = is_on(block, PropName::Powered);
+                let has_power = redstone.gate_input(chunks, pos, facing) > 0;
+
+                if !locked && powered != has_power && !chunks.is_tick_scheduled(pos) {
+                    let priority = if target_not_aligned(chunks, pos, facing) {
+                        TickPriority::ExtremelyHigh
+                    } else if powered {
+                        TickPriority::VeryHigh
+                    } else {
+                        TickPriority::High
+                    };
+
+                    chunks.schedule_tick(pos, repeater_delay(block), priority);
+                }
+            }
+            BlockKind::Comparator => {
+                if chunks.is_tick_scheduled(pos) {
+                    return;
+                }
+
+                let output = redstone.calculate_comparator_output(chunks, pos, block);
+                let current = redstone.comparator_output(chunks, pos, block);
+
+                if output != current || is_on(block, PropName::Powered) != (output > 0) {
+                    let priority = match facing(block) {
+                        Some(facing) if target_not_aligned(chunks, pos, facing) => {
+                            TickPriority::High
+                        }
+                        _ => TickPriority::Normal,
+                    };
+
+                    chunks.schedule_tick(pos, 2, priority);
+                }
+            }
+            BlockKind::Piston | BlockKind::StickyPiston => {
+                if let Some(m) = redstone.piston_move(chunks, pos, block) {
+                    move_piston(chunks, pos, block, m);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Queues neighbor updates around all blocks adjacent to `pos`.
+fn update_around<C: Config>(chunks: &mut Chunks<C>, pos: BlockPos) {
+    for dir in Direction::ALL {
+        chunks.update_neighbors(dir.offset(pos));
+    }
+}
+
+fn repeater_delay(block: BlockState) -> Ticks {
+    level(block, PropName::Delay).max(1) as Ticks * 2
+}
+
+/// Returns `true` if the gate at `pos` outputs into the side of another gate.
+fn target_not_aligned(world: &impl RedstoneWorld, pos: BlockPos, facing: Direction) -> bool {
+    world
+        .block(facing.opposite().offset(pos))
+        .is_some_and(|b| is_gate(b.to_kind()) && self::facing(b) != Some(facing))
+}
+
+fn move_piston<C: Config>(chunks: &mut Chunks<C>, pos: BlockPos, block: BlockState, m: PistonMove) {
+    let Some(facing) = facing(block) else {
+        return;
+    };
+
+    let sticky = block.to_kind() == BlockKind::StickyPiston;
+    let head_pos = facing.offset(pos);
+
+    match m {
+        PistonMove::Extend(plan) => {
+            if let Some(destroyed) = plan.destroyed {
+                chunks.set_block_state(destroyed, BlockState::AIR);
+            }
+
+            for &from in plan.moved.iter().rev() {
+                if let Some(moved) = chunks.get_block_state(from) {
+                    chunks.set_block_state(facing.offset(from), moved);
+                }
+            }
+
+            chunks.set_block_state(pos, block.set(PropName::Extended, PropValue::True));
+            chunks.set_block_state(head_pos, piston_head(facing, sticky));
+        }
+        PistonMove::Retract => {
+            chunks.set_block_state(pos, block.set(PropName::Extended, PropValue::False));
+
+            let head = chunks.get_block_state(head_pos);
+            if head.is_some_and(|h| h.to_kind() == BlockKind::PistonHead) {
+                chunks.set_block_state(head_pos, BlockState::AIR);
+            }
+
+            if sticky {
+                let pulled_pos = facing.offset(head_pos);
+
+                if let Some(pulled) = chunks.get_block_state(pulled_pos) {
+                    if piston_behavior(pulled) == PistonBehavior::Normal
+                        && chunks.get_block_state(head_pos).is_some_and(|b| b.is_air())
+                    {
+                        chunks.set_block_state(head_pos, pulled);
+                        chunks.set_block_state(pulled_pos, BlockState::AIR);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn piston_head(facing: Direction, sticky: bool) -> BlockState {
+    BlockState::PISTON_HEAD
+        .set(PropName::Facing, facing.to_prop())
+        .set(PropName::Short, PropValue::False)
+        .set(
+            PropName::Type,
+            if sticky {
+                PropValue::Sticky
+            } else {
+                PropValue::Normal
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestWorld(HashMap<BlockPos, BlockState>);
+
+    impl RedstoneWorld for TestWorld {
+        fn block(&self, pos: BlockPos) -> Option<BlockState> {
+            Some(if pos.y < 0 {
+                BlockState::STONE
+            } else {
+                self.0.get(&pos).copied().unwrap_or(BlockState::AIR)
+            })
+        }
+    }
+
+    impl TestWorld {
+        fn set(&mut self, pos: [i32; 3], block: BlockState) {
+            self.0.insert(BlockPos::from(pos), block);
+        }
+    }
+
+    fn lever(powered: bool) -> BlockState {
+        BlockState::LEVER
+            .set(PropName::Face, PropValue::Floor)
+            .set(PropName::Powered, PropValue::from_bool(powered))
+    }
+
+    fn wire(power: u16) -> BlockState {
+        BlockState::REDSTONE_WIRE.set(PropName::Power, PropValue::from_u16(power).unwrap())
+    }
+
+    #[test]
+    fn lever_powers_dust_and_blocks() {
+        let redstone = Redstone::default();
+        let mut world = TestWorld::default();
+
+        world.set([0, 0, 0], lever(true));
+        world.set([1, 0, 0], wire(0));
+
+        let dust = BlockPos::new(1, 0, 0);
+        assert_eq!(redstone.wire_power(&world, dust), 15);
+
+        // The lever strongly powers the block it is attached to.
+        assert_eq!(
+            redstone.received_strong_power(&world, BlockPos::new(0, -1, 0), true),
+            15
+        );
+
+        world.set([1, 0, 0], wire(15));
+        world.set([2, 0, 0], wire(0));
+        assert_eq!(redstone.wire_power(&world, BlockPos::new(2, 0, 0)), 14);
+
+        world.set([0, 0, 0], lever(false));
+        assert_eq!(redstone.received_power(&world, dust, false), 0);
+    }
+
+    #[test]
+    fn dust_connections() {
+        let redstone = Redstone::default();
+        let mut world = TestWorld::default();
+
+        // Lone dust forms a cross.
+        let block = redstone.updated_wire(&world, BlockPos::new(0, 0, 0), wire(0));
+        for dir in Direction::HORIZONTAL {
+            assert_eq!(block.get(wire_side_name(dir)), Some(PropValue::Side));
+        }
+
+        // Dust connected on one side forms a line.
+        world.set([0, 0, -1], wire(0));
+        let block = redstone.updated_wire(&world, BlockPos::new(0, 0, 0), wire(0));
+        assert_eq!(block.get(PropName::North), Some(PropValue::Side));
+        assert_eq!(block.get(PropName::South), Some(PropValue::Side));
+        assert_eq!(block.get(PropName::East), Some(PropValue::None));
+        assert_eq!(block.get(PropName::West), Some(PropValue::None));
+
+        // Dust runs up the side of solid blocks.
+        world.set([1, 0, 0], BlockState::STONE);
+        world.set([1, 1, 0], wire(0));
+        let block = redstone.updated_wire(&world, BlockPos::new(0, 0, 0), wire(0));
+        assert_eq!(block.get(PropName::East), Some(PropValue::Up));
+    }
+
+    #[test]
+    fn torch_turns_off_when_attached_block_is_powered() {
+        let redstone = Redstone::default();
+        let mut world = TestWorld::default();
+
+        let torch = BlockState::REDSTONE_WALL_TORCH.set(PropName::Facing, PropValue::East);
+        world.set([0, 0, 0], BlockState::STONE);
+        world.set([1, 0, 0], torch);
+
+        let pos = BlockPos::new(1, 0, 0);
+        assert!(redstone.torch_lit(&world, pos, torch));
+
+        // A lever on top of the block powers it strongly.
+        world.set([0, 1, 0], lever(true));
+        assert!(!redstone.torch_lit(&world, pos, torch));
+    }
+
+    #[test]
+    fn comparator_modes() {
+        let redstone = Redstone::default();
+        let mut world = TestWorld::default();
+
+        let pos = BlockPos::new(0, 0, 0);
+        let comparator = BlockState::COMPARATOR.set(PropName::Facing, PropValue::North);
+
+        // The rear input is to the north.
+        world.set([0, 0, -1], wire(10));
+        world.set([1, 0, 0], wire(4));
+
+        assert_eq!(
+            redstone.calculate_comparator_output(&world, pos, comparator),
+            10
+        );
+
+        let subtract = comparator.set(PropName::Mode, PropValue::Subtract);
+        assert_eq!(
+            redstone.calculate_comparator_output(&world, pos, subtract),
+            6
+        );
+
+        world.set([-1, 0, 0], wire(12));
+        assert_eq!(
+            redstone.calculate_comparator_output(&world, pos, comparator),
+            0
+        );
+    }
+
+    #[test]
+    fn piston_push_limits() {
+        let mut world = TestWorld::default();
+        let pos = BlockPos::new(0, 0, 0);
+
+        for x in 1..=12 {
+            world.set([x, 0, 0], BlockState::STONE);
+        }
+        world.set([13, 0, 0], BlockState::GRASS);
+
+        let plan = push_plan(&world, pos, Direction::East).unwrap();
+        assert_eq!(plan.moved.len(), 12);
+        assert_eq!(plan.destroyed, Some(BlockPos::new(13, 0, 0)));
+
+        world.set([13, 0, 0], BlockState::STONE);
+        assert_eq!(push_plan(&world, pos, Direction::East), None);
+
+        world.set([2, 0, 0], BlockState::OBSIDIAN);
+        assert_eq!(push_plan(&world, pos, Direction::East), None);
+
+        // The floor of the test world is made of stone.
+        let plan = push_plan(&world, pos, Direction::Up).unwrap();
+        assert!(plan.moved.is_empty());
+        assert_eq!(plan.destroyed, None);
+    }
+}
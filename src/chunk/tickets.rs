@@ -0,0 +1,175 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::chunk::ChunkPos;
+use crate::client::Clients;
+use crate::config::Config;
+use crate::util::chunks_in_view_distance;
+use crate::world::WorldId;
+use crate::Ticks;
+
+/// Identifies a forced or temporary ticket added to a world's
+/// [`Chunks`](crate::chunk::Chunks).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct TicketId(u64);
+
+/// The number of chunks beyond the view distance of clients that are covered
+/// by player tickets. This matches the cache used by clients so that a chunk is
+/// not unloaded while a client still has it loaded.
+const PLAYER_TICKET_CACHE: u8 = 2;
+
+#[derive(Default)]
+pub(super) struct Tickets {
+    next_id: u64,
+    tickets: HashMap<TicketId, Ticket>,
+    /// The number of forced and temporary tickets covering each chunk.
+    counts: HashMap<ChunkPos, u32>,
+    /// The chunks covered by player tickets. This is recomputed every tick.
+    player_chunks: HashSet<ChunkPos>,
+    /// The chunk and view distance of every client in the world.
+    viewers: Vec<(ChunkPos, u8)>,
+}
+
+struct Ticket {
+    center: ChunkPos,
+    radius: u32,
+    /// The tick on which this ticket is removed, or `None` if the ticket is
+    /// forced.
+    expires: Option<Ticks>,
+}
+
+impl Ticket {
+    fn chunks(&self) -> impl Iterator<Item = ChunkPos> {
+        let r = self.radius as i32;
+        let center = self.center;
+
+        (center.z - r..=center.z + r)
+            .flat_map(move |z| (center.x - r..=center.x + r).map(move |x| ChunkPos { x, z }))
+    }
+}
+
+impl Tickets {
+    pub fn add(&mut self, center: ChunkPos, radius: u32, expires: Option<Ticks>) -> TicketId {
+        let id = TicketId(self.next_id);
+        self.next_id += 1;
+
+        let ticket = Ticket {
+            center,
+            radius,
+            expires,
+        };
+
+        for pos in ticket.chunks() {
+            *self.counts.entry(pos).or_default() += 1;
+        }
+
+        self.tickets.insert(id, ticket);
+        id
+    }
+
+    pub fn remove(&mut self, id: TicketId) -> bool {
+        match self.tickets.remove(&id) {
+            Some(ticket) => {
+                self.release(&ticket);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn release(&mut self, ticket: &Ticket) {
+        for pos in ticket.chunks() {
+            if let Some(count) = self.counts.get_mut(&pos) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&pos);
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, pos: ChunkPos) -> bool {
+        self.counts.contains_key(&pos) || self.player_chunks.contains(&pos)
+    }
+
+    /// Removes expired tickets and recomputes the player tickets.
+    pub fn update<C: Config>(&mut self, world: WorldId, clients: &Clients<C>, current_tick: Ticks) {
+        let expired: Vec<_> = self
+            .tickets
+            .iter()
+            .filter(|(_, t)| t.expires.is_some_and(|e| e <= current_tick))
+            .map(|(&id, _)| id)
+            .collect();
+
+        for id in expired {
+            self.remove(id);
+        }
+
+        self.viewers.clear();
+        self.viewers.extend(
+            clients
+                .iter()
+                .filter(|(_, c)| c.world() == world && !c.is_disconnected())
+                .map(|(_, c)| {
+                    (
+                        ChunkPos::at(c.position().x, c.position().z),
+                        c.view_distance(),
+                    )
+                }),
+        );
+
+        self.player_chunks.clear();
+        for &(center, dist) in &self.viewers {
+            self.player_chunks
+                .extend(chunks_in_view_distance(center, dist + PLAYER_TICKET_CACHE));
+        }
+    }
+
+    /// Returns all chunks that should be loaded, starting with the chunks
+    /// closest to clients.
+    pub fn wanted(&self) -> Vec<ChunkPos> {
+        let mut near_clients = Vec::new();
+
+        for &(center, dist) in &self.viewers {
+            for pos in chunks_in_view_distance(center, dist) {
+                let dx = pos.x - center.x;
+                let dz = pos.z - center.z;
+                near_clients.push((dx * dx + dz * dz, pos));
+            }
+        }
+
+        near_clients.sort_unstable_by_key(|&(dist, _)| dist);
+
+        near_clients
+            .into_iter()
+            .map(|(_, pos)| pos)
+            .chain(self.counts.keys().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticket_reference_counting() {
+        let mut tickets = Tickets::default();
+
+        let a = tickets.add(ChunkPos::new(0, 0), 1, None);
+        let b = tickets.add(ChunkPos::new(2, 0), 1, None);
+
+        assert!(tickets.contains(ChunkPos::new(-1, -1)));
+        assert!(tickets.contains(ChunkPos::new(1, 0)));
+        assert!(tickets.contains(ChunkPos::new(3, 1)));
+        assert!(!tickets.contains(ChunkPos::new(4, 0)));
+
+        assert!(tickets.remove(a));
+        assert!(!tickets.remove(a));
+
+        assert!(!tickets.contains(ChunkPos::new(0, 0)));
+        assert!(tickets.contains(ChunkPos::new(1, 0)));
+
+        assert!(tickets.remove(b));
+        assert!(tickets.counts.is_empty());
+    }
+}
@@ -22,8 +22,10 @@ use crate::entity::{
     velocity_to_packet_units, Entities, EntityEvent, EntityId, EntityKind, StatusOrAnimation,
 };
 use crate::ident::Ident;
+use crate::particle::{particle_packet, Particle};
 use crate::player_list::{PlayerListId, PlayerLists};
 use crate::player_textures::SignedPlayerTextures;
+#[allow(hidden_glob_reexports)]
 use crate::protocol::packets::c2s::play::{
     C2sPlayPacket, DiggingStatus, InteractKind, PlayerCommandId,
 };
@@ -32,18 +34,21 @@ use crate::protocol::packets::s2c::play::{
     BiomeRegistry, ChatTypeRegistry, ChunkLoadDistance, ChunkRenderDistanceCenter, ClearTitles,
     DimensionTypeRegistry, DimensionTypeRegistryEntry, Disconnect, EntitiesDestroy,
     EntityAnimation, EntityAttributes, EntityAttributesProperty, EntityPosition, EntitySetHeadYaw,
-    EntityStatus, EntityTrackerUpdate, EntityVelocityUpdate, GameJoin, GameMessage,
-    GameStateChange, GameStateChangeReason, KeepAlive, MoveRelative, OverlayMessage, PlaySoundId,
+    EntityStatus, EntityTrackerUpdate, EntityVelocityUpdate, ExplosionS2c, GameJoin, GameMessage,
+    GameStateChange, GameStateChangeReason, KeepAlive, MoveRelative, OverlayMessage,
     PlayerActionResponse, PlayerPositionLook, PlayerPositionLookFlags, PlayerRespawn,
-    PlayerSpawnPosition, RegistryCodec, Rotate, RotateAndMoveRelative, S2cPlayPacket,
-    SoundCategory, UnloadChunk, UpdateSubtitle, UpdateTitle,
+    PlayerSpawnPosition, RegistryCodec, Rotate, RotateAndMoveRelative, S2cPlayPacket, StopSound,
+    UnloadChunk, UpdateSubtitle, UpdateTitle, WorldBorderCenterChanged, WorldBorderInitialize,
+    WorldBorderInterpolateSize, WorldBorderSizeChanged, WorldBorderWarningBlocksChanged,
+    WorldBorderWarningTimeChanged, WorldTimeUpdate,
 };
-use crate::protocol::{BoundedInt, ByteAngle, NbtBridge, RawBytes, VarInt};
+use crate::protocol::{BoundedInt, ByteAngle, NbtBridge, RawBytes, VarInt, VarLong};
 use crate::server::{C2sPacketChannels, NewClientData, S2cPlayMessage, SharedServer};
 use crate::slab_versioned::{Key, VersionedSlab};
+use crate::sound::{sound_packet, SoundCategory};
 use crate::text::Text;
 use crate::util::{chunks_in_view_distance, is_chunk_in_view_distance};
-use crate::world::{WorldId, Worlds};
+use crate::world::{Weather, WorldId, Worlds, EXPLOSION_RANGE, PORTAL_TELEPORT_BOUNDARY};
 use crate::{ident, LIBRARY_NAMESPACE};
 
 /// Contains the [`ClientEvent`] enum and related data types.
@@ -228,6 +233,10 @@ pub struct Client<C: Config> {
     bar_to_send: Option<Text>,
     attack_speed: f64,
     movement_speed: f64,
+    time_override: Option<i64>,
+    weather_override: Option<Weather>,
+    /// The rain and thunder levels last sent to the client.
+    sent_weather_levels: (f32, f32),
     bits: ClientBits,
     /// The data for the client's own player entity.
     player_data: Player,
@@ -249,7 +258,8 @@ struct ClientBits {
     velocity_modified: bool,
     created_this_tick: bool,
     view_distance_modified: bool,
-    #[bits(5)]
+    time_override_modified: bool,
+    #[bits(4)]
     _pad: u8,
 }
 
@@ -294,6 +304,9 @@ impl<C: Config> Client<C> {
             bar_to_send: None,
             attack_speed: 4.0,
             movement_speed: 0.7,
+            time_override: None,
+            weather_override: None,
+            sent_weather_levels: (0.0, 0.0),
             bits: ClientBits::new()
                 .with_modified_spawn_position(true)
                 .with_got_keepalive(true)
@@ -379,6 +392,15 @@ impl<C: Config> Client<C> {
         self.position
     }
 
+    /// Returns `true` if this client is outside the
+    /// [world border](crate::world::WorldBorder) of the world it is located
+    /// in. If the client's world is invalid, `false` is returned.
+    pub fn is_outside_world_border(&self, worlds: &Worlds<C>) -> bool {
+        worlds
+            .get(self.world)
+            .is_some_and(|w| w.border.is_outside(self.position))
+    }
+
     /// Changes the position and rotation of this client in the world it is
     /// located in.
     ///
@@ -462,22 +484,27 @@ impl<C: Config> Client<C> {
     }
 
     /// Plays a sound to the client at a given position.
+    ///
+    /// `sound` is either a [`Sound`](crate::sound::Sound) or the name of a
+    /// custom sound from a resource pack. To play a sound for every client
+    /// nearby, use [`World::play_sound`](crate::world::World::play_sound).
     pub fn play_sound(
         &mut self,
-        name: Ident,
+        sound: impl Into<Ident>,
         category: SoundCategory,
         pos: Vec3<f64>,
         volume: f32,
         pitch: f32,
     ) {
-        self.send_packet(PlaySoundId {
-            name,
-            category,
-            position: pos.iter().map(|x| *x as i32 * 8).collect(),
-            volume,
-            pitch,
-            seed: 0,
-        });
+        self.send_packet(sound_packet(sound.into(), category, pos, volume, pitch));
+    }
+
+    /// Stops sounds playing for the client.
+    ///
+    /// If `category` or `sound` is given, only the sounds matching it are
+    /// stopped.
+    pub fn stop_sound(&mut self, category: Option<SoundCategory>, sound: Option<Ident>) {
+        self.send_packet(StopSound { category, sound });
     }
 
     /// Sets the title this client sees.
@@ -539,6 +566,65 @@ impl<C: Config> Client<C> {
         }
     }
 
+    /// Gets the time of day shown to this client instead of the time of its
+    /// world, if any.
+    pub fn time_override(&self) -> Option<i64> {
+        self.time_override
+    }
+
+    /// Sets a fixed time of day to show to this client instead of the
+    /// [time of its world](crate::world::WorldMeta::time_of_day). Use `None`
+    /// to show the world's time again.
+    ///
+    /// This only affects how the sky looks to this client.
+    pub fn set_time_override(&mut self, time: Option<i64>) {
+        if self.time_override != time {
+            self.time_override = time;
+            self.bits.set_time_override_modified(true);
+        }
+    }
+
+    /// Gets the weather shown to this client instead of the weather of its
+    /// world, if any.
+    pub fn weather_override(&self) -> Option<Weather> {
+        self.weather_override
+    }
+
+    /// Sets the weather to show to this client instead of the
+    /// [weather of its world](crate::world::WorldMeta::weather). Use `None` to
+    /// show the world's weather again.
+    ///
+    /// The overridden weather is shown without a transition.
+    pub fn set_weather_override(&mut self, weather: Option<Weather>) {
+        self.weather_override = weather;
+    }
+
+    /// Spawns `count` particles at `position` for this client only.
+    ///
+    /// See [`World::spawn_particle`] for the meaning of the arguments. Unlike
+    /// the world's method, the particles are sent regardless of the distance
+    /// to the client.
+    ///
+    /// [`World::spawn_particle`]: crate::world::World::spawn_particle
+    pub fn spawn_particle(
+        &mut self,
+        particle: &Particle,
+        long_distance: bool,
+        position: impl Into<Vec3<f64>>,
+        offset: impl Into<Vec3<f32>>,
+        max_speed: f32,
+        count: i32,
+    ) {
+        self.send_packet(particle_packet(
+            particle,
+            long_distance,
+            position.into(),
+            offset.into(),
+            max_speed,
+            count,
+        ));
+    }
+
     /// Removes the current title from the client's screen.
     pub fn clear_title(&mut self) {
         self.send_packet(ClearTitles { reset: true });
@@ -904,7 +990,7 @@ impl<C: Config> Client<C> {
         player_lists: &PlayerLists<C>,
     ) {
         // Mark the client as disconnected when appropriate.
-        if self.recv.is_disconnected() || self.send.as_ref().map_or(true, |s| s.is_disconnected()) {
+        if self.recv.is_disconnected() || self.send.as_ref().is_none_or(|s| s.is_disconnected()) {
             self.send = None;
             return;
         }
@@ -923,6 +1009,9 @@ impl<C: Config> Client<C> {
 
         let current_tick = shared.current_tick();
 
+        // If the client is joining a world this tick.
+        let joined_world = self.created_this_tick() || self.bits.spawn();
+
         // Send the join game packet and other initial packets. We defer this until now
         // so that the user can set the client's initial location, game mode, etc.
         if self.created_this_tick() {
@@ -969,6 +1058,8 @@ impl<C: Config> Client<C> {
                     .map(|(id, pos)| (ident!("{LIBRARY_NAMESPACE}:dimension_{}", id.0), pos)),
             });
 
+            self.send_packet(shared.tags_packet().clone());
+
             self.teleport(self.position(), self.yaw(), self.pitch());
         } else {
             if self.bits.spawn() {
@@ -1047,6 +1138,127 @@ impl<C: Config> Client<C> {
             }
         }
 
+        // Update the time of day. Clients advance the time on their own, so it
+        // is only synchronized once per second unless it advances at a different
+        // rate.
+        if joined_world
+            || world.meta.time_modified()
+            || self.bits.time_override_modified()
+            || !matches!(world.meta.time_rate(), 0 | 1)
+            || current_tick % shared.tick_rate() == 0
+        {
+            self.bits.set_time_override_modified(false);
+
+            let time_of_day = match self.time_override {
+                Some(time) => fixed_time_of_day(time),
+                None if world.meta.time_rate() == 0 => fixed_time_of_day(world.meta.time_of_day()),
+                None => world.meta.time_of_day(),
+            };
+
+            self.send_packet(WorldTimeUpdate {
+                world_age: world.meta.world_age(),
+                time_of_day,
+            });
+        }
+
+        // Update the world border.
+        {
+            let border = &world.border;
+            let modified = border.modified();
+
+            // Converts a duration in ticks to milliseconds.
+            let lerp_millis = || VarLong(border.remaining_lerp_ticks() * 1000 / shared.tick_rate());
+
+            if joined_world {
+                self.send_packet(WorldBorderInitialize {
+                    x: border.center().x,
+                    z: border.center().y,
+                    old_diameter: border.diameter(),
+                    new_diameter: border.target_diameter(),
+                    speed: lerp_millis(),
+                    portal_teleport_boundary: VarInt(PORTAL_TELEPORT_BOUNDARY),
+                    warning_blocks: VarInt(border.warning_blocks()),
+                    warning_time: VarInt(border.warning_time()),
+                });
+            } else {
+                if modified.center {
+                    self.send_packet(WorldBorderCenterChanged {
+                        x: border.center().x,
+                        z: border.center().y,
+                    });
+                }
+
+                if modified.diameter {
+                    if border.remaining_lerp_ticks() > 0 {
+                        self.send_packet(WorldBorderInterpolateSize {
+                            old_diameter: border.diameter(),
+                            new_diameter: border.target_diameter(),
+                            speed: lerp_millis(),
+                        });
+                    } else {
+                        self.send_packet(WorldBorderSizeChanged {
+                            diameter: border.diameter(),
+                        });
+                    }
+                }
+
+                if modified.warning_time {
+                    self.send_packet(WorldBorderWarningTimeChanged {
+                        warning_time: VarInt(border.warning_time()),
+                    });
+                }
+
+                if modified.warning_blocks {
+                    self.send_packet(WorldBorderWarningBlocksChanged {
+                        warning_blocks: VarInt(border.warning_blocks()),
+                    });
+                }
+            }
+        }
+
+        // Update the weather. Clients start out with clear weather after joining
+        // a world.
+        {
+            if joined_world {
+                self.sent_weather_levels = (0.0, 0.0);
+            }
+
+            let (old_rain, old_thunder) = self.sent_weather_levels;
+
+            let (rain, thunder) = match self.weather_override {
+                Some(weather) => weather.levels(),
+                None => (world.meta.rain_level(), world.meta.thunder_level()),
+            };
+
+            if rain != old_rain {
+                if old_rain == 0.0 {
+                    self.send_packet(GameStateChange {
+                        reason: GameStateChangeReason::BeginRaining,
+                        value: 0.0,
+                    });
+                } else if rain == 0.0 {
+                    self.send_packet(GameStateChange {
+                        reason: GameStateChangeReason::EndRaining,
+                        value: 0.0,
+                    });
+                }
+
+                self.send_packet(GameStateChange {
+                    reason: GameStateChangeReason::RainLevelChange,
+                    value: rain,
+                });
+            }
+
+            if thunder != old_thunder {
+                self.send_packet(GameStateChange {
+                    reason: GameStateChangeReason::ThunderLevelChange,
+                    value: thunder,
+                });
+            }
+
+            self.sent_weather_levels = (rain, thunder);
+        }
+
         // Set player attributes
         if self.bits.attack_speed_modified() {
             self.bits.set_attack_speed_modified(false);
@@ -1158,15 +1370,58 @@ impl<C: Config> Client<C> {
         });
 
         // Load new chunks within the view distance
+        let biome_registry_len = shared.biomes().len();
         for pos in chunks_in_view_distance(center, self.view_distance) {
             if let Some(chunk) = world.chunks.get(pos) {
                 if self.loaded_chunks.insert(pos) {
-                    self.send_packet(chunk.chunk_data_packet(pos));
+                    self.send_packet(chunk.chunk_data_packet(pos, biome_registry_len));
                     chunk.block_change_packets(pos, dimension.min_y, |pkt| self.send_packet(pkt));
                 }
             }
         }
 
+        // Send particles and other effects near the client.
+        for pkt in world.packets_near(self.position) {
+            send_packet(&mut self.send, pkt.clone());
+        }
+
+        // Send explosions near the client along with the knockback they cause.
+        for explosion in &world.explosions {
+            if explosion.position.distance_squared(self.position)
+                >= EXPLOSION_RANGE * EXPLOSION_RANGE
+            {
+                continue;
+            }
+
+            let player_motion = if self.game_mode() == GameMode::Spectator {
+                Vec3::zero()
+            } else {
+                explosion.player_knockback(self.position, &world.chunks)
+            };
+
+            let origin = BlockPos::at(explosion.position);
+
+            send_packet(
+                &mut self.send,
+                ExplosionS2c {
+                    position: explosion.position,
+                    strength: explosion.power,
+                    records: explosion
+                        .blocks
+                        .iter()
+                        .map(|b| {
+                            [
+                                (b.x - origin.x) as i8,
+                                (b.y - origin.y) as i8,
+                                (b.z - origin.z) as i8,
+                            ]
+                        })
+                        .collect(),
+                    player_motion: player_motion.as_(),
+                },
+            );
+        }
+
         // Acknowledge broken blocks.
         for seq in self.dug_blocks.drain(..) {
             send_packet(
@@ -1389,6 +1644,16 @@ impl<C: Config> Client<C> {
 
 type SendOpt = Option<Sender<S2cPlayMessage>>;
 
+/// Encodes a time of day that should not be advanced by the client. Clients
+/// stop the day/night cycle when the time is negative.
+fn fixed_time_of_day(time: i64) -> i64 {
+    match time.rem_euclid(24000) {
+        // Zero cannot be negated, so a full day later is used instead.
+        0 => -24000,
+        time => -time,
+    }
+}
+
 fn send_packet(send_opt: &mut SendOpt, pkt: impl Into<S2cPlayMessage>) {
     if let Some(send) = send_opt {
         match send.try_send(pkt.into()) {
@@ -1466,3 +1731,19 @@ fn make_registry_codec<C: Config>(shared: &SharedServer<C>) -> RegistryCodec {
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_time_of_day_is_negative() {
+        assert_eq!(fixed_time_of_day(6000), -6000);
+        assert_eq!(fixed_time_of_day(30000), -6000);
+        assert_eq!(fixed_time_of_day(-6000), -18000);
+        assert_eq!(fixed_time_of_day(0), -24000);
+        assert_eq!(fixed_time_of_day(48000), -24000);
+        assert!((-24000..0).contains(&fixed_time_of_day(i64::MIN)));
+        assert!((-24000..0).contains(&fixed_time_of_day(i64::MAX)));
+    }
+}
@@ -9,6 +9,7 @@ use tokio::runtime::Handle as TokioHandle;
 use crate::biome::Biome;
 use crate::dimension::Dimension;
 use crate::server::{NewClientData, Server, SharedServer};
+use crate::tag::Tag;
 use crate::text::Text;
 use crate::{Ticks, STANDARD_TPS};
 
@@ -132,6 +133,18 @@ pub trait Config: Sized + Send + Sync + UnwindSafe + RefUnwindSafe + 'static {
         None
     }
 
+    /// Called once at startup to get the number of worker threads used to
+    /// generate and load chunks with
+    /// [`ChunkProvider`](crate::chunk::ChunkProvider)s.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns the number of logical CPUs available, or 1 if that could not be
+    /// determined.
+    fn chunk_worker_threads(&self) -> usize {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    }
+
     /// Called once at startup to get the list of [`Dimension`]s usable on the
     /// server.
     ///
@@ -166,6 +179,21 @@ pub trait Config: Sized + Send + Sync + UnwindSafe + RefUnwindSafe + 'static {
         vec![Biome::default()]
     }
 
+    /// Called once at startup to get the custom [`Tag`]s sent to clients
+    /// along with the vanilla tags.
+    ///
+    /// A custom tag with the same name as a vanilla tag of the same registry
+    /// replaces the vanilla tag on clients. The vanilla tag queries such as
+    /// [`BlockKind::has_tag`](crate::block::BlockKind::has_tag) are not
+    /// affected.
+    ///
+    /// # Default Implementation
+    ///
+    /// Returns an empty `Vec`.
+    fn tags(&self) -> Vec<Tag> {
+        vec![]
+    }
+
     /// Called when the server receives a Server List Ping query.
     /// Data for the response can be provided or the query can be ignored.
     ///
@@ -228,6 +256,7 @@ pub trait Config: Sized + Send + Sync + UnwindSafe + RefUnwindSafe + 'static {
 
 /// The result of the [`server_list_ping`](Config::server_list_ping) callback.
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ServerListPing<'a> {
     /// Responds to the server list ping with the given information.
     Respond {
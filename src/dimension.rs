@@ -56,6 +56,9 @@ pub struct Dimension {
     /// * `0 <= height <= 4064`
     /// * `min_y + height <= 2032`
     pub height: i32,
+    /// When true, lava flows faster and further. See
+    /// [`Chunks::enable_fluids`](crate::chunk::Chunks::enable_fluids).
+    pub ultrawarm: bool,
     // TODO: The following fields should be added if they can affect the
     // appearance of the dimension to clients.
     // * infiniburn
@@ -67,7 +70,6 @@ pub struct Dimension {
     // * has_raids
     // * logical_height
     // * coordinate_scale
-    // * ultrawarm
     // * has_ceiling
 }
 
@@ -94,7 +96,7 @@ impl Dimension {
             height: self.height,
             logical_height: self.height,
             coordinate_scale: 1.0,
-            ultrawarm: false,
+            ultrawarm: self.ultrawarm,
             has_ceiling: false,
         }
     }
@@ -109,6 +111,7 @@ impl Default for Dimension {
             effects: DimensionEffects::default(),
             min_y: -64,
             height: 384,
+            ultrawarm: false,
         }
     }
 }
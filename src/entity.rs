@@ -7,6 +7,7 @@ use std::num::NonZeroU32;
 
 use bitfield_struct::bitfield;
 pub use data::{EntityKind, TrackedData};
+pub use physics::Physics;
 use rayon::iter::ParallelIterator;
 use uuid::Uuid;
 use vek::{Aabb, Vec3};
@@ -19,9 +20,12 @@ use crate::protocol::{ByteAngle, RawBytes, VarInt};
 use crate::slab_versioned::{Key, VersionedSlab};
 use crate::util::aabb_from_bottom_and_size;
 use crate::world::WorldId;
-use crate::STANDARD_TPS;
+use crate::{Ticks, STANDARD_TPS};
 
 pub mod data;
+mod falling_block;
+mod lightning;
+mod physics;
 pub mod types;
 
 include!(concat!(env!("OUT_DIR"), "/entity_event.rs"));
@@ -40,6 +44,8 @@ pub struct Entities<C: Config> {
     slab: VersionedSlab<Entity<C>>,
     uuid_to_entity: HashMap<Uuid, EntityId>,
     network_id_to_entity: HashMap<NonZeroU32, u32>,
+    /// Lightning bolts and the number of ticks until they are removed.
+    lightning: Vec<(EntityId, Ticks)>,
 }
 
 impl<C: Config> Entities<C> {
@@ -48,6 +54,7 @@ impl<C: Config> Entities<C> {
             slab: VersionedSlab::new(),
             uuid_to_entity: HashMap::new(),
             network_id_to_entity: HashMap::new(),
+            lightning: Vec::new(),
         }
     }
 
@@ -88,6 +95,8 @@ impl<C: Config> Entities<C> {
                     pitch: 0.0,
                     head_yaw: 0.0,
                     velocity: Vec3::default(),
+                    physics: None,
+                    spawn_data: 1,
                     uuid,
                 });
 
@@ -210,6 +219,8 @@ impl<C: Config> Entities<C> {
     }
 
     pub(crate) fn update(&mut self) {
+        self.update_lightning();
+
         for (_, e) in self.iter_mut() {
             e.old_position = e.new_position;
             e.variants.clear_modifications();
@@ -265,6 +276,8 @@ pub struct Entity<C: Config> {
     pitch: f32,
     head_yaw: f32,
     velocity: Vec3<f32>,
+    physics: Option<Physics>,
+    spawn_data: i32,
     uuid: Uuid,
 }
 
@@ -397,6 +410,47 @@ impl<C: Config> Entity<C> {
         }
     }
 
+    /// Gets the physics constants of this entity, or `None` if physics is
+    /// disabled.
+    pub fn physics(&self) -> Option<Physics> {
+        self.physics
+    }
+
+    /// Enables or disables server-side physics for this entity.
+    ///
+    /// Entities with physics enabled are moved according to their velocity at
+    /// the end of every tick, after [`Config::update`] is called. Gravity and
+    /// drag are applied to the velocity, the entity collides with the blocks
+    /// in its world, and the "on ground" flag is kept up to date. Clients are
+    /// sent the new position as usual, but are not sent the changes in
+    /// velocity made by the simulation.
+    ///
+    /// Physics is disabled by default. Use [`Physics::vanilla`] to get the
+    /// constants vanilla uses for this entity's kind.
+    ///
+    /// [`Config::update`]: crate::config::Config::update
+    pub fn set_physics(&mut self, physics: Option<Physics>) {
+        self.physics = physics;
+    }
+
+    /// Gets the extra data sent to clients when this entity is spawned.
+    pub fn spawn_data(&self) -> i32 {
+        self.spawn_data
+    }
+
+    /// Sets the extra data sent to clients when this entity is spawned.
+    ///
+    /// The meaning of the value depends on the kind of entity. For falling
+    /// blocks, it is the raw ID of the [`BlockState`] being displayed. For
+    /// item frames and paintings, it is the direction the entity is facing.
+    /// The new value is only sent to clients that have not loaded the entity
+    /// yet. The default value is `1`.
+    ///
+    /// [`BlockState`]: crate::block::BlockState
+    pub fn set_spawn_data(&mut self, data: i32) {
+        self.spawn_data = data;
+    }
+
     /// Gets the value of the "on ground" flag.
     pub fn on_ground(&self) -> bool {
         self.bits.on_ground()
@@ -621,7 +675,7 @@ impl<C: Config> Entity<C> {
                 pitch: ByteAngle::from_degrees(self.pitch),
                 yaw: ByteAngle::from_degrees(self.yaw),
                 head_yaw: ByteAngle::from_degrees(self.head_yaw),
-                data: VarInt(1), // TODO
+                data: VarInt(self.spawn_data),
                 velocity: velocity_to_packet_units(self.velocity),
             })),
         }
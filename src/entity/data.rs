@@ -4,6 +4,7 @@
 
 use crate::block::{BlockPos, BlockState};
 use crate::entity::types::*;
+use crate::item::ItemStack;
 use crate::protocol::{Encode, VarInt};
 use crate::text::Text;
 use crate::uuid::Uuid;
@@ -0,0 +1,75 @@
+use vek::Vec3;
+
+use crate::block::{BlockKind, BlockPos, BlockState};
+use crate::chunk::is_concrete_powder;
+use crate::config::Config;
+use crate::entity::{Entities, EntityKind, Physics, TrackedData};
+use crate::world::Worlds;
+
+impl<C: Config> Entities<C> {
+    /// Spawns [`FallingBlock`] entities for the blocks that started falling in
+    /// worlds with [falling blocks enabled], and turns falling blocks that
+    /// have landed back into blocks.
+    ///
+    /// This is called every tick after [`Config::update`].
+    ///
+    /// The falling block entities have [physics] enabled and carry the raw ID
+    /// of their block state in their [spawn data]. A falling block is
+    /// destroyed if it lands in a space occupied by another block or leaves
+    /// the loaded chunks.
+    ///
+    /// [`FallingBlock`]: EntityKind::FallingBlock
+    /// [falling blocks enabled]: crate::chunk::Chunks::enable_falling_blocks
+    /// [`Config::update`]: crate::config::Config::update
+    /// [physics]: crate::entity::Entity::set_physics
+    /// [spawn data]: crate::entity::Entity::spawn_data
+    pub(crate) fn update_falling_blocks(&mut self, worlds: &mut Worlds<C>) {
+        self.retain(|_, e| {
+            if e.kind() != EntityKind::FallingBlock {
+                return true;
+            }
+
+            let Some(world) = worlds.get_mut(e.world()) else {
+                return true;
+            };
+
+            let Some(block) = BlockState::from_raw(e.spawn_data() as u16) else {
+                return true;
+            };
+
+            // The position is at the bottom of the hitbox, which can be
+            // slightly below the surface the entity landed on.
+            let pos = BlockPos::at(e.position() + Vec3::new(0.0, 0.5, 0.0));
+
+            let Some(current) = world.chunks.get_block_state(pos) else {
+                return false;
+            };
+
+            // Concrete powder stops falling in water.
+            let in_water =
+                current.to_kind() == BlockKind::Water && is_concrete_powder(block.to_kind());
+
+            if e.on_ground() || in_water {
+                world.chunks.land_falling_block(pos, block);
+                return false;
+            }
+
+            true
+        });
+
+        for (id, world) in worlds.iter_mut() {
+            for (pos, block, state) in world.chunks.take_falling_blocks() {
+                let (_, e) = self.insert(EntityKind::FallingBlock, state);
+
+                e.set_world(id);
+                e.set_position([pos.x as f64 + 0.5, pos.y as f64, pos.z as f64 + 0.5]);
+                e.set_spawn_data(block.to_raw() as i32);
+                e.set_physics(Some(Physics::vanilla(EntityKind::FallingBlock)));
+
+                if let TrackedData::FallingBlock(fb) = e.data_mut() {
+                    fb.set_block_pos(pos);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use vek::Vec3;
+
+use crate::config::Config;
+use crate::entity::{Entities, EntityId, EntityKind};
+use crate::world::WorldId;
+use crate::Ticks;
+
+/// The number of ticks a lightning bolt exists for before it is removed.
+pub(super) const LIGHTNING_LIFETIME: Ticks = 20;
+
+impl<C: Config> Entities<C> {
+    /// Spawns a [`Lightning`] bolt at `position` in `world`.
+    ///
+    /// The lightning bolt is purely visual and does not set fire to blocks or
+    /// damage entities. It is removed automatically after one second.
+    ///
+    /// [`Lightning`]: EntityKind::Lightning
+    pub fn spawn_lightning(
+        &mut self,
+        world: WorldId,
+        position: impl Into<Vec3<f64>>,
+        state: C::EntityState,
+    ) -> EntityId {
+        let (id, e) = self.insert(EntityKind::Lightning, state);
+
+        e.set_world(world);
+        e.set_position(position);
+
+        self.lightning.push((id, LIGHTNING_LIFETIME));
+
+        id
+    }
+
+    /// Counts down the remaining lifetime of lightning bolts and removes the
+    /// expired ones.
+    pub(super) fn update_lightning(&mut self) {
+        let mut lightning = std::mem::take(&mut self.lightning);
+
+        lightning.retain_mut(|(id, ticks)| {
+            *ticks -= 1;
+
+            if *ticks <= 0 {
+                self.remove(*id);
+                false
+            } else {
+                self.get(*id).is_some()
+            }
+        });
+
+        self.lightning = lightning;
+    }
+}
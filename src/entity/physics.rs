@@ -0,0 +1,287 @@
+//! Server-side entity movement.
+
+use rayon::iter::ParallelIterator;
+use vek::{Aabb, Vec3};
+
+use crate::block::{BlockKind, BlockPos, BlockState};
+use crate::chunk::MoveResult;
+use crate::config::Config;
+use crate::entity::{Entities, EntityKind};
+use crate::world::Worlds;
+use crate::STANDARD_TPS;
+
+/// The constants used to simulate the movement of an entity with physics
+/// enabled. See [`Entity::set_physics`].
+///
+/// All values are in blocks per tick, which is how they are defined in the
+/// vanilla game.
+///
+/// [`Entity::set_physics`]: crate::entity::Entity::set_physics
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Physics {
+    /// The downward acceleration applied every tick.
+    pub gravity: f64,
+    /// The factor vertical velocity is multiplied by every tick.
+    pub drag: f64,
+    /// The factor horizontal velocity is multiplied by every tick in addition
+    /// to the friction. Items and projectiles use their drag here, while mobs
+    /// are only slowed horizontally by friction.
+    pub horizontal_drag: f64,
+    /// The factor horizontal velocity is additionally multiplied by every tick
+    /// while in the air. On the ground, the slipperiness of the block below
+    /// the entity is used instead.
+    pub air_friction: f64,
+    /// The height of the blocks the entity can walk up without jumping.
+    pub step_height: f64,
+    /// If gravity is applied before the entity is moved rather than after.
+    /// Items and projectiles do this, while mobs do not.
+    pub gravity_before_move: bool,
+}
+
+impl Physics {
+    /// Returns the vanilla physics constants for the given kind of entity.
+    ///
+    /// Entities that are not affected by gravity in vanilla (such as
+    /// fireballs) have a gravity of zero. Flying mobs use the same
+    /// constants as other mobs.
+    pub fn vanilla(kind: EntityKind) -> Self {
+        let projectile = |gravity, drag| Self {
+            gravity,
+            drag,
+            horizontal_drag: drag,
+            air_friction: 1.0,
+            step_height: 0.0,
+            gravity_before_move: true,
+        };
+
+        match kind {
+            EntityKind::Item | EntityKind::FallingBlock | EntityKind::Tnt => projectile(0.04, 0.98),
+            EntityKind::ExperienceOrb => projectile(0.03, 0.98),
+            EntityKind::Arrow | EntityKind::SpectralArrow | EntityKind::Trident => {
+                projectile(0.05, 0.99)
+            }
+            EntityKind::Snowball | EntityKind::Egg | EntityKind::EnderPearl => {
+                projectile(0.03, 0.99)
+            }
+            EntityKind::Potion => projectile(0.05, 0.99),
+            EntityKind::ExperienceBottle => projectile(0.07, 0.99),
+            EntityKind::LlamaSpit => projectile(0.06, 0.99),
+            EntityKind::FishingBobber => projectile(0.03, 0.92),
+            EntityKind::Minecart
+            | EntityKind::ChestMinecart
+            | EntityKind::CommandBlockMinecart
+            | EntityKind::FurnaceMinecart
+            | EntityKind::HopperMinecart
+            | EntityKind::SpawnerMinecart
+            | EntityKind::TntMinecart => projectile(0.04, 0.95),
+            EntityKind::Boat | EntityKind::ChestBoat => projectile(0.04, 0.9),
+            EntityKind::Fireball
+            | EntityKind::SmallFireball
+            | EntityKind::DragonFireball
+            | EntityKind::WitherSkull
+            | EntityKind::ShulkerBullet
+            | EntityKind::FireworkRocket
+            | EntityKind::EyeOfEnder
+            | EntityKind::AreaEffectCloud
+            | EntityKind::EndCrystal
+            | EntityKind::EvokerFangs
+            | EntityKind::Lightning
+            | EntityKind::Marker
+            | EntityKind::Painting
+            | EntityKind::ItemFrame
+            | EntityKind::GlowItemFrame
+            | EntityKind::LeashKnot => projectile(0.0, 1.0),
+            // Living entities.
+            _ => Self {
+                gravity: 0.08,
+                drag: 0.98,
+                horizontal_drag: 1.0,
+                air_friction: 0.91,
+                step_height: 0.6,
+                gravity_before_move: false,
+            },
+        }
+    }
+}
+
+impl<C: Config> Entities<C> {
+    /// Moves every entity with physics enabled according to its velocity.
+    pub(crate) fn update_physics(&mut self, worlds: &Worlds<C>) {
+        self.par_iter_mut().for_each(|(_, e)| {
+            let Some(physics) = e.physics else {
+                return;
+            };
+
+            let Some(world) = worlds.get(e.world()) else {
+                return;
+            };
+
+            // Velocity is stored in meters per second.
+            let velocity = e.velocity().as_::<f64>() / STANDARD_TPS as f64;
+
+            let (movement, velocity, on_ground) = step(
+                &physics,
+                e.hitbox(),
+                velocity,
+                |bb, movement, step_height| world.chunks.move_and_slide(bb, movement, step_height),
+                |pos| world.chunks.get_block_state(pos),
+            );
+
+            e.set_position(e.position() + movement);
+            e.velocity = (velocity * STANDARD_TPS as f64).as_();
+            e.set_on_ground(on_ground);
+        });
+    }
+}
+
+/// Simulates one tick of movement for a bounding box. Returns the offset to
+/// move by, the new velocity and the new value of the "on ground" flag.
+fn step(
+    physics: &Physics,
+    bb: Aabb<f64>,
+    mut velocity: Vec3<f64>,
+    move_and_slide: impl FnOnce(Aabb<f64>, Vec3<f64>, f64) -> MoveResult,
+    get_block: impl FnOnce(BlockPos) -> Option<BlockState>,
+) -> (Vec3<f64>, Vec3<f64>, bool) {
+    if physics.gravity_before_move {
+        velocity.y -= physics.gravity;
+    }
+
+    let res = move_and_slide(bb, velocity, physics.step_height);
+
+    // Stop moving along the axes that were obstructed.
+    if res.movement.x != velocity.x {
+        velocity.x = 0.0;
+    }
+    if res.movement.y != velocity.y {
+        velocity.y = 0.0;
+    }
+    if res.movement.z != velocity.z {
+        velocity.z = 0.0;
+    }
+
+    if !physics.gravity_before_move {
+        velocity.y -= physics.gravity;
+    }
+
+    let friction = if res.on_ground {
+        // The block that affects movement is slightly below the entity.
+        let below = BlockPos::at([
+            (bb.min.x + bb.max.x) / 2.0 + res.movement.x,
+            bb.min.y + res.movement.y - 0.5,
+            (bb.min.z + bb.max.z) / 2.0 + res.movement.z,
+        ]);
+        get_block(below).map_or(0.6, |b| slipperiness(b.to_kind())) * physics.air_friction
+    } else {
+        physics.air_friction
+    };
+
+    velocity.y *= physics.drag;
+    velocity.x *= physics.horizontal_drag * friction;
+    velocity.z *= physics.horizontal_drag * friction;
+
+    (res.movement, velocity, res.on_ground)
+}
+
+/// Returns how slippery a block is to walk on.
+fn slipperiness(kind: BlockKind) -> f64 {
+    match kind {
+        BlockKind::Ice | BlockKind::PackedIce | BlockKind::FrostedIce => 0.98,
+        BlockKind::BlueIce => 0.989,
+        BlockKind::SlimeBlock => 0.8,
+        _ => 0.6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn unobstructed(_: Aabb<f64>, movement: Vec3<f64>, _: f64) -> MoveResult {
+        MoveResult {
+            movement,
+            horizontal_collision: false,
+            vertical_collision: false,
+            on_ground: false,
+        }
+    }
+
+    fn floor(_: Aabb<f64>, movement: Vec3<f64>, _: f64) -> MoveResult {
+        MoveResult {
+            movement: Vec3::new(movement.x, 0.0, movement.z),
+            horizontal_collision: false,
+            vertical_collision: true,
+            on_ground: true,
+        }
+    }
+
+    fn bb() -> Aabb<f64> {
+        Aabb {
+            min: Vec3::new(-0.3, 1.0, -0.3),
+            max: Vec3::new(0.3, 2.8, 0.3),
+        }
+    }
+
+    #[test]
+    fn falling_mob() {
+        let physics = Physics::vanilla(EntityKind::Zombie);
+
+        let mut velocity = Vec3::zero();
+        let mut y = 0.0;
+
+        for _ in 0..3 {
+            let (movement, new_velocity, on_ground) =
+                step(&physics, bb(), velocity, unobstructed, |_| None);
+            assert!(!on_ground);
+            y += movement.y;
+            velocity = new_velocity;
+        }
+
+        // Vanilla values for a mob falling from rest.
+        assert_relative_eq!(y, -0.0784 - 0.155232, epsilon = 1e-9);
+        assert_relative_eq!(velocity.y, -0.2305, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn friction_on_ground() {
+        let physics = Physics::vanilla(EntityKind::Item);
+        let velocity = Vec3::new(1.0, 0.0, 0.0);
+
+        let (movement, velocity_stone, on_ground) =
+            step(&physics, bb(), velocity, floor, |_| Some(BlockState::STONE));
+        assert!(on_ground);
+        assert_relative_eq!(movement.x, 1.0);
+        assert_relative_eq!(velocity_stone.y, 0.0);
+        assert_relative_eq!(velocity_stone.x, 0.98 * 0.6);
+
+        let (_, velocity_ice, _) = step(&physics, bb(), velocity, floor, |_| Some(BlockState::ICE));
+        assert!(velocity_ice.x > velocity_stone.x);
+    }
+
+    #[test]
+    fn mob_horizontal_decay() {
+        let physics = Physics::vanilla(EntityKind::Zombie);
+        let velocity = Vec3::new(1.0, 0.0, 1.0);
+
+        // Vanilla multiplies the horizontal velocity of mobs by 0.91 in the air
+        // and by the slipperiness times 0.91 on the ground, without the
+        // vertical drag.
+        let (_, in_air, _) = step(&physics, bb(), velocity, unobstructed, |_| None);
+        assert_relative_eq!(in_air.x, 0.91);
+        assert_relative_eq!(in_air.z, 0.91);
+
+        let (_, on_stone, _) = step(&physics, bb(), velocity, floor, |_| Some(BlockState::STONE));
+        assert_relative_eq!(on_stone.x, 0.6 * 0.91);
+
+        let (_, on_ice, _) = step(&physics, bb(), velocity, floor, |_| Some(BlockState::ICE));
+        assert_relative_eq!(on_ice.x, 0.98 * 0.91);
+
+        let mut velocity = velocity;
+        for _ in 0..10 {
+            velocity = step(&physics, bb(), velocity, unobstructed, |_| None).1;
+        }
+        assert_relative_eq!(velocity.x, 0.91f64.powi(10), epsilon = 1e-12);
+    }
+}
@@ -243,14 +243,4 @@ impl Encode for PaintingKind {
     }
 }
 
-// TODO
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub enum Particle {
-    EntityEffect = 21,
-}
-
-impl Encode for Particle {
-    fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
-        VarInt(*self as i32).encode(w)
-    }
-}
+pub use crate::particle::Particle;
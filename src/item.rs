@@ -0,0 +1,161 @@
+//! Items and item stacks.
+
+#![allow(clippy::all, missing_docs)]
+
+use std::io::{Read, Write};
+
+use anyhow::Context;
+
+use crate::block::BlockKind;
+use crate::nbt::Compound;
+use crate::protocol::{Decode, Encode, VarInt};
+use crate::text::Color;
+
+include!(concat!(env!("OUT_DIR"), "/item.rs"));
+
+/// The rarity of an [`ItemKind`], which determines the color of its name.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    /// Returns the color of the names of items with this rarity.
+    pub const fn color(self) -> Color {
+        match self {
+            Rarity::Common => Color::WHITE,
+            Rarity::Uncommon => Color::YELLOW,
+            Rarity::Rare => Color::AQUA,
+            Rarity::Epic => Color::LIGHT_PURPLE,
+        }
+    }
+}
+
+impl Encode for ItemKind {
+    fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        VarInt(self.to_raw() as i32).encode(w)
+    }
+}
+
+impl Decode for ItemKind {
+    fn decode(r: &mut impl Read) -> anyhow::Result<Self> {
+        let id = VarInt::decode(r)?.0;
+        let errmsg = "invalid item ID";
+
+        ItemKind::from_raw(id.try_into().context(errmsg)?).context(errmsg)
+    }
+}
+
+/// A stack of items in an inventory, on the ground or held by an entity.
+///
+/// In the protocol, an empty slot is written as `None` and a filled slot as
+/// `Some(stack)`, so `Option<ItemStack>` is the protocol's slot type.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ItemStack {
+    /// The kind of item in the stack.
+    pub item: ItemKind,
+    /// The number of items in the stack. Vanilla clients expect this to be
+    /// between 1 and the item's [`max_stack`](ItemKind::max_stack).
+    pub count: i8,
+    /// Extra data such as enchantments, custom names and damage.
+    pub nbt: Option<Compound>,
+}
+
+impl ItemStack {
+    /// Creates a new item stack without any NBT.
+    pub fn new(item: ItemKind, count: i8) -> Self {
+        Self {
+            item,
+            count,
+            nbt: None,
+        }
+    }
+}
+
+impl Encode for ItemStack {
+    fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        self.item.encode(w)?;
+        self.count.encode(w)?;
+
+        match &self.nbt {
+            Some(nbt) => nbt.encode(w),
+            // An end tag in place of the compound means there is no NBT.
+            None => 0u8.encode(w),
+        }
+    }
+}
+
+impl Decode for ItemStack {
+    fn decode(r: &mut impl Read) -> anyhow::Result<Self> {
+        let item = ItemKind::decode(r)?;
+        let count = i8::decode(r)?;
+
+        let nbt = match u8::decode(r)? {
+            0 => None,
+            tag => Some(Compound::decode(&mut [tag].as_slice().chain(r))?),
+        };
+
+        Ok(Self { item, count, nbt })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nbt::Value;
+
+    #[test]
+    fn item_kind_properties() {
+        for kind in ItemKind::ALL {
+            assert_eq!(ItemKind::from_raw(kind.to_raw()), Some(kind));
+            assert_eq!(ItemKind::from_str(kind.to_str()), Some(kind));
+
+            if let Some(block) = kind.to_block_kind() {
+                assert!(block.to_item_kind().is_some());
+            }
+        }
+
+        assert_eq!(ItemKind::Air.to_raw(), 0);
+        assert_eq!(ItemKind::DiamondPickaxe.max_stack(), 1);
+        assert_eq!(ItemKind::EnderPearl.max_stack(), 16);
+        assert_eq!(ItemKind::Stone.max_stack(), 64);
+        assert_eq!(ItemKind::Stone.translation_key(), "block.minecraft.stone");
+        assert_eq!(ItemKind::Apple.translation_key(), "item.minecraft.apple");
+        assert_eq!(ItemKind::EnchantedGoldenApple.rarity(), Rarity::Epic);
+        assert_eq!(ItemKind::Stick.rarity(), Rarity::Common);
+        assert_eq!(ItemKind::Stone.to_block_kind(), Some(BlockKind::Stone));
+        assert_eq!(ItemKind::WheatSeeds.to_block_kind(), Some(BlockKind::Wheat));
+        assert_eq!(ItemKind::Wheat.to_block_kind(), None);
+        assert_eq!(BlockKind::Carrots.to_item_kind(), Some(ItemKind::Carrot));
+        assert_eq!(BlockKind::Water.to_item_kind(), None);
+    }
+
+    #[test]
+    fn item_stack_slot_round_trip() {
+        let mut nbt = Compound::new();
+        nbt.insert("Damage".into(), Value::Int(5));
+
+        let slots = [
+            None,
+            Some(ItemStack::new(ItemKind::Stone, 64)),
+            Some(ItemStack {
+                item: ItemKind::DiamondSword,
+                count: 1,
+                nbt: Some(nbt),
+            }),
+        ];
+
+        for slot in slots {
+            let mut buf = Vec::new();
+            slot.encode(&mut buf).unwrap();
+
+            let mut r = buf.as_slice();
+            assert_eq!(Option::<ItemStack>::decode(&mut r).unwrap(), slot);
+            assert!(r.is_empty());
+        }
+    }
+}
@@ -100,16 +100,23 @@ pub mod config;
 pub mod dimension;
 pub mod entity;
 pub mod ident;
+pub mod item;
+pub mod particle;
 pub mod player_list;
 pub mod player_textures;
 #[allow(dead_code)]
 #[doc(hidden)]
 pub mod protocol;
+pub mod schematic;
 pub mod server;
 mod slab;
 mod slab_rc;
 mod slab_versioned;
+mod snbt;
+pub mod sound;
 pub mod spatial_index;
+pub mod structure;
+pub mod tag;
 pub mod text;
 pub mod util;
 pub mod world;
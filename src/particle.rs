@@ -0,0 +1,106 @@
+//! Particle effects.
+
+#![allow(clippy::all, missing_docs)]
+
+use std::io::Write;
+
+use vek::Vec3;
+
+use crate::block::{BlockPos, BlockState};
+use crate::entity::EntityId;
+use crate::ident;
+use crate::item::ItemStack;
+use crate::protocol::packets::s2c::play::ParticleS2c;
+use crate::protocol::{Encode, RawBytes, VarInt};
+
+include!(concat!(env!("OUT_DIR"), "/particle.rs"));
+
+impl Encode for Particle {
+    fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        VarInt(self.id()).encode(w)?;
+        self.encode_data(w)
+    }
+}
+
+/// The destination a [`Particle::Vibration`] travels to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VibrationDestination {
+    /// The center of a block.
+    Block(BlockPos),
+    /// An entity, offset vertically from its position by `y_offset`.
+    Entity { entity: EntityId, y_offset: f32 },
+}
+
+impl Encode for VibrationDestination {
+    fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        match self {
+            Self::Block(pos) => {
+                ident!("block").encode(w)?;
+                pos.encode(w)
+            }
+            Self::Entity { entity, y_offset } => {
+                ident!("entity").encode(w)?;
+                VarInt(entity.to_network_id()).encode(w)?;
+                y_offset.encode(w)
+            }
+        }
+    }
+}
+
+/// Creates the packet for spawning `count` particles at `position`.
+pub(crate) fn particle_packet(
+    particle: &Particle,
+    long_distance: bool,
+    position: Vec3<f64>,
+    offset: Vec3<f32>,
+    max_speed: f32,
+    count: i32,
+) -> ParticleS2c {
+    let mut data = Vec::new();
+    particle.encode_data(&mut data).unwrap();
+
+    ParticleS2c {
+        particle_id: VarInt(particle.id()),
+        long_distance,
+        position,
+        offset,
+        max_speed,
+        count,
+        data: RawBytes(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn particle_encoding() {
+        assert_eq!(Particle::EntityEffect.id(), 21);
+        assert_eq!(Particle::Shriek { delay: 0 }.name(), "shriek");
+
+        let mut buf = Vec::new();
+        Particle::Dust {
+            rgb: Vec3::new(1.0, 0.0, 0.0),
+            scale: 1.0,
+        }
+        .encode(&mut buf)
+        .unwrap();
+
+        // The ID followed by four floats.
+        assert_eq!(buf.len(), 1 + 4 * 4);
+        assert_eq!(buf[0], 14);
+
+        let pkt = particle_packet(
+            &Particle::Block(BlockState::STONE),
+            false,
+            Vec3::zero(),
+            Vec3::zero(),
+            0.0,
+            1,
+        );
+
+        assert_eq!(pkt.particle_id.0, 2);
+        assert_eq!(pkt.data.0, [1]);
+    }
+}
@@ -18,6 +18,7 @@ use vek::Vec3;
 // use {def_bitfield, def_enum, def_struct};
 use crate::block_pos::BlockPos;
 use crate::ident::Ident;
+use crate::item::ItemStack;
 use crate::nbt::Compound;
 use crate::protocol::{
     BoundedArray, BoundedInt, BoundedString, ByteAngle, Decode, Encode, NbtBridge, RawBytes,
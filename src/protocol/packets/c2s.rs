@@ -232,7 +232,25 @@ pub mod play {
 
     def_struct! {
         ClickSlot {
-            // TODO
+            window_id: u8,
+            state_id: VarInt,
+            slot_idx: i16,
+            button: i8,
+            mode: ClickSlotMode,
+            slots: Vec<(i16, Option<ItemStack>)>,
+            carried_item: Option<ItemStack>,
+        }
+    }
+
+    def_enum! {
+        ClickSlotMode: VarInt {
+            Click = 0,
+            ShiftClick = 1,
+            Hotbar = 2,
+            CreativeMiddleClick = 3,
+            DropKey = 4,
+            Drag = 5,
+            DoubleClick = 6,
         }
     }
 
@@ -565,7 +583,7 @@ pub mod play {
     def_struct! {
         UpdateCreativeModeSlot {
             slot: i16,
-            // TODO: clicked_item: Slot,
+            clicked_item: Option<ItemStack>,
         }
     }
 
@@ -228,6 +228,7 @@ pub mod play {
     }
 
     def_enum! {
+        #[derive(Copy, PartialEq, Eq)]
         SoundCategory: VarInt {
             Master = 0,
             Music = 1,
@@ -266,6 +267,18 @@ pub mod play {
         }
     }
 
+    def_struct! {
+        ExplosionS2c {
+            position: Vec3<f64>,
+            strength: f32,
+            /// Positions of the destroyed blocks relative to the block
+            /// containing `position`.
+            records: Vec<[i8; 3]>,
+            /// Velocity added to the receiving player, in blocks per tick.
+            player_motion: Vec3<f32>,
+        }
+    }
+
     def_struct! {
         UnloadChunk {
             chunk_x: i32,
@@ -283,8 +296,8 @@ pub mod play {
     def_enum! {
         GameStateChangeReason: u8 {
             NoRespawnBlockAvailable = 0,
-            EndRaining = 1,
-            BeginRaining = 2,
+            BeginRaining = 1,
+            EndRaining = 2,
             ChangeGameMode = 3,
             WinGame = 4,
             DemoEvent = 5,
@@ -316,6 +329,19 @@ pub mod play {
         }
     }
 
+    def_struct! {
+        ParticleS2c {
+            particle_id: VarInt,
+            long_distance: bool,
+            position: Vec3<f64>,
+            offset: Vec3<f32>,
+            max_speed: f32,
+            count: i32,
+            /// The extra data of the particle, which depends on its type.
+            data: RawBytes,
+        }
+    }
+
     def_struct! {
         ChunkData {
             chunk_x: i32,
@@ -661,6 +687,41 @@ pub mod play {
         }
     }
 
+    def_struct! {
+        WorldBorderCenterChanged {
+            x: f64,
+            z: f64,
+        }
+    }
+
+    def_struct! {
+        WorldBorderInterpolateSize {
+            old_diameter: f64,
+            new_diameter: f64,
+            /// The duration of the interpolation in milliseconds.
+            speed: VarLong,
+        }
+    }
+
+    def_struct! {
+        WorldBorderSizeChanged {
+            diameter: f64,
+        }
+    }
+
+    def_struct! {
+        WorldBorderWarningTimeChanged {
+            /// The warning time in seconds.
+            warning_time: VarInt,
+        }
+    }
+
+    def_struct! {
+        WorldBorderWarningBlocksChanged {
+            warning_blocks: VarInt,
+        }
+    }
+
     def_struct! {
         UpdateSelectedSlot {
             slot: BoundedInt<u8, 0, 9>,
@@ -742,7 +803,8 @@ pub mod play {
             category: SoundCategory,
             entity_id: VarInt,
             volume: f32,
-            pitch: f32
+            pitch: f32,
+            seed: i64
         }
     }
 
@@ -757,6 +819,50 @@ pub mod play {
         }
     }
 
+    /// Stops sounds with the given category and name, or all sounds if both
+    /// are `None`.
+    #[derive(Clone, Debug)]
+    pub struct StopSound {
+        pub category: Option<SoundCategory>,
+        pub sound: Option<Ident>,
+    }
+
+    impl Encode for StopSound {
+        fn encode(&self, w: &mut impl Write) -> anyhow::Result<()> {
+            let flags = self.category.is_some() as u8 | (self.sound.is_some() as u8) << 1;
+            flags.encode(w)?;
+
+            if let Some(category) = &self.category {
+                category.encode(w)?;
+            }
+
+            if let Some(sound) = &self.sound {
+                sound.encode(w)?;
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Decode for StopSound {
+        fn decode(r: &mut impl Read) -> anyhow::Result<Self> {
+            let flags = u8::decode(r)?;
+
+            Ok(Self {
+                category: if flags & 1 != 0 {
+                    Some(SoundCategory::decode(r)?)
+                } else {
+                    None
+                },
+                sound: if flags & 2 != 0 {
+                    Some(Ident::decode(r)?)
+                } else {
+                    None
+                },
+            })
+        }
+    }
+
     def_struct! {
         GameMessage {
             chat: Text,
@@ -805,6 +911,28 @@ pub mod play {
         }
     }
 
+    def_struct! {
+        UpdateTags {
+            groups: Vec<TagGroup>,
+        }
+    }
+
+    def_struct! {
+        TagGroup {
+            /// The registry the tags belong to, like `minecraft:block`.
+            registry: Ident,
+            tags: Vec<TagEntries>,
+        }
+    }
+
+    def_struct! {
+        TagEntries {
+            name: Ident,
+            /// Protocol IDs of the registry entries in the tag.
+            entries: Vec<VarInt>,
+        }
+    }
+
     def_packet_group! {
         S2cPlayPacket {
             EntitySpawn = 0,
@@ -821,10 +949,13 @@ pub mod play {
             PlaySoundId = 23,
             Disconnect = 25,
             EntityStatus = 26,
+            ExplosionS2c = 27,
             UnloadChunk = 28,
             GameStateChange = 29,
+            WorldBorderInitialize = 31,
             KeepAlive = 32,
             ChunkData = 33,
+            ParticleS2c = 35,
             GameJoin = 37,
             MoveRelative = 40,
             RotateAndMoveRelative = 41,
@@ -837,6 +968,11 @@ pub mod play {
             EntitySetHeadYaw = 63,
             ChunkSectionUpdate = 64,
             OverlayMessage = 67,
+            WorldBorderCenterChanged = 68,
+            WorldBorderInterpolateSize = 69,
+            WorldBorderSizeChanged = 70,
+            WorldBorderWarningTimeChanged = 71,
+            WorldBorderWarningBlocksChanged = 72,
             UpdateSelectedSlot = 74,
             ChunkRenderDistanceCenter = 75,
             ChunkLoadDistance = 76,
@@ -849,10 +985,36 @@ pub mod play {
             TitleFade = 94,
             PlaySoundFromEntity = 95,
             PlaySound = 96,
+            StopSound = 97,
             GameMessage = 98,
             PlayerListHeaderFooter = 99,
             EntityPosition = 102,
             EntityAttributes = 104,
+            UpdateTags = 107,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::play::*;
+    use super::*;
+
+    #[test]
+    fn play_sound_from_entity_encode() {
+        let pkt = PlaySoundFromEntity {
+            id: VarInt(1),
+            category: SoundCategory::Neutral,
+            entity_id: VarInt(2),
+            volume: 1.0,
+            pitch: 1.0,
+            seed: 3,
+        };
+
+        let mut buf = Vec::new();
+        pkt.encode(&mut buf).unwrap();
+
+        // Three one-byte VarInts, two floats and the seed.
+        assert_eq!(buf.len(), 3 + 4 + 4 + 8);
+    }
+}
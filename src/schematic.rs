@@ -0,0 +1,658 @@
+//! Reading and writing [Sponge schematics](https://github.com/SpongePowered/Schematic-Specification).
+//!
+//! Schematics store a box-shaped region of blocks, biomes and block entities.
+//! They are usually saved with the `.schem` file extension and can be created
+//! with tools such as WorldEdit.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_nbt::{Compound, List, Value};
+use vek::Vec3;
+
+use crate::block::{format_block_state, BlockPos, BlockState, Mirror, Rotation};
+use crate::ident::Ident;
+use crate::protocol::{Decode, Encode, VarInt};
+
+/// The data version of Minecraft 1.19.2.
+const DATA_VERSION: i32 = 3120;
+
+/// A box-shaped region of blocks, biomes and block entities.
+///
+/// Schematics can be loaded from and saved to Sponge schematic files with
+/// [`read`](Self::read) and [`write`](Self::write). Versions 1, 2 and 3 of the
+/// format can be read. Schematics are always written as version 3.
+///
+/// Schematics are also used as clipboards for editing worlds. Blocks are
+/// copied from a world with [`Chunks::copy`](crate::chunk::Chunks::copy) and placed into a world with
+/// [`Chunks::paste`](crate::chunk::Chunks::paste).
+#[derive(Clone, Debug)]
+pub struct Schematic {
+    size: Vec3<u16>,
+    /// The offset applied to the schematic when it is pasted.
+    pub offset: Vec3<i32>,
+    /// Extra data about the schematic, such as its name and author.
+    pub metadata: Compound,
+    /// The block entities in the schematic.
+    ///
+    /// Block entities are not placed when the schematic is pasted, since
+    /// chunks do not store block entities yet.
+    pub block_entities: Vec<SchematicBlockEntity>,
+    /// Block states stored in x, z, y order.
+    pub(crate) blocks: Vec<BlockState>,
+    pub(crate) biome_palette: Vec<Ident>,
+    /// Indices into `biome_palette` stored in x, z, y order. Empty if the
+    /// schematic does not have biomes.
+    pub(crate) biomes: Vec<u16>,
+}
+
+/// A block entity stored in a [`Schematic`].
+#[derive(Clone, Debug)]
+pub struct SchematicBlockEntity {
+    /// The position of the block entity relative to the schematic's origin.
+    pub pos: BlockPos,
+    /// The block entity type, such as `minecraft:chest`.
+    pub id: Ident,
+    /// The block entity's NBT data.
+    pub data: Compound,
+}
+
+impl Schematic {
+    /// Creates a new schematic of the given size filled with air and without
+    /// biomes.
+    pub fn new(size: impl Into<Vec3<u16>>) -> Self {
+        let size = size.into();
+        let volume = size.map(usize::from).product();
+
+        Self {
+            size,
+            offset: Vec3::zero(),
+            metadata: Compound::new(),
+            block_entities: Vec::new(),
+            blocks: vec![BlockState::AIR; volume],
+            biome_palette: Vec::new(),
+            biomes: Vec::new(),
+        }
+    }
+
+    /// Returns the size of the schematic in blocks along the X, Y and Z axes.
+    pub fn size(&self) -> Vec3<u16> {
+        self.size
+    }
+
+    pub(crate) fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        let size = self.size.map(usize::from);
+
+        assert!(
+            x < size.x && y < size.y && z < size.z,
+            "schematic offsets must be within bounds"
+        );
+
+        x + z * size.x + y * size.x * size.z
+    }
+
+    /// Gets the block state at the provided offsets in the schematic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the schematic.
+    pub fn get_block_state(&self, x: usize, y: usize, z: usize) -> BlockState {
+        self.blocks[self.index(x, y, z)]
+    }
+
+    /// Sets the block state at the provided offsets in the schematic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the schematic.
+    pub fn set_block_state(&mut self, x: usize, y: usize, z: usize, block: BlockState) {
+        let idx = self.index(x, y, z);
+        self.blocks[idx] = block;
+    }
+
+    /// Gets the biome at the provided offsets in the schematic. Returns `None`
+    /// if the schematic does not have biomes.
+    ///
+    /// Note: unlike chunks, schematics store a biome for every block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the schematic.
+    pub fn get_biome(&self, x: usize, y: usize, z: usize) -> Option<&Ident> {
+        let idx = self.index(x, y, z);
+        self.biomes
+            .get(idx)
+            .map(|&i| &self.biome_palette[i as usize])
+    }
+
+    /// Sets the biome at the provided offsets in the schematic.
+    ///
+    /// If the schematic does not have biomes yet, then all other blocks are
+    /// given the same biome.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the offsets are outside the bounds of the schematic.
+    pub fn set_biome(&mut self, x: usize, y: usize, z: usize, biome: Ident) {
+        let idx = self.index(x, y, z);
+
+        let palette_idx = match self.biome_palette.iter().position(|b| *b == biome) {
+            Some(i) => i,
+            None => {
+                self.biome_palette.push(biome);
+                self.biome_palette.len() - 1
+            }
+        };
+
+        if self.biomes.is_empty() {
+            self.biomes = vec![palette_idx as u16; self.blocks.len()];
+        } else {
+            self.biomes[idx] = palette_idx as u16;
+        }
+    }
+
+    /// Rotates the contents of this schematic around the Y axis.
+    ///
+    /// Pasting the rotated schematic gives the same result as pasting the
+    /// original schematic with the same rotation.
+    pub fn rotate(&mut self, rotation: Rotation) {
+        self.transform(|p| rotation.rotate_pos(p), |b| b.rotate(rotation));
+    }
+
+    /// Mirrors the contents of this schematic.
+    ///
+    /// Pasting the mirrored schematic gives the same result as pasting the
+    /// original schematic with the same mirror.
+    pub fn mirror(&mut self, mirror: Mirror) {
+        self.transform(|p| mirror.mirror_pos(p), |b| b.mirror(mirror));
+    }
+
+    fn transform(
+        &mut self,
+        transform_pos: impl Fn(BlockPos) -> BlockPos,
+        transform_block: impl Fn(BlockState) -> BlockState,
+    ) {
+        let size = self.size.map(i32::from);
+        let offset = self.offset;
+
+        // The transformed position of a block relative to the new offset.
+        let corner_a = transform_pos(BlockPos::new(offset.x, offset.y, offset.z));
+        let corner_b = transform_pos(BlockPos::new(
+            offset.x + size.x - 1,
+            offset.y + size.y - 1,
+            offset.z + size.z - 1,
+        ));
+        let new_offset = Vec3::new(
+            corner_a.x.min(corner_b.x),
+            corner_a.y.min(corner_b.y),
+            corner_a.z.min(corner_b.z),
+        );
+        let new_size = Vec3::new(
+            (corner_a.x - corner_b.x).unsigned_abs() + 1,
+            (corner_a.y - corner_b.y).unsigned_abs() + 1,
+            (corner_a.z - corner_b.z).unsigned_abs() + 1,
+        )
+        .map(|n| n as u16);
+
+        let to_new = |p: BlockPos| {
+            let p = transform_pos(BlockPos::new(
+                p.x + offset.x,
+                p.y + offset.y,
+                p.z + offset.z,
+            ));
+            BlockPos::new(p.x - new_offset.x, p.y - new_offset.y, p.z - new_offset.z)
+        };
+
+        let mut new = Self::new(new_size);
+        let has_biomes = !self.biomes.is_empty();
+        if has_biomes {
+            new.biomes = vec![0; new.blocks.len()];
+        }
+
+        for y in 0..size.y as usize {
+            for z in 0..size.z as usize {
+                for x in 0..size.x as usize {
+                    let p = to_new(BlockPos::new(x as i32, y as i32, z as i32));
+                    let old_idx = self.index(x, y, z);
+                    let new_idx = new.index(p.x as usize, p.y as usize, p.z as usize);
+
+                    new.blocks[new_idx] = transform_block(self.blocks[old_idx]);
+                    if has_biomes {
+                        new.biomes[new_idx] = self.biomes[old_idx];
+                    }
+                }
+            }
+        }
+
+        for be in &mut self.block_entities {
+            be.pos = to_new(be.pos);
+        }
+
+        self.size = new_size;
+        self.offset = new_offset;
+        self.blocks = new.blocks;
+        self.biomes = new.biomes;
+    }
+
+    /// Loads a gzip compressed schematic file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("failed to open schematic \"{}\"", path.display()))?;
+        Self::read(BufReader::new(file))
+    }
+
+    /// Saves this schematic to a gzip compressed file.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("failed to create schematic \"{}\"", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a gzip compressed schematic.
+    pub fn read(reader: impl Read) -> anyhow::Result<Self> {
+        let mut root: Compound = serde_nbt::binary::from_reader(GzDecoder::new(reader))
+            .context("failed to read schematic NBT")?;
+
+        // Version 3 nests everything in a compound named "Schematic".
+        let mut nbt = match root.remove("Schematic") {
+            Some(Value::Compound(c)) => c,
+            _ => root,
+        };
+
+        let version = match nbt.get("Version") {
+            Some(Value::Int(v)) => *v,
+            _ => bail!("missing schematic version"),
+        };
+
+        ensure!(
+            (1..=3).contains(&version),
+            "unsupported schematic version {version}"
+        );
+
+        let size = Vec3::new(
+            get_short(&nbt, "Width")?,
+            get_short(&nbt, "Height")?,
+            get_short(&nbt, "Length")?,
+        );
+
+        let mut schem = Self::new(size);
+
+        if let Some(Value::IntArray(offset)) = nbt.get("Offset") {
+            ensure!(offset.len() == 3, "invalid schematic offset");
+            schem.offset = Vec3::new(offset[0], offset[1], offset[2]);
+        }
+
+        if let Some(Value::Compound(metadata)) = nbt.remove("Metadata") {
+            schem.metadata = metadata;
+        }
+
+        // Version 3 stores the blocks in a separate compound.
+        let blocks_v3;
+        let (blocks, data_key) = if version == 3 {
+            blocks_v3 = match nbt.remove("Blocks") {
+                Some(Value::Compound(c)) => c,
+                _ => bail!("missing schematic blocks"),
+            };
+            (&blocks_v3, "Data")
+        } else {
+            (&nbt, "BlockData")
+        };
+
+        let palette = read_palette(blocks, "Palette", |s| Ok(s.parse()?))?;
+        let data = read_var_ints(get_byte_array(blocks, data_key)?, schem.blocks.len())?;
+
+        for (block, idx) in schem.blocks.iter_mut().zip(data) {
+            *block = *palette
+                .get(&idx)
+                .with_context(|| format!("invalid block palette index {idx}"))?;
+        }
+
+        // Version 1 predates the rename of tile entities to block entities.
+        let block_entities_key = if version == 1 {
+            "TileEntities"
+        } else {
+            "BlockEntities"
+        };
+
+        if let Some(Value::List(List::Compound(entities))) = blocks.get(block_entities_key) {
+            for mut entity in entities.iter().cloned() {
+                let pos = match entity.remove("Pos") {
+                    Some(Value::IntArray(p)) if p.len() == 3 => BlockPos::new(p[0], p[1], p[2]),
+                    _ => bail!("invalid block entity position"),
+                };
+
+                let id = match entity.remove("Id").or_else(|| entity.remove("id")) {
+                    Some(Value::String(id)) => Ident::new(id)?,
+                    _ => bail!("missing block entity ID"),
+                };
+
+                let data = if version == 3 {
+                    match entity.remove("Data") {
+                        Some(Value::Compound(data)) => data,
+                        _ => Compound::new(),
+                    }
+                } else {
+                    entity
+                };
+
+                schem
+                    .block_entities
+                    .push(SchematicBlockEntity { pos, id, data });
+            }
+        }
+
+        // Version 2 stores a biome for every column while version 3 stores a biome for
+        // every block.
+        let biomes = if version == 3 {
+            match nbt.remove("Biomes") {
+                Some(Value::Compound(c)) => Some((c, "Palette", "Data")),
+                _ => None,
+            }
+        } else if nbt.contains_key("BiomePalette") {
+            Some((nbt, "BiomePalette", "BiomeData"))
+        } else {
+            None
+        };
+
+        if let Some((biomes, palette_key, data_key)) = biomes {
+            let palette = read_palette(&biomes, palette_key, |s| Ok(Ident::new(s.to_owned())?))?;
+
+            let mut remap = HashMap::new();
+            for (idx, biome) in palette {
+                remap.insert(idx, schem.biome_palette.len() as u16);
+                schem.biome_palette.push(biome);
+            }
+
+            let size = size.map(usize::from);
+            let count = if version == 3 {
+                schem.blocks.len()
+            } else {
+                size.x * size.z
+            };
+
+            let data = read_var_ints(get_byte_array(&biomes, data_key)?, count)?
+                .into_iter()
+                .map(|idx| {
+                    remap
+                        .get(&idx)
+                        .copied()
+                        .with_context(|| format!("invalid biome palette index {idx}"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            schem.biomes = if version == 3 {
+                data
+            } else {
+                data.iter()
+                    .copied()
+                    .cycle()
+                    .take(schem.blocks.len())
+                    .collect()
+            };
+        }
+
+        Ok(schem)
+    }
+
+    /// Writes this schematic in the version 3 format with gzip compression.
+    pub fn write(&self, writer: impl Write) -> anyhow::Result<()> {
+        let mut block_palette = HashMap::new();
+        let mut block_data = Vec::new();
+
+        for block in &self.blocks {
+            let len = block_palette.len() as i32;
+            let idx = *block_palette.entry(*block).or_insert(len);
+            VarInt(idx).encode(&mut block_data)?;
+        }
+
+        let mut blocks = Compound::new();
+
+        blocks.insert(
+            "Palette".into(),
+            Value::Compound(
+                block_palette
+                    .into_iter()
+                    .map(|(block, idx)| (format_block_state(block), Value::Int(idx)))
+                    .collect(),
+            ),
+        );
+        blocks.insert("Data".into(), to_byte_array(block_data));
+        blocks.insert(
+            "BlockEntities".into(),
+            Value::List(List::Compound(
+                self.block_entities
+                    .iter()
+                    .map(|be| {
+                        Compound::from_iter([
+                            (
+                                "Pos".into(),
+                                Value::IntArray(vec![be.pos.x, be.pos.y, be.pos.z]),
+                            ),
+                            ("Id".into(), Value::String(be.id.to_string())),
+                            ("Data".into(), Value::Compound(be.data.clone())),
+                        ])
+                    })
+                    .collect(),
+            )),
+        );
+
+        let mut nbt = Compound::from_iter([
+            ("Version".into(), Value::Int(3)),
+            ("DataVersion".into(), Value::Int(DATA_VERSION)),
+            ("Width".into(), Value::Short(self.size.x as i16)),
+            ("Height".into(), Value::Short(self.size.y as i16)),
+            ("Length".into(), Value::Short(self.size.z as i16)),
+            (
+                "Offset".into(),
+                Value::IntArray(vec![self.offset.x, self.offset.y, self.offset.z]),
+            ),
+            ("Blocks".into(), Value::Compound(blocks)),
+        ]);
+
+        if !self.metadata.is_empty() {
+            nbt.insert("Metadata".into(), Value::Compound(self.metadata.clone()));
+        }
+
+        if !self.biomes.is_empty() {
+            let mut biome_data = Vec::new();
+            for &idx in &self.biomes {
+                VarInt(idx.into()).encode(&mut biome_data)?;
+            }
+
+            let palette = self
+                .biome_palette
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (b.to_string(), Value::Int(i as i32)))
+                .collect();
+
+            nbt.insert(
+                "Biomes".into(),
+                Value::Compound(Compound::from_iter([
+                    ("Palette".into(), Value::Compound(palette)),
+                    ("Data".into(), to_byte_array(biome_data)),
+                ])),
+            );
+        }
+
+        let root = Compound::from_iter([("Schematic".into(), Value::Compound(nbt))]);
+
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        serde_nbt::binary::to_writer(&mut encoder, &root)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
+
+fn get_short(nbt: &Compound, key: &str) -> anyhow::Result<u16> {
+    match nbt.get(key) {
+        Some(Value::Short(n)) => Ok(*n as u16),
+        _ => bail!("missing or invalid \"{key}\" in schematic"),
+    }
+}
+
+fn get_byte_array<'a>(nbt: &'a Compound, key: &str) -> anyhow::Result<&'a [i8]> {
+    match nbt.get(key) {
+        Some(Value::ByteArray(data)) => Ok(data),
+        _ => bail!("missing or invalid \"{key}\" in schematic"),
+    }
+}
+
+fn read_palette<T>(
+    nbt: &Compound,
+    key: &str,
+    mut parse: impl FnMut(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<HashMap<u32, T>> {
+    match nbt.get(key) {
+        Some(Value::Compound(palette)) => palette
+            .iter()
+            .map(|(name, idx)| match idx {
+                Value::Int(idx) => Ok((*idx as u32, parse(name)?)),
+                _ => bail!("invalid palette index for \"{name}\""),
+            })
+            .collect(),
+        _ => bail!("missing or invalid \"{key}\" in schematic"),
+    }
+}
+
+fn read_var_ints(data: &[i8], count: usize) -> anyhow::Result<Vec<u32>> {
+    let bytes: Vec<u8> = data.iter().map(|&b| b as u8).collect();
+    let mut r = bytes.as_slice();
+
+    let vals = (0..count)
+        .map(|_| Ok(VarInt::decode(&mut r)?.0 as u32))
+        .collect::<anyhow::Result<Vec<_>>>()
+        .context("schematic data is too short")?;
+
+    ensure!(r.is_empty(), "schematic data is too long");
+    Ok(vals)
+}
+
+fn to_byte_array(data: Vec<u8>) -> Value {
+    Value::ByteArray(data.into_iter().map(|b| b as i8).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockKind;
+
+    #[test]
+    fn write_read_round_trip() {
+        let mut schem = Schematic::new([3, 4, 5]);
+        schem.offset = Vec3::new(-1, 0, 2);
+
+        schem.set_block_state(0, 0, 0, BlockState::STONE);
+        schem.set_block_state(2, 3, 4, BlockState::OAK_STAIRS);
+        schem.set_biome(1, 1, 1, Ident::new("minecraft:plains").unwrap());
+        schem.set_biome(2, 2, 2, Ident::new("desert").unwrap());
+        schem.block_entities.push(SchematicBlockEntity {
+            pos: BlockPos::new(1, 2, 3),
+            id: Ident::new("minecraft:chest").unwrap(),
+            data: Compound::from_iter([("Lock".into(), Value::String("key".into()))]),
+        });
+
+        let mut buf = Vec::new();
+        schem.write(&mut buf).unwrap();
+        let read = Schematic::read(buf.as_slice()).unwrap();
+
+        assert_eq!(read.size(), schem.size());
+        assert_eq!(read.offset, schem.offset);
+        assert_eq!(read.blocks, schem.blocks);
+        assert_eq!(read.get_biome(0, 0, 0), schem.get_biome(0, 0, 0));
+        assert_eq!(read.get_biome(2, 2, 2), schem.get_biome(2, 2, 2));
+        assert_eq!(read.block_entities.len(), 1);
+        assert_eq!(read.block_entities[0].pos, BlockPos::new(1, 2, 3));
+        assert_eq!(read.block_entities[0].data, schem.block_entities[0].data);
+    }
+
+    #[test]
+    fn read_version_1() {
+        let chest = Compound::from_iter([
+            ("Pos".into(), Value::IntArray(vec![0, 0, 0])),
+            ("Id".into(), Value::String("minecraft:chest".into())),
+            ("Lock".into(), Value::String("key".into())),
+        ]);
+
+        let nbt = Compound::from_iter([
+            ("Version".into(), Value::Int(1)),
+            ("Width".into(), Value::Short(1)),
+            ("Height".into(), Value::Short(1)),
+            ("Length".into(), Value::Short(1)),
+            (
+                "Palette".into(),
+                Value::Compound(Compound::from_iter([(
+                    "minecraft:chest".into(),
+                    Value::Int(0),
+                )])),
+            ),
+            ("BlockData".into(), Value::ByteArray(vec![0])),
+            (
+                "TileEntities".into(),
+                Value::List(List::Compound(vec![chest])),
+            ),
+        ]);
+
+        let mut buf = Vec::new();
+        let mut encoder = GzEncoder::new(&mut buf, Compression::default());
+        serde_nbt::binary::to_writer(&mut encoder, &nbt).unwrap();
+        encoder.finish().unwrap();
+
+        let read = Schematic::read(buf.as_slice()).unwrap();
+
+        assert_eq!(read.get_block_state(0, 0, 0).to_kind(), BlockKind::Chest);
+        assert_eq!(read.block_entities.len(), 1);
+        assert_eq!(
+            read.block_entities[0].id,
+            Ident::new("minecraft:chest").unwrap()
+        );
+        assert_eq!(
+            read.block_entities[0].data,
+            Compound::from_iter([("Lock".into(), Value::String("key".into()))])
+        );
+    }
+
+    #[test]
+    fn rotate_schematic() {
+        let mut schem = Schematic::new([2, 1, 3]);
+        schem.offset = Vec3::new(1, 0, 0);
+        schem.set_block_state(1, 0, 2, BlockState::STONE);
+
+        let mut rotated = schem.clone();
+        rotated.rotate(Rotation::Clockwise90);
+
+        assert_eq!(rotated.size(), Vec3::new(3, 1, 2));
+        let pos = Rotation::Clockwise90.rotate_pos(BlockPos::new(2, 0, 2));
+        assert_eq!(
+            rotated.get_block_state(
+                (pos.x - rotated.offset.x) as usize,
+                0,
+                (pos.z - rotated.offset.z) as usize
+            ),
+            BlockState::STONE
+        );
+
+        for _ in 0..3 {
+            rotated.rotate(Rotation::Clockwise90);
+        }
+        assert_eq!(rotated.size(), schem.size());
+        assert_eq!(rotated.offset, schem.offset);
+        assert_eq!(rotated.blocks, schem.blocks);
+
+        rotated.mirror(Mirror::LeftRight);
+        rotated.mirror(Mirror::LeftRight);
+        assert_eq!(rotated.offset, schem.offset);
+        assert_eq!(rotated.blocks, schem.blocks);
+    }
+}
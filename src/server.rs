@@ -14,6 +14,7 @@ use flume::{Receiver, Sender};
 use num::BigInt;
 use rand::rngs::OsRng;
 use rayon::iter::ParallelIterator;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use reqwest::Client as HttpClient;
 use rsa::{PaddingScheme, PublicKeyParts, RsaPrivateKey};
 use serde::Deserialize;
@@ -42,10 +43,11 @@ use crate::protocol::packets::c2s::status::{QueryPing, QueryRequest};
 use crate::protocol::packets::s2c::login::{
     EncryptionRequest, LoginCompression, LoginDisconnect, LoginSuccess,
 };
-use crate::protocol::packets::s2c::play::S2cPlayPacket;
+use crate::protocol::packets::s2c::play::{S2cPlayPacket, UpdateTags};
 use crate::protocol::packets::s2c::status::{QueryPong, QueryResponse};
 use crate::protocol::packets::Property;
 use crate::protocol::{BoundedArray, BoundedString, VarInt};
+use crate::tag::update_tags_packet;
 use crate::util::valid_username;
 use crate::world::Worlds;
 use crate::{Ticks, PROTOCOL_VERSION, VERSION_NAME};
@@ -95,6 +97,8 @@ struct SharedServerInner<C: Config> {
     _tokio_runtime: Option<Runtime>,
     dimensions: Vec<Dimension>,
     biomes: Vec<Biome>,
+    /// The tags sent to clients when they join.
+    tags: UpdateTags,
     /// The instant the server was started.
     start_instant: Instant,
     /// Receiver for new clients past the login stage.
@@ -114,6 +118,8 @@ struct SharedServerInner<C: Config> {
     public_key_der: Box<[u8]>,
     /// For session server requests.
     http_client: HttpClient,
+    /// The thread pool used by chunk providers.
+    chunk_workers: ThreadPool,
 }
 
 /// Contains information about a new client.
@@ -142,6 +148,7 @@ pub(crate) type C2sPacketChannels = (Sender<S2cPlayMessage>, Receiver<C2sPlayPac
 
 /// Messages sent to packet encoders.
 #[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum S2cPlayMessage {
     /// Queue a play packet for sending.
     Queue(S2cPlayPacket),
@@ -240,6 +247,17 @@ impl<C: Config> SharedServer<C> {
             .map(|(i, b)| (BiomeId(i as u16), b))
     }
 
+    /// Returns the packet containing the vanilla and custom tags.
+    pub(crate) fn tags_packet(&self) -> &UpdateTags {
+        &self.0.tags
+    }
+
+    /// The thread pool used to run [`ChunkProvider`](crate::chunk::ChunkProvider)
+    /// requests.
+    pub(crate) fn chunk_workers(&self) -> &ThreadPool {
+        &self.0.chunk_workers
+    }
+
     /// Returns the instant the server was started.
     pub fn start_instant(&self) -> Instant {
         self.0.start_instant
@@ -314,6 +332,19 @@ fn setup_server<C: Config>(cfg: C) -> anyhow::Result<SharedServer<C>> {
     );
 
     let tokio_handle = cfg.tokio_handle();
+
+    let chunk_worker_threads = cfg.chunk_worker_threads();
+
+    ensure!(
+        chunk_worker_threads > 0,
+        "chunk worker thread count must be nonzero"
+    );
+
+    let chunk_workers = ThreadPoolBuilder::new()
+        .num_threads(chunk_worker_threads)
+        .thread_name(|i| format!("valence-chunk-worker-{i}"))
+        .build()?;
+
     let dimensions = cfg.dimensions();
 
     ensure!(
@@ -371,6 +402,8 @@ fn setup_server<C: Config>(cfg: C) -> anyhow::Result<SharedServer<C>> {
         );
     }
 
+    let tags = update_tags_packet(cfg.tags());
+
     let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024)?;
 
     let public_key_der =
@@ -402,6 +435,7 @@ fn setup_server<C: Config>(cfg: C) -> anyhow::Result<SharedServer<C>> {
         _tokio_runtime: runtime,
         dimensions,
         biomes,
+        tags,
         start_instant: Instant::now(),
         new_clients_rx,
         new_clients_tx,
@@ -411,6 +445,7 @@ fn setup_server<C: Config>(cfg: C) -> anyhow::Result<SharedServer<C>> {
         rsa_key,
         public_key_der,
         http_client: HttpClient::new(),
+        chunk_workers,
     };
 
     Ok(SharedServer(Arc::new(server)))
@@ -436,7 +471,13 @@ fn do_update_loop<C: Config>(server: &mut Server<C>) -> ShutdownResult {
 
         shared.config().update(server);
 
+        server.entities.update_physics(&server.worlds);
+        server.entities.update_falling_blocks(&mut server.worlds);
+
         server.worlds.par_iter_mut().for_each(|(id, world)| {
+            world.chunks.update_tickets(id, &server.clients);
+            world.chunks.update_blocks();
+
             // Chunks created this tick can have their changes applied immediately because
             // they have not been observed by clients yet. Clients will not have to be sent
             // the block change packet in this case, since the changes are applied before we
@@ -459,6 +500,10 @@ fn do_update_loop<C: Config>(server: &mut Server<C>) -> ShutdownResult {
 
         server.worlds.par_iter_mut().for_each(|(_, world)| {
             world.chunks.update();
+            world.meta.update();
+            world.border.update();
+            world.nearby_packets.clear();
+            world.explosions.clear();
         });
 
         server.player_lists.update();
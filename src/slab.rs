@@ -133,14 +133,14 @@ impl<T> Slab<T> {
         self.len = 0;
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             entries: self.entries.iter().enumerate(),
             len: self.len,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             entries: self.entries.iter_mut().enumerate(),
             len: self.len,
@@ -0,0 +1,376 @@
+//! The stringified NBT format used in commands and JSON text.
+
+use std::fmt::Write;
+
+use anyhow::{bail, ensure, Context};
+
+use crate::nbt::{Compound, List, Value};
+
+/// Formats a compound as SNBT, such as `{Damage:5,display:{Name:"..."}}`.
+pub(crate) fn to_snbt(compound: &Compound) -> String {
+    let mut s = String::new();
+    write_compound(&mut s, compound);
+    s
+}
+
+/// Parses a compound from SNBT.
+pub(crate) fn from_snbt(s: &str) -> anyhow::Result<Compound> {
+    let mut r = Reader { s, pos: 0 };
+
+    let compound = r.read_compound()?;
+    r.skip_whitespace();
+    ensure!(r.rest().is_empty(), "trailing data after compound");
+
+    Ok(compound)
+}
+
+fn write_compound(s: &mut String, compound: &Compound) {
+    s.push('{');
+    for (i, (key, value)) in compound.iter().enumerate() {
+        if i != 0 {
+            s.push(',');
+        }
+
+        if !key.is_empty() && key.chars().all(is_unquoted_char) {
+            s.push_str(key);
+        } else {
+            write_string(s, key);
+        }
+
+        s.push(':');
+        write_value(s, value);
+    }
+    s.push('}');
+}
+
+fn write_value(s: &mut String, value: &Value) {
+    match value {
+        Value::Byte(v) => write!(s, "{v}b").unwrap(),
+        Value::Short(v) => write!(s, "{v}s").unwrap(),
+        Value::Int(v) => write!(s, "{v}").unwrap(),
+        Value::Long(v) => write!(s, "{v}L").unwrap(),
+        Value::Float(v) => write!(s, "{v}f").unwrap(),
+        Value::Double(v) => write!(s, "{v}d").unwrap(),
+        Value::ByteArray(v) => write_seq(s, "B;", v, |s, v| write!(s, "{v}b").unwrap()),
+        Value::String(v) => write_string(s, v),
+        Value::List(v) => write_list(s, v),
+        Value::Compound(v) => write_compound(s, v),
+        Value::IntArray(v) => write_seq(s, "I;", v, |s, v| write!(s, "{v}").unwrap()),
+        Value::LongArray(v) => write_seq(s, "L;", v, |s, v| write!(s, "{v}L").unwrap()),
+    }
+}
+
+fn write_list(s: &mut String, list: &List) {
+    match list {
+        List::Byte(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Byte(*v))),
+        List::Short(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Short(*v))),
+        List::Int(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Int(*v))),
+        List::Long(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Long(*v))),
+        List::Float(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Float(*v))),
+        List::Double(l) => write_seq(s, "", l, |s, v| write_value(s, &Value::Double(*v))),
+        List::ByteArray(l) => write_seq(s, "", l, |s, v| {
+            write_seq(s, "B;", v, |s, v| write!(s, "{v}b").unwrap())
+        }),
+        List::String(l) => write_seq(s, "", l, |s, v| write_string(s, v)),
+        List::List(l) => write_seq(s, "", l, write_list),
+        List::Compound(l) => write_seq(s, "", l, write_compound),
+        List::IntArray(l) => write_seq(s, "", l, |s, v| {
+            write_seq(s, "I;", v, |s, v| write!(s, "{v}").unwrap())
+        }),
+        List::LongArray(l) => write_seq(s, "", l, |s, v| {
+            write_seq(s, "L;", v, |s, v| write!(s, "{v}L").unwrap())
+        }),
+    }
+}
+
+fn write_seq<T>(s: &mut String, prefix: &str, elems: &[T], mut f: impl FnMut(&mut String, &T)) {
+    s.push('[');
+    s.push_str(prefix);
+    for (i, elem) in elems.iter().enumerate() {
+        if i != 0 {
+            s.push(',');
+        }
+        f(s, elem);
+    }
+    s.push(']');
+}
+
+fn write_string(s: &mut String, string: &str) {
+    // Like vanilla, prefer double quotes unless the string contains them.
+    let quote = if string.contains('"') && !string.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+
+    s.push(quote);
+    for c in string.chars() {
+        if c == quote || c == '\\' {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s.push(quote);
+}
+
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+struct Reader<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips whitespace and consumes `c` if it is the next character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> anyhow::Result<()> {
+        ensure!(self.eat(c), "expected '{c}' at position {}", self.pos);
+        Ok(())
+    }
+
+    fn read_compound(&mut self) -> anyhow::Result<Compound> {
+        self.expect('{')?;
+
+        let mut compound = Compound::new();
+        if self.eat('}') {
+            return Ok(compound);
+        }
+
+        loop {
+            let key = self.read_string()?;
+            ensure!(!key.is_empty(), "empty key at position {}", self.pos);
+            self.expect(':')?;
+            let value = self.read_value()?;
+            compound.insert(key, value);
+
+            if !self.eat(',') {
+                self.expect('}')?;
+                return Ok(compound);
+            }
+        }
+    }
+
+    fn read_value(&mut self) -> anyhow::Result<Value> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => Ok(Value::Compound(self.read_compound()?)),
+            Some('[') => self.read_list_or_array(),
+            Some('"' | '\'') => Ok(Value::String(self.read_string()?)),
+            _ => {
+                let s = self.read_string()?;
+                ensure!(!s.is_empty(), "expected value at position {}", self.pos);
+                Ok(parse_unquoted(s))
+            }
+        }
+    }
+
+    fn read_list_or_array(&mut self) -> anyhow::Result<Value> {
+        self.expect('[')?;
+
+        let rest = self.rest();
+        for (prefix, kind) in [("B;", 'B'), ("I;", 'I'), ("L;", 'L')] {
+            if rest.starts_with(prefix) {
+                self.pos += prefix.len();
+                let elems = self.read_elements()?;
+
+                return Ok(match kind {
+                    'B' => Value::ByteArray(array_of(elems, |v| match v {
+                        Value::Byte(v) => Some(v),
+                        _ => None,
+                    })?),
+                    'I' => Value::IntArray(array_of(elems, |v| match v {
+                        Value::Int(v) => Some(v),
+                        _ => None,
+                    })?),
+                    _ => Value::LongArray(array_of(elems, |v| match v {
+                        Value::Long(v) => Some(v),
+                        _ => None,
+                    })?),
+                });
+            }
+        }
+
+        let elems = self.read_elements()?;
+        let mut elems = elems.into_iter();
+
+        let Some(first) = elems.next() else {
+            return Ok(Value::List(List::Byte(Vec::new())));
+        };
+
+        macro_rules! list {
+            ($($variant:ident),*) => {
+                match first {
+                    $(
+                        Value::$variant(first) => List::$variant(
+                            std::iter::once(Ok(first))
+                                .chain(elems.map(|e| match e {
+                                    Value::$variant(v) => Ok(v),
+                                    _ => bail!("list elements must have the same type"),
+                                }))
+                                .collect::<anyhow::Result<_>>()?,
+                        ),
+                    )*
+                }
+            };
+        }
+
+        Ok(Value::List(list!(
+            Byte, Short, Int, Long, Float, Double, ByteArray, String, List, Compound, IntArray,
+            LongArray
+        )))
+    }
+
+    /// Reads comma separated values up to and including the closing `]`.
+    fn read_elements(&mut self) -> anyhow::Result<Vec<Value>> {
+        let mut elems = Vec::new();
+        if self.eat(']') {
+            return Ok(elems);
+        }
+
+        loop {
+            elems.push(self.read_value()?);
+
+            if !self.eat(',') {
+                self.expect(']')?;
+                return Ok(elems);
+            }
+        }
+    }
+
+    /// Reads a quoted or unquoted string.
+    fn read_string(&mut self) -> anyhow::Result<String> {
+        self.skip_whitespace();
+
+        let quote = match self.peek() {
+            Some(c @ ('"' | '\'')) => c,
+            _ => {
+                let rest = self.rest();
+                let len = rest.find(|c| !is_unquoted_char(c)).unwrap_or(rest.len());
+                self.pos += len;
+                return Ok(rest[..len].to_owned());
+            }
+        };
+
+        self.pos += 1;
+        let mut s = String::new();
+        let mut chars = self.rest().char_indices();
+
+        loop {
+            let (i, c) = chars.next().context("unterminated string")?;
+
+            if c == quote {
+                self.pos += i + 1;
+                return Ok(s);
+            }
+
+            if c == '\\' {
+                let (_, c) = chars.next().context("unterminated string")?;
+                ensure!(c == quote || c == '\\', "invalid escape sequence '\\{c}'");
+                s.push(c);
+            } else {
+                s.push(c);
+            }
+        }
+    }
+}
+
+fn array_of<T>(elems: Vec<Value>, f: impl Fn(Value) -> Option<T>) -> anyhow::Result<Vec<T>> {
+    elems
+        .into_iter()
+        .map(|e| f(e).context("array elements have the wrong type"))
+        .collect()
+}
+
+/// Parses an unquoted value, which is a number if it looks like one and a
+/// string otherwise.
+fn parse_unquoted(s: String) -> Value {
+    let (num, suffix) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], Some(c.to_ascii_lowercase())),
+        _ => (s.as_str(), None),
+    };
+
+    let value = match suffix {
+        Some('b') => num.parse().ok().map(Value::Byte),
+        Some('s') => num.parse().ok().map(Value::Short),
+        Some('l') => num.parse().ok().map(Value::Long),
+        Some('f') => num.parse().ok().map(Value::Float),
+        Some('d') => num.parse().ok().map(Value::Double),
+        None if num.contains(['.', 'e', 'E']) => num.parse().ok().map(Value::Double),
+        None => num.parse().ok().map(Value::Int),
+        _ => None,
+    };
+
+    value.unwrap_or_else(|| match s.as_str() {
+        "true" => Value::Byte(1),
+        "false" => Value::Byte(0),
+        _ => Value::String(s),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snbt_round_trip() {
+        let mut display = Compound::new();
+        display.insert(
+            "Name".into(),
+            Value::String(r#"{"text":"It's \"sharp\""}"#.into()),
+        );
+
+        let mut enchantment = Compound::new();
+        enchantment.insert("id".into(), Value::String("minecraft:sharpness".into()));
+        enchantment.insert("lvl".into(), Value::Short(5));
+
+        let mut nbt = Compound::new();
+        nbt.insert("Damage".into(), Value::Int(-3));
+        nbt.insert("display".into(), Value::Compound(display));
+        nbt.insert(
+            "Enchantments".into(),
+            Value::List(List::Compound(vec![enchantment])),
+        );
+        nbt.insert("key with spaces".into(), Value::Byte(1));
+        nbt.insert("Scale".into(), Value::Float(1.5));
+        nbt.insert("Speed".into(), Value::Double(0.25));
+        nbt.insert("Time".into(), Value::Long(i64::MAX));
+        nbt.insert("Ints".into(), Value::IntArray(vec![1, -2, 3]));
+        nbt.insert("Bytes".into(), Value::ByteArray(vec![]));
+        nbt.insert("Empty".into(), Value::List(List::Byte(vec![])));
+
+        let snbt = to_snbt(&nbt);
+        assert_eq!(from_snbt(&snbt).unwrap(), nbt);
+
+        assert_eq!(
+            to_snbt(&from_snbt("{ Damage : 5 , Unbreakable:true,x:\"a\\\\b\"}").unwrap()),
+            r#"{Damage:5,Unbreakable:1b,x:"a\\b"}"#
+        );
+        assert!(from_snbt("{a:[1,2b]}").is_err());
+        assert!(from_snbt("{a:1").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! Sound events.
+
+#![allow(clippy::all, missing_docs)]
+
+use vek::Vec3;
+
+use crate::ident::Ident;
+use crate::protocol::packets::s2c::play::PlaySoundId;
+pub use crate::protocol::packets::s2c::play::SoundCategory;
+
+include!(concat!(env!("OUT_DIR"), "/sound.rs"));
+
+impl From<Sound> for Ident {
+    fn from(sound: Sound) -> Self {
+        Ident::new(sound.to_str()).expect("sound names should be valid identifiers")
+    }
+}
+
+/// Returns the distance from which clients hear a sound with the given volume.
+pub(crate) fn hearing_range(volume: f32) -> f64 {
+    16.0 * volume.max(1.0) as f64
+}
+
+/// Creates the packet for playing the sound `name` at `position`.
+pub(crate) fn sound_packet(
+    name: Ident,
+    category: SoundCategory,
+    position: Vec3<f64>,
+    volume: f32,
+    pitch: f32,
+) -> PlaySoundId {
+    PlaySoundId {
+        name,
+        category,
+        // Positions are sent in fixed point with three fractional bits.
+        position: position.map(|n| (n * 8.0) as i32),
+        volume,
+        pitch,
+        // The seed picks one of the sound's variants.
+        seed: rand::random(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ident;
+
+    #[test]
+    fn sound_names() {
+        for sound in Sound::ALL {
+            assert_eq!(Sound::from_str(sound.to_str()), Some(sound));
+        }
+
+        assert_eq!(
+            Ident::from(Sound::BlockNoteBlockHarp),
+            ident!("minecraft:block.note_block.harp")
+        );
+
+        assert_eq!(hearing_range(0.5), 16.0);
+        assert_eq!(hearing_range(2.0), 32.0);
+    }
+}
@@ -0,0 +1,447 @@
+//! Loading and placing vanilla structure templates.
+//!
+//! Structure templates are the `.nbt` files saved by structure blocks. Vanilla
+//! and datapack structures such as villages and shipwrecks are stored in this
+//! format.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context};
+use flate2::read::GzDecoder;
+use serde_nbt::{Compound, List, Value};
+use vek::Vec3;
+
+use crate::block::{BlockPos, BlockState, Mirror, Rotation};
+use crate::chunk::Chunks;
+use crate::config::Config;
+use crate::entity::{Entities, EntityId, EntityKind};
+use crate::world::WorldId;
+
+/// A structure template loaded from a vanilla `.nbt` structure file.
+///
+/// Templates can have several palettes, which are variants of the same
+/// structure with different blocks. Most templates only have one.
+#[derive(Clone, Debug)]
+pub struct StructureTemplate {
+    size: Vec3<i32>,
+    palettes: Vec<Vec<BlockState>>,
+    /// The blocks in the structure. Positions without a block are structure
+    /// voids and are left untouched when the structure is placed.
+    pub blocks: Vec<StructureBlock>,
+    /// The entities in the structure.
+    pub entities: Vec<StructureEntity>,
+}
+
+/// A block in a [`StructureTemplate`].
+#[derive(Clone, Debug)]
+pub struct StructureBlock {
+    /// The position of the block relative to the structure's origin.
+    pub pos: BlockPos,
+    /// The index of the block state in each palette of the structure.
+    pub state: usize,
+    /// The block entity data of this block, if any.
+    ///
+    /// Block entities are not placed when the structure is placed, since
+    /// chunks do not store block entities yet.
+    pub nbt: Option<Compound>,
+}
+
+/// An entity in a [`StructureTemplate`].
+#[derive(Clone, Debug)]
+pub struct StructureEntity {
+    /// The exact position of the entity relative to the structure's origin.
+    pub pos: Vec3<f64>,
+    /// The block position of the entity relative to the structure's origin.
+    pub block_pos: BlockPos,
+    /// The entity's NBT data. The entity type is stored in the `id` field.
+    pub nbt: Compound,
+}
+
+impl StructureEntity {
+    /// Returns the kind of this entity, or `None` if the `id` in the entity's
+    /// NBT data is missing or unknown.
+    pub fn kind(&self) -> Option<EntityKind> {
+        match self.nbt.get("id") {
+            Some(Value::String(id)) => {
+                EntityKind::from_str(id.strip_prefix("minecraft:").unwrap_or(id))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl StructureTemplate {
+    /// Returns the size of the structure in blocks along the X, Y and Z axes.
+    pub fn size(&self) -> Vec3<i32> {
+        self.size
+    }
+
+    /// Returns the number of palettes in this structure. This is always at
+    /// least one.
+    pub fn palette_count(&self) -> usize {
+        self.palettes.len()
+    }
+
+    /// Returns the block states of a palette, indexed by
+    /// [`StructureBlock::state`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette index is out of bounds.
+    pub fn palette(&self, palette: usize) -> &[BlockState] {
+        &self.palettes[palette]
+    }
+
+    /// Places the blocks of this structure into a world using the given
+    /// palette.
+    ///
+    /// The block at `pos` in the structure is placed at
+    /// `origin + rotation(mirror(pos))`. Block states are transformed with
+    /// [`BlockState::mirror`] and [`BlockState::rotate`]. Blocks outside of
+    /// loaded chunks are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette index is out of bounds.
+    pub fn place<C: Config>(
+        &self,
+        chunks: &mut Chunks<C>,
+        origin: impl Into<BlockPos>,
+        rotation: Rotation,
+        mirror: Mirror,
+        palette: usize,
+    ) {
+        let origin = origin.into();
+        let palette = &self.palettes[palette];
+
+        for block in &self.blocks {
+            let p = rotation.rotate_pos(mirror.mirror_pos(block.pos));
+            let pos = BlockPos::new(origin.x + p.x, origin.y + p.y, origin.z + p.z);
+            let state = palette[block.state].mirror(mirror).rotate(rotation);
+
+            chunks.set_block_state(pos, state);
+        }
+    }
+
+    /// Spawns the entities of this structure into a world, using the same
+    /// transformation as [`place`](Self::place).
+    ///
+    /// Only the kind, position and rotation of each entity is applied. Entities
+    /// of unknown kinds are skipped. `state` is called to create the
+    /// `EntityState` of every spawned entity. The IDs of the new entities are
+    /// returned.
+    pub fn spawn_entities<C: Config>(
+        &self,
+        entities: &mut Entities<C>,
+        world: WorldId,
+        origin: impl Into<BlockPos>,
+        rotation: Rotation,
+        mirror: Mirror,
+        mut state: impl FnMut(&StructureEntity) -> C::EntityState,
+    ) -> Vec<EntityId> {
+        let origin = origin.into();
+        let mut ids = Vec::new();
+
+        for structure_entity in &self.entities {
+            let kind = match structure_entity.kind() {
+                Some(kind) => kind,
+                None => continue,
+            };
+
+            let (id, entity) = entities.insert(kind, state(structure_entity));
+
+            let pos = transform_point(structure_entity.pos, rotation, mirror);
+            entity.set_world(world);
+            entity.set_position([
+                origin.x as f64 + pos.x,
+                origin.y as f64 + pos.y,
+                origin.z as f64 + pos.z,
+            ]);
+
+            if let Some(Value::List(List::Float(rot))) = structure_entity.nbt.get("Rotation") {
+                if let [yaw, pitch] = rot[..] {
+                    let yaw = match mirror {
+                        Mirror::None => yaw,
+                        Mirror::LeftRight => 180.0 - yaw,
+                        Mirror::FrontBack => -yaw,
+                    };
+                    let yaw = match rotation {
+                        Rotation::None => yaw,
+                        Rotation::Clockwise90 => yaw + 90.0,
+                        Rotation::Clockwise180 => yaw + 180.0,
+                        Rotation::Counterclockwise90 => yaw + 270.0,
+                    }
+                    .rem_euclid(360.0);
+
+                    entity.set_yaw(yaw);
+                    entity.set_head_yaw(yaw);
+                    entity.set_pitch(pitch);
+                }
+            }
+
+            ids.push(id);
+        }
+
+        ids
+    }
+
+    /// Loads a gzip compressed structure file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("failed to open structure \"{}\"", path.display()))?;
+        Self::read(BufReader::new(file))
+    }
+
+    /// Reads a gzip compressed structure.
+    pub fn read(reader: impl Read) -> anyhow::Result<Self> {
+        let nbt: Compound = serde_nbt::binary::from_reader(GzDecoder::new(reader))
+            .context("failed to read structure NBT")?;
+
+        let size = match nbt.get("size") {
+            Some(Value::List(List::Int(s))) if s.len() == 3 => Vec3::new(s[0], s[1], s[2]),
+            _ => bail!("missing or invalid structure size"),
+        };
+
+        let palettes = match (nbt.get("palette"), nbt.get("palettes")) {
+            (Some(Value::List(palette)), _) => vec![read_palette(palette)?],
+            (_, Some(Value::List(List::List(palettes)))) => palettes
+                .iter()
+                .map(read_palette)
+                .collect::<anyhow::Result<_>>()?,
+            _ => bail!("missing structure palette"),
+        };
+
+        ensure!(!palettes.is_empty(), "structure has no palettes");
+        let palette_len = palettes[0].len();
+        ensure!(
+            palettes.iter().all(|p| p.len() == palette_len),
+            "structure palettes have different lengths"
+        );
+
+        let blocks = match nbt.get("blocks") {
+            Some(Value::List(List::Compound(blocks))) => blocks
+                .iter()
+                .map(|block| {
+                    let pos = read_block_pos(block.get("pos"))?;
+
+                    let state = match block.get("state") {
+                        Some(&Value::Int(state)) => usize::try_from(state)
+                            .ok()
+                            .filter(|&s| s < palette_len)
+                            .with_context(|| format!("invalid block state index {state}"))?,
+                        _ => bail!("missing block state index"),
+                    };
+
+                    let nbt = match block.get("nbt") {
+                        Some(Value::Compound(nbt)) => Some(nbt.clone()),
+                        _ => None,
+                    };
+
+                    Ok(StructureBlock { pos, state, nbt })
+                })
+                .collect::<anyhow::Result<_>>()?,
+            // An empty list might not be a list of compounds.
+            Some(Value::List(l)) if l.is_empty() => Vec::new(),
+            _ => bail!("missing structure blocks"),
+        };
+
+        let entities = match nbt.get("entities") {
+            Some(Value::List(List::Compound(entities))) => entities
+                .iter()
+                .map(|entity| {
+                    let pos = match entity.get("pos") {
+                        Some(Value::List(List::Double(p))) if p.len() == 3 => {
+                            Vec3::new(p[0], p[1], p[2])
+                        }
+                        _ => bail!("missing or invalid entity position"),
+                    };
+
+                    let block_pos = read_block_pos(entity.get("blockPos"))?;
+
+                    let nbt = match entity.get("nbt") {
+                        Some(Value::Compound(nbt)) => nbt.clone(),
+                        _ => Compound::new(),
+                    };
+
+                    Ok(StructureEntity {
+                        pos,
+                        block_pos,
+                        nbt,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            size,
+            palettes,
+            blocks,
+            entities,
+        })
+    }
+}
+
+fn read_palette(palette: &List) -> anyhow::Result<Vec<BlockState>> {
+    let entries = match palette {
+        List::Compound(entries) => entries,
+        l if l.is_empty() => return Ok(Vec::new()),
+        _ => bail!("invalid structure palette"),
+    };
+
+    entries
+        .iter()
+        .map(|entry| BlockState::from_nbt(entry).context("invalid block in structure palette"))
+        .collect()
+}
+
+fn read_block_pos(val: Option<&Value>) -> anyhow::Result<BlockPos> {
+    match val {
+        Some(Value::List(List::Int(p))) if p.len() == 3 => Ok(BlockPos::new(p[0], p[1], p[2])),
+        _ => bail!("missing or invalid block position"),
+    }
+}
+
+/// Transforms a point in the same way as a block position, such that a point
+/// inside of a block stays inside of the transformed block.
+fn transform_point(p: Vec3<f64>, rotation: Rotation, mirror: Mirror) -> Vec3<f64> {
+    let p = match mirror {
+        Mirror::None => p,
+        Mirror::LeftRight => Vec3::new(p.x, p.y, 1.0 - p.z),
+        Mirror::FrontBack => Vec3::new(1.0 - p.x, p.y, p.z),
+    };
+
+    match rotation {
+        Rotation::None => p,
+        Rotation::Clockwise90 => Vec3::new(1.0 - p.z, p.y, p.x),
+        Rotation::Clockwise180 => Vec3::new(1.0 - p.x, p.y, 1.0 - p.z),
+        Rotation::Counterclockwise90 => Vec3::new(p.z, p.y, 1.0 - p.x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+    use crate::block::{PropName, PropValue};
+
+    #[test]
+    fn read_structure() {
+        let palette_entry = |name: &str, props: &[(&str, &str)]| {
+            let mut c = Compound::from_iter([("Name".into(), Value::String(name.into()))]);
+            if !props.is_empty() {
+                c.insert(
+                    "Properties".into(),
+                    Value::Compound(
+                        props
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())))
+                            .collect(),
+                    ),
+                );
+            }
+            c
+        };
+
+        let block = |pos: [i32; 3], state: i32| {
+            Compound::from_iter([
+                ("pos".into(), Value::List(List::Int(pos.to_vec()))),
+                ("state".into(), Value::Int(state)),
+            ])
+        };
+
+        let nbt = Compound::from_iter([
+            ("DataVersion".into(), Value::Int(3120)),
+            ("size".into(), Value::List(List::Int(vec![2, 1, 1]))),
+            (
+                "palette".into(),
+                Value::List(List::Compound(vec![
+                    palette_entry("minecraft:stone", &[]),
+                    palette_entry(
+                        "minecraft:oak_stairs",
+                        &[
+                            ("facing", "east"),
+                            ("half", "bottom"),
+                            ("shape", "straight"),
+                            ("waterlogged", "false"),
+                        ],
+                    ),
+                ])),
+            ),
+            (
+                "blocks".into(),
+                Value::List(List::Compound(vec![
+                    block([0, 0, 0], 0),
+                    block([1, 0, 0], 1),
+                ])),
+            ),
+            (
+                "entities".into(),
+                Value::List(List::Compound(vec![Compound::from_iter([
+                    ("pos".into(), Value::List(List::Double(vec![0.5, 0.0, 0.5]))),
+                    ("blockPos".into(), Value::List(List::Int(vec![0, 0, 0]))),
+                    (
+                        "nbt".into(),
+                        Value::Compound(Compound::from_iter([(
+                            "id".into(),
+                            Value::String("minecraft:armor_stand".into()),
+                        )])),
+                    ),
+                ])])),
+            ),
+        ]);
+
+        let mut buf = Vec::new();
+        let mut enc = GzEncoder::new(&mut buf, Compression::default());
+        serde_nbt::binary::to_writer(&mut enc, &nbt).unwrap();
+        enc.finish().unwrap().flush().unwrap();
+
+        let structure = StructureTemplate::read(buf.as_slice()).unwrap();
+
+        assert_eq!(structure.size(), Vec3::new(2, 1, 1));
+        assert_eq!(structure.palette_count(), 1);
+        assert_eq!(structure.blocks.len(), 2);
+        assert_eq!(
+            structure.palette(0)[structure.blocks[1].state],
+            BlockState::OAK_STAIRS.set(PropName::Facing, PropValue::East)
+        );
+        assert_eq!(structure.entities[0].kind(), Some(EntityKind::ArmorStand));
+    }
+
+    #[test]
+    fn transform_points() {
+        let p = Vec3::new(0.25, 1.0, 0.75);
+
+        assert_eq!(
+            transform_point(p, Rotation::Clockwise90, Mirror::None),
+            Vec3::new(0.25, 1.0, 0.25)
+        );
+        assert_eq!(
+            transform_point(p, Rotation::None, Mirror::FrontBack),
+            Vec3::new(0.75, 1.0, 0.75)
+        );
+
+        // A point inside of a block stays inside of the transformed block.
+        for rot in [
+            Rotation::None,
+            Rotation::Clockwise90,
+            Rotation::Clockwise180,
+            Rotation::Counterclockwise90,
+        ] {
+            for mirror in [Mirror::None, Mirror::LeftRight, Mirror::FrontBack] {
+                let block = BlockPos::new(3, 1, -2);
+                let p = Vec3::new(3.5, 1.5, -1.5);
+
+                let expected = rot.rotate_pos(mirror.mirror_pos(block));
+                assert_eq!(BlockPos::at(transform_point(p, rot, mirror)), expected);
+            }
+        }
+    }
+}
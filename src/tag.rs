@@ -0,0 +1,154 @@
+//! Block, item, fluid and entity type tags.
+//!
+//! Tags are named groups of registry entries, such as `minecraft:logs`. The
+//! vanilla tags are sent to clients when they join, along with any custom tags
+//! returned by [`Config::tags`](crate::config::Config::tags). Clients rely on
+//! some of them for features like climbing and swimming.
+
+#![allow(clippy::all, missing_docs)]
+
+use crate::block::BlockKind;
+use crate::entity::EntityKind;
+use crate::ident;
+use crate::ident::Ident;
+use crate::item::ItemKind;
+use crate::protocol::packets::s2c::play::{TagEntries, TagGroup, UpdateTags};
+use crate::protocol::VarInt;
+
+include!(concat!(env!("OUT_DIR"), "/tag.rs"));
+
+/// A custom tag sent to clients in addition to the vanilla tags. See
+/// [`Config::tags`](crate::config::Config::tags).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Tag {
+    registry: &'static str,
+    name: Ident,
+    entries: Vec<VarInt>,
+}
+
+impl Tag {
+    /// Creates a block tag named `name` containing `kinds`.
+    pub fn blocks(name: Ident, kinds: impl IntoIterator<Item = BlockKind>) -> Self {
+        Self {
+            registry: "block",
+            name,
+            // Block kinds are declared in registry order.
+            entries: kinds.into_iter().map(|k| VarInt(k as i32)).collect(),
+        }
+    }
+
+    /// Creates an item tag named `name` containing `kinds`.
+    pub fn items(name: Ident, kinds: impl IntoIterator<Item = ItemKind>) -> Self {
+        Self {
+            registry: "item",
+            name,
+            entries: kinds
+                .into_iter()
+                .map(|k| VarInt(k.to_raw() as i32))
+                .collect(),
+        }
+    }
+
+    /// Creates an entity type tag named `name` containing `kinds`.
+    pub fn entity_types(name: Ident, kinds: impl IntoIterator<Item = EntityKind>) -> Self {
+        Self {
+            registry: "entity_type",
+            name,
+            entries: kinds.into_iter().map(|k| VarInt(k as i32)).collect(),
+        }
+    }
+
+    /// Returns the name of this tag.
+    pub fn name(&self) -> &Ident {
+        &self.name
+    }
+}
+
+/// Creates the packet containing the vanilla tags and `custom` tags. Custom
+/// tags replace the vanilla tags with the same name in the same registry.
+pub(crate) fn update_tags_packet(custom: Vec<Tag>) -> UpdateTags {
+    let mut groups: Vec<TagGroup> = VANILLA_TAGS
+        .iter()
+        .map(|&(registry, tags)| TagGroup {
+            registry: ident!("{registry}"),
+            tags: tags
+                .iter()
+                .map(|&(name, entries)| TagEntries {
+                    name: ident!("{name}"),
+                    entries: entries.iter().map(|&id| VarInt(id)).collect(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    for tag in custom {
+        let registry = ident!("{}", tag.registry);
+
+        let group = match groups.iter().position(|g| g.registry == registry) {
+            Some(idx) => &mut groups[idx],
+            None => {
+                groups.push(TagGroup {
+                    registry,
+                    tags: vec![],
+                });
+                groups.last_mut().unwrap()
+            }
+        };
+
+        group.tags.retain(|t| t.name != tag.name);
+        group.tags.push(TagEntries {
+            name: tag.name,
+            entries: tag.entries,
+        });
+    }
+
+    UpdateTags { groups }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_membership_and_packet() {
+        assert!(BlockKind::OakLog.has_tag(BlockTag::Logs));
+        assert!(BlockKind::Ladder.has_tag(BlockTag::Climbable));
+        assert!(!BlockKind::Stone.has_tag(BlockTag::Logs));
+        assert!(EntityKind::Skeleton.has_tag(EntityTag::Skeletons));
+        assert_eq!(
+            BlockTag::from_str("mineable/pickaxe"),
+            Some(BlockTag::MineablePickaxe)
+        );
+
+        let custom = Tag::blocks(ident!("logs"), [BlockKind::Stone]);
+        let pkt = update_tags_packet(vec![custom]);
+
+        let blocks = pkt
+            .groups
+            .iter()
+            .find(|g| g.registry == ident!("block"))
+            .unwrap();
+
+        let logs: Vec<_> = blocks
+            .tags
+            .iter()
+            .filter(|t| t.name == ident!("logs"))
+            .collect();
+
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].entries, [VarInt(BlockKind::Stone as i32)]);
+        assert!(pkt.groups.iter().any(|g| g.registry == ident!("fluid")));
+
+        let custom = Tag::items(ident!("valence:tools"), [ItemKind::DiamondPickaxe]);
+        let pkt = update_tags_packet(vec![custom]);
+
+        let items = pkt
+            .groups
+            .iter()
+            .find(|g| g.registry == ident!("item"))
+            .unwrap();
+
+        assert!(items.tags.iter().any(|t| t.name == ident!("valence:tools")
+            && t.entries == [VarInt(ItemKind::DiamondPickaxe.to_raw() as i32)]));
+    }
+}
@@ -8,6 +8,7 @@ use serde::de::Visitor;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::ident::Ident;
+use crate::item::ItemStack;
 use crate::protocol::{BoundedString, Decode, Encode};
 
 /// Represents formatted text in Minecraft's JSON text format.
@@ -320,6 +321,12 @@ pub trait TextFormat: Into<Text> {
         t
     }
 
+    fn on_hover_show_item(self, item: &ItemStack) -> Text {
+        let mut t = self.into();
+        t.hover_event = Some(HoverEvent::ShowItem(item.clone()));
+        t
+    }
+
     fn clear_hover_event(self) -> Text {
         let mut t = self.into();
         t.hover_event = None;
@@ -377,11 +384,8 @@ enum ClickEvent {
 #[allow(clippy::enum_variant_names)]
 enum HoverEvent {
     ShowText(Box<Text>),
-    ShowItem {
-        id: Ident,
-        count: Option<i32>,
-        // TODO: tag
-    },
+    #[serde(with = "show_item")]
+    ShowItem(ItemStack),
     ShowEntity {
         name: Box<Text>,
         #[serde(rename = "type")]
@@ -390,6 +394,62 @@ enum HoverEvent {
     },
 }
 
+/// (De)serializes the contents of a `show_item` hover event, which has the NBT
+/// of the item as an SNBT string.
+mod show_item {
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+
+    use super::*;
+    use crate::item::ItemKind;
+    use crate::snbt::{from_snbt, to_snbt};
+
+    #[derive(Serialize, Deserialize)]
+    struct Contents {
+        id: Ident,
+        #[serde(default = "one")]
+        count: i8,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tag: Option<String>,
+    }
+
+    fn one() -> i8 {
+        1
+    }
+
+    pub fn serialize<S: Serializer>(item: &ItemStack, serializer: S) -> Result<S::Ok, S::Error> {
+        Contents {
+            id: Ident::new(format!("minecraft:{}", item.item.to_str()))
+                .map_err(S::Error::custom)?,
+            count: item.count,
+            tag: item.nbt.as_ref().map(to_snbt),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ItemStack, D::Error> {
+        let contents = Contents::deserialize(deserializer)?;
+
+        if contents.id.namespace().unwrap_or("minecraft") != "minecraft" {
+            return Err(D::Error::custom(format!("unknown item '{}'", contents.id)));
+        }
+
+        let item = ItemKind::from_str(contents.id.path())
+            .ok_or_else(|| D::Error::custom(format!("unknown item '{}'", contents.id)))?;
+
+        let nbt = contents
+            .tag
+            .map(|tag| from_snbt(&tag).map_err(D::Error::custom))
+            .transpose()?;
+
+        Ok(ItemStack {
+            item,
+            count: contents.count,
+            nbt,
+        })
+    }
+}
+
 impl<T: Into<Text>> TextFormat for T {}
 
 impl<T: Into<Text>> std::ops::Add<T> for Text {
@@ -601,4 +661,34 @@ mod tests {
         assert!(txt.is_empty());
         assert!(txt.to_plain().is_empty());
     }
+
+    #[test]
+    fn hover_show_item() {
+        use crate::item::ItemKind;
+        use crate::nbt::{Compound, Value};
+
+        let mut nbt = Compound::new();
+        nbt.insert("Damage".into(), Value::Int(5));
+
+        let item = ItemStack {
+            item: ItemKind::DiamondSword,
+            count: 1,
+            nbt: Some(nbt),
+        };
+
+        let before = "sword".on_hover_show_item(&item);
+        let json = serde_json::to_value(&before).unwrap();
+
+        assert_eq!(
+            json["hoverEvent"]["contents"],
+            serde_json::json!({
+                "id": "minecraft:diamond_sword",
+                "count": 1,
+                "tag": "{Damage:5}",
+            })
+        );
+
+        let after: Text = serde_json::from_value(json).unwrap();
+        assert_eq!(before, after);
+    }
 }
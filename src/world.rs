@@ -2,14 +2,31 @@
 
 use std::iter::FusedIterator;
 
+pub use border::WorldBorder;
+pub(crate) use border::PORTAL_TELEPORT_BOUNDARY;
+pub(crate) use explosion::EXPLOSION_RANGE;
+pub use explosion::{Explosion, ExplosionHit};
 use rayon::iter::ParallelIterator;
+use vek::{Aabb, Vec3};
 
-use crate::chunk::Chunks;
+use crate::block::{BlockPos, BlockState};
+use crate::chunk::{BlockChangePacket, BlockRaycastHit, Chunks};
 use crate::config::Config;
 use crate::dimension::DimensionId;
+use crate::ident::Ident;
+use crate::particle::{particle_packet, Particle};
+use crate::protocol::packets::s2c::play::{
+    BlockBreakingProgress, BlockEvent, S2cPlayPacket, StopSound,
+};
+use crate::protocol::{BoundedInt, VarInt};
 use crate::server::SharedServer;
 use crate::slab_versioned::{Key, VersionedSlab};
-use crate::spatial_index::SpatialIndex;
+use crate::sound::{hearing_range, sound_packet, SoundCategory};
+use crate::spatial_index::{RaycastHit, SpatialIndex};
+use crate::{Ticks, STANDARD_TPS};
+
+mod border;
+mod explosion;
 
 /// A container for all [`World`]s on a [`Server`](crate::server::Server).
 pub struct Worlds<C: Config> {
@@ -49,7 +66,10 @@ impl<C: Config> Worlds<C> {
             state,
             spatial_index: SpatialIndex::new(),
             chunks: Chunks::new(self.shared.clone(), dim),
-            meta: WorldMeta { dimension: dim },
+            meta: WorldMeta::new(dim),
+            border: WorldBorder::new(),
+            nearby_packets: Vec::new(),
+            explosions: Vec::new(),
         });
 
         (WorldId(id), world)
@@ -135,16 +155,525 @@ pub struct World<C: Config> {
     pub chunks: Chunks<C>,
     /// This world's metadata.
     pub meta: WorldMeta,
+    /// The world border of this world.
+    pub border: WorldBorder,
+    /// Packets sent this tick to the clients within a distance of a position,
+    /// such as particles and sounds.
+    pub(crate) nearby_packets: Vec<(Vec3<f64>, f64, S2cPlayPacket)>,
+    /// Explosions created this tick.
+    pub(crate) explosions: Vec<Explosion>,
+}
+
+/// The distance from which clients see particles.
+const PARTICLE_RANGE: f64 = 32.0;
+
+/// The distance from which clients see long distance particles.
+const LONG_DISTANCE_PARTICLE_RANGE: f64 = 512.0;
+
+impl<C: Config> World<C> {
+    /// Spawns `count` particles at `position` for all clients in this world
+    /// within range.
+    ///
+    /// Particles are visible to clients within 32 blocks, or 512 blocks if
+    /// `long_distance` is `true`. Long distance particles are also rendered
+    /// regardless of the client's particle settings.
+    ///
+    /// Each particle is offset from `position` randomly by up to `offset` in
+    /// each direction, and `max_speed` limits the speed of particles that
+    /// move. If `count` is `0`, a single particle is spawned exactly at
+    /// `position` and `offset` is its velocity instead.
+    pub fn spawn_particle(
+        &mut self,
+        particle: &Particle,
+        long_distance: bool,
+        position: impl Into<Vec3<f64>>,
+        offset: impl Into<Vec3<f32>>,
+        max_speed: f32,
+        count: i32,
+    ) {
+        let position = position.into();
+
+        let range = if long_distance {
+            LONG_DISTANCE_PARTICLE_RANGE
+        } else {
+            PARTICLE_RANGE
+        };
+
+        let pkt = particle_packet(
+            particle,
+            long_distance,
+            position,
+            offset.into(),
+            max_speed,
+            count,
+        );
+
+        self.nearby_packets.push((position, range, pkt.into()));
+    }
+
+    /// Plays a sound at `position` for all clients in this world within
+    /// hearing range.
+    ///
+    /// `sound` is either a [`Sound`](crate::sound::Sound) or the name of a
+    /// custom sound from a resource pack. Clients hear the sound from 16
+    /// blocks away, or further if `volume` is greater than `1.0`.
+    pub fn play_sound(
+        &mut self,
+        sound: impl Into<Ident>,
+        category: SoundCategory,
+        position: impl Into<Vec3<f64>>,
+        volume: f32,
+        pitch: f32,
+    ) {
+        let position = position.into();
+        let pkt = sound_packet(sound.into(), category, position, volume, pitch);
+
+        self.nearby_packets
+            .push((position, hearing_range(volume), pkt.into()));
+    }
+
+    /// Stops sounds playing for all clients in this world.
+    ///
+    /// If `category` or `sound` is given, only the sounds matching it are
+    /// stopped.
+    pub fn stop_sound(&mut self, category: Option<SoundCategory>, sound: Option<Ident>) {
+        let pkt = StopSound { category, sound };
+
+        self.nearby_packets
+            .push((Vec3::zero(), f64::INFINITY, pkt.into()));
+    }
+
+    /// Returns the packets sent this tick to clients at `pos`.
+    pub(crate) fn packets_near(&self, pos: Vec3<f64>) -> impl Iterator<Item = &S2cPlayPacket> + '_ {
+        self.nearby_packets
+            .iter()
+            .filter(move |(p, range, _)| p.distance_squared(pos) <= range * range)
+            .map(|(_, _, pkt)| pkt)
+    }
+
+    /// Shows the crack animation of a block being broken at `pos` to the
+    /// clients that have the block loaded.
+    ///
+    /// `breaker_id` is an arbitrary ID identifying the breaker, such as the
+    /// network ID of an entity. Each breaker shows progress on at most one
+    /// block at a time, so setting the progress for a different position moves
+    /// the animation. `stage` goes from `0` to `9` and is clamped to that
+    /// range.
+    ///
+    /// Returns `false` if the position is not inside of a chunk.
+    pub fn set_block_breaking_progress(
+        &mut self,
+        breaker_id: i32,
+        pos: impl Into<BlockPos>,
+        stage: u8,
+    ) -> bool {
+        self.push_breaking_progress(breaker_id, pos.into(), stage.min(9))
+    }
+
+    /// Removes the crack animation shown by
+    /// [`set_block_breaking_progress`](Self::set_block_breaking_progress) for
+    /// `breaker_id`.
+    ///
+    /// Returns `false` if the position is not inside of a chunk.
+    pub fn clear_block_breaking_progress(
+        &mut self,
+        breaker_id: i32,
+        pos: impl Into<BlockPos>,
+    ) -> bool {
+        // Stages outside of 0..=9 remove the animation.
+        self.push_breaking_progress(breaker_id, pos.into(), 10)
+    }
+
+    fn push_breaking_progress(&mut self, breaker_id: i32, pos: BlockPos, stage: u8) -> bool {
+        self.chunks.push_block_effect(
+            pos,
+            BlockChangePacket::BreakingProgress(BlockBreakingProgress {
+                entity_id: VarInt(breaker_id),
+                location: pos,
+                destroy_stage: BoundedInt(stage),
+            }),
+        )
+    }
+
+    /// Triggers a block action at `pos` for the clients that have the block
+    /// loaded. This is used for animations such as opening chest lids, playing
+    /// note blocks and moving pistons.
+    ///
+    /// The meaning of `action_id` and `param` depends on the kind of the block
+    /// at `pos`. For instance, `action_id` `1` on a chest sets the number of
+    /// players viewing it to `param`, which opens the lid when nonzero. The
+    /// action is ignored by clients if the block does not support it.
+    ///
+    /// Returns `false` if the position is not inside of a chunk.
+    pub fn play_block_action(
+        &mut self,
+        pos: impl Into<BlockPos>,
+        action_id: u8,
+        param: u8,
+    ) -> bool {
+        let pos = pos.into();
+
+        let Some(block) = self.chunks.get_block_state(pos) else {
+            return false;
+        };
+
+        self.chunks.push_block_effect(
+            pos,
+            BlockChangePacket::Event(BlockEvent {
+                location: pos,
+                action_id,
+                action_param: param,
+                // Block kinds are declared in registry order.
+                block_type: VarInt(block.to_kind() as i32),
+            }),
+        )
+    }
+
+    /// Creates an explosion at `position` and returns the blocks and entities
+    /// affected by it.
+    ///
+    /// The destroyed blocks are determined the same way as in vanilla, using
+    /// the [blast resistance] of the blocks around the explosion. If
+    /// `destroy_blocks` is `true`, they are replaced with air. TNT has a
+    /// `power` of `4.0`.
+    ///
+    /// Clients within 64 blocks are shown the explosion and are knocked back
+    /// by it. Entities are not damaged or knocked back automatically, so the
+    /// entities caught in the explosion are returned along with the damage and
+    /// knockback they should receive.
+    ///
+    /// [blast resistance]: crate::block::BlockKind::blast_resistance
+    pub fn explode(
+        &mut self,
+        position: impl Into<Vec3<f64>>,
+        power: f32,
+        destroy_blocks: bool,
+    ) -> Explosion {
+        let position = position.into();
+
+        let blocks = if destroy_blocks {
+            explosion::destroyed_blocks(position, power, &mut rand::thread_rng(), |pos| {
+                self.chunks.get_block_state(pos)
+            })
+        } else {
+            vec![]
+        };
+
+        let range = power as f64 * 2.0 + 1.0;
+        let area = Aabb {
+            min: position - range,
+            max: position + range,
+        };
+
+        let mut entities = vec![];
+
+        self.spatial_index.query::<_, _, ()>(
+            |bb| bb.collides_with_aabb(area),
+            |entity, bb| {
+                // Most entities have their eyes at 85% of their height.
+                let eye_height = bb.size().h * 0.85;
+
+                if let Some((damage, knockback)) =
+                    explosion::impact(position, power, bb, eye_height, &self.chunks)
+                {
+                    entities.push(ExplosionHit {
+                        entity,
+                        damage,
+                        knockback: (knockback * STANDARD_TPS as f64).as_(),
+                    });
+                }
+
+                None
+            },
+        );
+
+        for &pos in &blocks {
+            self.chunks.set_block_state(pos, BlockState::AIR);
+        }
+
+        let explosion = Explosion {
+            position,
+            power,
+            blocks,
+            entities,
+        };
+
+        self.explosions.push(explosion.clone());
+
+        explosion
+    }
+
+    /// Casts a ray through both the blocks and the entity hitboxes of this
+    /// world and returns whichever intersection is closest.
+    ///
+    /// `block_filter` and `entity_filter` are used to filter intersections in
+    /// the same way as [`Chunks::raycast`] and [`SpatialIndex::raycast`].
+    /// Intersections further than `max_distance` from the origin are ignored.
+    /// The given ray direction must be normalized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_distance` is not finite.
+    pub fn raycast<B, E>(
+        &self,
+        origin: Vec3<f64>,
+        direction: Vec3<f64>,
+        max_distance: f64,
+        block_filter: B,
+        entity_filter: E,
+    ) -> Option<WorldRaycastHit>
+    where
+        B: FnMut(BlockPos, BlockState) -> bool,
+        E: FnMut(&RaycastHit) -> bool,
+    {
+        let block = self
+            .chunks
+            .raycast(origin, direction, max_distance, block_filter);
+
+        let entity = self
+            .spatial_index
+            .raycast(origin, direction, entity_filter)
+            .filter(|hit| hit.near <= max_distance);
+
+        match (block, entity) {
+            (Some(b), Some(e)) if e.near < b.near => Some(WorldRaycastHit::Entity(e)),
+            (Some(b), _) => Some(WorldRaycastHit::Block(b)),
+            (None, Some(e)) => Some(WorldRaycastHit::Entity(e)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The result of a successful raycast with [`World::raycast`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum WorldRaycastHit {
+    /// The ray hit a block.
+    Block(BlockRaycastHit),
+    /// The ray hit an entity.
+    Entity(RaycastHit),
+}
+
+impl WorldRaycastHit {
+    /// Returns the distance from the ray origin to the intersection point.
+    pub fn near(&self) -> f64 {
+        match self {
+            Self::Block(hit) => hit.near,
+            Self::Entity(hit) => hit.near,
+        }
+    }
 }
 
 /// Contains miscellaneous data about the world.
 pub struct WorldMeta {
     dimension: DimensionId,
+    world_age: i64,
+    time_of_day: i64,
+    time_rate: i64,
+    /// If the time of day was set this tick.
+    time_modified: bool,
+    weather: Weather,
+    weather_duration: Option<Ticks>,
+    rain_level: f32,
+    thunder_level: f32,
+}
+
+/// The weather in a world. See [`WorldMeta::set_weather`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Thunder,
+}
+
+impl Weather {
+    /// Returns the rain and thunder levels of this weather once fully
+    /// transitioned to.
+    pub fn levels(self) -> (f32, f32) {
+        match self {
+            Weather::Clear => (0.0, 0.0),
+            Weather::Rain => (1.0, 0.0),
+            Weather::Thunder => (1.0, 1.0),
+        }
+    }
 }
 
+/// How much the rain and thunder levels change every tick during a weather
+/// transition.
+const WEATHER_TRANSITION_SPEED: f32 = 0.01;
+
 impl WorldMeta {
+    fn new(dimension: DimensionId) -> Self {
+        Self {
+            dimension,
+            world_age: 0,
+            time_of_day: 0,
+            time_rate: 1,
+            time_modified: false,
+            weather: Weather::Clear,
+            weather_duration: None,
+            rain_level: 0.0,
+            thunder_level: 0.0,
+        }
+    }
+
     /// Gets the dimension the world was created with.
     pub fn dimension(&self) -> DimensionId {
         self.dimension
     }
+
+    /// Gets the number of ticks that have passed in this world since it was
+    /// created.
+    pub fn world_age(&self) -> i64 {
+        self.world_age
+    }
+
+    /// Gets the time of day in ticks.
+    ///
+    /// The time of day is not wrapped around at the end of a day, so it also
+    /// counts the days that have passed. The time within the current day is
+    /// `time_of_day % 24000`, where 6000 is noon, 12000 is sunset and 18000
+    /// is midnight. The number of days determines the phase of the moon.
+    pub fn time_of_day(&self) -> i64 {
+        self.time_of_day
+    }
+
+    /// Sets the time of day in ticks. See [`Self::time_of_day`].
+    ///
+    /// The new time is sent to all clients in the world this tick. Otherwise,
+    /// the time is synchronized with clients once every second.
+    pub fn set_time_of_day(&mut self, time: i64) {
+        self.time_of_day = time;
+        self.time_modified = true;
+    }
+
+    /// Gets the number of ticks the time of day advances by every tick.
+    pub fn time_rate(&self) -> i64 {
+        self.time_rate
+    }
+
+    /// Sets the number of ticks the time of day advances by every tick. Use
+    /// `0` to stop the day/night cycle. The default rate is `1`.
+    ///
+    /// Note that the time of day is fixed in dimensions with a
+    /// [`fixed_time`](crate::dimension::Dimension::fixed_time).
+    pub fn set_time_rate(&mut self, rate: i64) {
+        if self.time_rate != rate {
+            self.time_rate = rate;
+            self.time_modified = true;
+        }
+    }
+
+    /// If the time of day or the rate it advances at was modified this tick.
+    pub(crate) fn time_modified(&self) -> bool {
+        self.time_modified
+    }
+
+    /// Gets the current weather of this world.
+    pub fn weather(&self) -> Weather {
+        self.weather
+    }
+
+    /// Gets the number of ticks until the current weather ends, or `None` if
+    /// it lasts indefinitely.
+    pub fn weather_duration(&self) -> Option<Ticks> {
+        self.weather_duration
+    }
+
+    /// Changes the weather of this world.
+    ///
+    /// The weather changes gradually over the course of five seconds, just as
+    /// in vanilla. If `duration` is given, the weather clears up again after
+    /// that many ticks. All clients in the world are kept up to date with the
+    /// weather, except for those with a
+    /// [weather override](crate::client::Client::set_weather_override).
+    ///
+    /// Note that rain and thunder are only visible in dimensions with
+    /// [overworld effects](crate::dimension::DimensionEffects::Overworld).
+    pub fn set_weather(&mut self, weather: Weather, duration: Option<Ticks>) {
+        self.weather = weather;
+        self.weather_duration = duration;
+    }
+
+    /// Changes the weather of this world immediately, without a transition.
+    pub fn set_weather_instantly(&mut self, weather: Weather, duration: Option<Ticks>) {
+        self.set_weather(weather, duration);
+        (self.rain_level, self.thunder_level) = weather.levels();
+    }
+
+    /// Gets the current intensity of the rain, from `0.0` to `1.0`.
+    pub fn rain_level(&self) -> f32 {
+        self.rain_level
+    }
+
+    /// Gets the current intensity of the thunder, from `0.0` to `1.0`.
+    pub fn thunder_level(&self) -> f32 {
+        self.thunder_level
+    }
+
+    /// Advances the time and weather of the world by one tick.
+    pub(crate) fn update(&mut self) {
+        self.world_age += 1;
+        self.time_of_day += self.time_rate;
+        self.time_modified = false;
+
+        if let Some(duration) = &mut self.weather_duration {
+            *duration -= 1;
+
+            if *duration <= 0 {
+                self.weather = Weather::Clear;
+                self.weather_duration = None;
+            }
+        }
+
+        let (rain, thunder) = self.weather.levels();
+
+        self.rain_level = approach(self.rain_level, rain, WEATHER_TRANSITION_SPEED);
+        self.thunder_level = approach(self.thunder_level, thunder, WEATHER_TRANSITION_SPEED);
+    }
+}
+
+/// Moves `current` towards `target` by at most `step`.
+fn approach(current: f32, target: f32, step: f32) -> f32 {
+    if current < target {
+        (current + step).min(target)
+    } else {
+        (current - step).max(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weather_transitions() {
+        let mut meta = WorldMeta::new(DimensionId::default());
+
+        meta.set_weather(Weather::Thunder, Some(150));
+
+        for _ in 0..50 {
+            meta.update();
+        }
+
+        assert_eq!(meta.weather(), Weather::Thunder);
+        assert_eq!(meta.weather_duration(), Some(100));
+        assert!((meta.rain_level() - 0.5).abs() < 1e-4);
+        assert!((meta.thunder_level() - 0.5).abs() < 1e-4);
+
+        for _ in 0..100 {
+            meta.update();
+        }
+
+        assert_eq!(meta.weather(), Weather::Clear);
+        assert_eq!(meta.weather_duration(), None);
+        assert!((meta.rain_level() - 0.99).abs() < 1e-4);
+
+        for _ in 0..100 {
+            meta.update();
+        }
+
+        assert_eq!(meta.rain_level(), 0.0);
+        assert_eq!(meta.thunder_level(), 0.0);
+    }
 }
@@ -0,0 +1,259 @@
+use vek::{Vec2, Vec3};
+
+use crate::Ticks;
+
+/// The world border of a [`World`](crate::world::World).
+///
+/// The border is a square centered on [`center`](Self::center) which clients
+/// render as a wall and are unable to move through. Clients are kept up to
+/// date with changes to the border automatically.
+///
+/// The border does not damage or push back anything on its own. Use
+/// [`is_outside`](Self::is_outside) and [`damage`](Self::damage) to implement
+/// those behaviors.
+#[derive(Clone, Debug)]
+pub struct WorldBorder {
+    center: Vec2<f64>,
+    old_diameter: f64,
+    new_diameter: f64,
+    lerp_duration: Ticks,
+    lerp_elapsed: Ticks,
+    warning_time: i32,
+    warning_blocks: i32,
+    damage_per_block: f64,
+    damage_safe_zone: f64,
+    modified: WorldBorderModified,
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub(crate) struct WorldBorderModified {
+    pub center: bool,
+    pub diameter: bool,
+    pub warning_time: bool,
+    pub warning_blocks: bool,
+}
+
+/// The maximum distance from the origin that clients can be teleported to by
+/// portals.
+pub(crate) const PORTAL_TELEPORT_BOUNDARY: i32 = 29_999_984;
+
+impl WorldBorder {
+    /// The default and maximum diameter of the world border.
+    pub const MAX_DIAMETER: f64 = 59_999_968.0;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            center: Vec2::zero(),
+            old_diameter: Self::MAX_DIAMETER,
+            new_diameter: Self::MAX_DIAMETER,
+            lerp_duration: 0,
+            lerp_elapsed: 0,
+            warning_time: 15,
+            warning_blocks: 5,
+            damage_per_block: 0.2,
+            damage_safe_zone: 5.0,
+            modified: WorldBorderModified::default(),
+        }
+    }
+
+    /// Gets the X and Z coordinates of the center of the border.
+    pub fn center(&self) -> Vec2<f64> {
+        self.center
+    }
+
+    /// Sets the X and Z coordinates of the center of the border.
+    pub fn set_center(&mut self, center: impl Into<Vec2<f64>>) {
+        let center = center.into();
+
+        if self.center != center {
+            self.center = center;
+            self.modified.center = true;
+        }
+    }
+
+    /// Gets the current side length of the border, in blocks.
+    ///
+    /// While the border is [lerping](Self::lerp_diameter), this is somewhere
+    /// between the old and the target diameter.
+    pub fn diameter(&self) -> f64 {
+        if self.lerp_elapsed >= self.lerp_duration {
+            self.new_diameter
+        } else {
+            let t = self.lerp_elapsed as f64 / self.lerp_duration as f64;
+            self.old_diameter + (self.new_diameter - self.old_diameter) * t
+        }
+    }
+
+    /// Gets the diameter the border is lerping to. This is the same as
+    /// [`diameter`](Self::diameter) if the border is not lerping.
+    pub fn target_diameter(&self) -> f64 {
+        self.new_diameter
+    }
+
+    /// Gets the number of ticks until the border reaches its
+    /// [target diameter](Self::target_diameter).
+    pub fn remaining_lerp_ticks(&self) -> Ticks {
+        self.lerp_duration - self.lerp_elapsed
+    }
+
+    /// Sets the diameter of the border immediately, stopping any lerp in
+    /// progress.
+    ///
+    /// The diameter is clamped to [`MAX_DIAMETER`](Self::MAX_DIAMETER).
+    pub fn set_diameter(&mut self, diameter: f64) {
+        self.lerp_diameter(diameter, 0);
+    }
+
+    /// Smoothly changes the diameter of the border from its current diameter
+    /// to `diameter` over `ticks` ticks. If `ticks` is not positive, the
+    /// diameter is changed immediately.
+    ///
+    /// The diameter is clamped to [`MAX_DIAMETER`](Self::MAX_DIAMETER).
+    pub fn lerp_diameter(&mut self, diameter: f64, ticks: Ticks) {
+        let diameter = diameter.clamp(0.0, Self::MAX_DIAMETER);
+
+        self.old_diameter = if ticks > 0 { self.diameter() } else { diameter };
+        self.new_diameter = diameter;
+        self.lerp_duration = ticks.max(0);
+        self.lerp_elapsed = 0;
+        self.modified.diameter = true;
+    }
+
+    /// Gets the number of seconds before a shrinking border reaches clients at
+    /// which their screen starts to turn red.
+    pub fn warning_time(&self) -> i32 {
+        self.warning_time
+    }
+
+    /// Sets the number of seconds before a shrinking border reaches clients at
+    /// which their screen starts to turn red.
+    pub fn set_warning_time(&mut self, seconds: i32) {
+        if self.warning_time != seconds {
+            self.warning_time = seconds;
+            self.modified.warning_time = true;
+        }
+    }
+
+    /// Gets the distance from the border in blocks at which the screen of
+    /// clients starts to turn red.
+    pub fn warning_blocks(&self) -> i32 {
+        self.warning_blocks
+    }
+
+    /// Sets the distance from the border in blocks at which the screen of
+    /// clients starts to turn red.
+    pub fn set_warning_blocks(&mut self, blocks: i32) {
+        if self.warning_blocks != blocks {
+            self.warning_blocks = blocks;
+            self.modified.warning_blocks = true;
+        }
+    }
+
+    /// Gets the damage dealt for every block beyond the safe zone. See
+    /// [`damage`](Self::damage).
+    pub fn damage_per_block(&self) -> f64 {
+        self.damage_per_block
+    }
+
+    /// Sets the damage dealt for every block beyond the safe zone. See
+    /// [`damage`](Self::damage).
+    pub fn set_damage_per_block(&mut self, damage: f64) {
+        self.damage_per_block = damage;
+    }
+
+    /// Gets the distance outside the border in blocks where no damage is
+    /// dealt. See [`damage`](Self::damage).
+    pub fn damage_safe_zone(&self) -> f64 {
+        self.damage_safe_zone
+    }
+
+    /// Sets the distance outside the border in blocks where no damage is
+    /// dealt. See [`damage`](Self::damage).
+    pub fn set_damage_safe_zone(&mut self, distance: f64) {
+        self.damage_safe_zone = distance;
+    }
+
+    /// Returns the distance from `pos` to the nearest edge of the border. The
+    /// distance is negative if `pos` is outside the border.
+    pub fn distance_inside(&self, pos: impl Into<Vec3<f64>>) -> f64 {
+        let pos = pos.into();
+        let radius = self.diameter() / 2.0;
+
+        let min_x = pos.x - (self.center.x - radius);
+        let max_x = (self.center.x + radius) - pos.x;
+        let min_z = pos.z - (self.center.y - radius);
+        let max_z = (self.center.y + radius) - pos.z;
+
+        min_x.min(max_x).min(min_z).min(max_z)
+    }
+
+    /// Returns `true` if `pos` is outside the border.
+    pub fn is_outside(&self, pos: impl Into<Vec3<f64>>) -> bool {
+        self.distance_inside(pos) < 0.0
+    }
+
+    /// Returns the damage that vanilla deals every tick to an entity at `pos`,
+    /// or `0.0` if the position is not beyond the safe zone.
+    ///
+    /// The damage is the distance beyond the safe zone multiplied by the
+    /// [damage per block](Self::damage_per_block), rounded down to at least
+    /// one.
+    pub fn damage(&self, pos: impl Into<Vec3<f64>>) -> f32 {
+        let beyond = -(self.distance_inside(pos) + self.damage_safe_zone);
+
+        if beyond > 0.0 && self.damage_per_block > 0.0 {
+            (beyond * self.damage_per_block).floor().max(1.0) as f32
+        } else {
+            0.0
+        }
+    }
+
+    pub(crate) fn modified(&self) -> WorldBorderModified {
+        self.modified
+    }
+
+    /// Advances the lerp of the border by one tick.
+    pub(crate) fn update(&mut self) {
+        if self.lerp_elapsed < self.lerp_duration {
+            self.lerp_elapsed += 1;
+        }
+
+        self.modified = WorldBorderModified::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn border_lerp_and_bounds() {
+        let mut border = WorldBorder::new();
+
+        border.set_center([10.0, -10.0]);
+        border.set_diameter(100.0);
+        border.lerp_diameter(20.0, 10);
+
+        for _ in 0..5 {
+            border.update();
+        }
+
+        assert_eq!(border.diameter(), 60.0);
+        assert_eq!(border.remaining_lerp_ticks(), 5);
+
+        for _ in 0..10 {
+            border.update();
+        }
+
+        assert_eq!(border.diameter(), 20.0);
+
+        assert!(!border.is_outside([10.0, 0.0, -10.0]));
+        assert!(!border.is_outside([19.0, 64.0, -1.0]));
+        assert!(border.is_outside([21.0, 64.0, -10.0]));
+        assert_eq!(border.distance_inside([10.0, 0.0, -15.0]), 5.0);
+
+        assert_eq!(border.damage([25.0, 0.0, -10.0]), 0.0);
+        assert_eq!(border.damage([26.0, 0.0, -10.0]), 1.0);
+        assert_eq!(border.damage([40.0, 0.0, -10.0]), 3.0);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashSet;
+
+use rand::Rng;
+use vek::{Aabb, Vec3};
+
+use crate::block::{BlockKind, BlockPos, BlockState, PropName, PropValue};
+use crate::chunk::Chunks;
+use crate::config::Config;
+use crate::entity::EntityId;
+
+/// The outcome of an explosion created with
+/// [`World::explode`](crate::world::World::explode).
+#[derive(Clone, Debug)]
+pub struct Explosion {
+    /// The center of the explosion.
+    pub position: Vec3<f64>,
+    /// The power of the explosion. TNT has a power of `4.0`.
+    pub power: f32,
+    /// The blocks destroyed by the explosion.
+    pub blocks: Vec<BlockPos>,
+    /// The entities caught in the explosion.
+    pub entities: Vec<ExplosionHit>,
+}
+
+/// An entity caught in an [`Explosion`].
+///
+/// The explosion does not affect entities on its own. It is up to game code
+/// to apply the damage and knockback.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ExplosionHit {
+    /// The entity that was hit.
+    pub entity: EntityId,
+    /// The amount of damage vanilla deals to the entity, before armor and
+    /// enchantments are taken into account.
+    pub damage: f32,
+    /// The knockback vanilla adds to the entity's velocity, in meters per
+    /// second.
+    pub knockback: Vec3<f32>,
+}
+
+/// Clients within this distance of an explosion are sent the explosion.
+pub(crate) const EXPLOSION_RANGE: f64 = 64.0;
+
+/// The dimensions of the hitbox of a standing player.
+const PLAYER_WIDTH: f64 = 0.6;
+const PLAYER_HEIGHT: f64 = 1.8;
+const PLAYER_EYE_HEIGHT: f64 = 1.62;
+
+impl Explosion {
+    /// Returns the knockback in blocks per tick of this explosion on a player
+    /// standing at `position`.
+    pub(crate) fn player_knockback<C: Config>(
+        &self,
+        position: Vec3<f64>,
+        chunks: &Chunks<C>,
+    ) -> Vec3<f64> {
+        let half_width = PLAYER_WIDTH / 2.0;
+
+        let bb = Aabb {
+            min: position - Vec3::new(half_width, 0.0, half_width),
+            max: position + Vec3::new(half_width, PLAYER_HEIGHT, half_width),
+        };
+
+        impact(self.position, self.power, bb, PLAYER_EYE_HEIGHT, chunks)
+            .map_or(Vec3::zero(), |(_, knockback)| knockback)
+    }
+}
+
+/// Returns the blocks destroyed by an explosion at `position`.
+///
+/// Rays are cast from the center of the explosion towards the surface of a
+/// 16×16×16 cube. Each ray starts with an intensity based on `power` and
+/// loses intensity over distance and to the blast resistance of the blocks it
+/// passes through. Every non-air block reached by a ray with intensity left is
+/// destroyed. Rays stop at unloaded chunks and the bottom and top of the world,
+/// which are given by `get_block`.
+pub(crate) fn destroyed_blocks(
+    position: Vec3<f64>,
+    power: f32,
+    rng: &mut impl Rng,
+    mut get_block: impl FnMut(BlockPos) -> Option<BlockState>,
+) -> Vec<BlockPos> {
+    const STEP: f64 = 0.3;
+
+    let mut blocks = HashSet::new();
+
+    for x in 0..16 {
+        for y in 0..16 {
+            for z in 0..16 {
+                let on_surface = x == 0 || x == 15 || y == 0 || y == 15 || z == 0 || z == 15;
+
+                if !on_surface {
+                    continue;
+                }
+
+                let dir = (Vec3::new(x, y, z).as_::<f64>() / 15.0 * 2.0 - 1.0).normalized();
+
+                let mut intensity = power * (0.7 + rng.gen::<f32>() * 0.6);
+                let mut pos = position;
+
+                while intensity > 0.0 {
+                    let block_pos = BlockPos::at(pos);
+
+                    let Some(block) = get_block(block_pos) else {
+                        break;
+                    };
+
+                    if !block.is_air() {
+                        intensity -= (blast_resistance(block) + STEP as f32) * STEP as f32;
+
+                        if intensity > 0.0 {
+                            blocks.insert(block_pos);
+                        }
+                    }
+
+                    pos += dir * STEP;
+                    intensity -= STEP as f32 * 0.75;
+                }
+            }
+        }
+    }
+
+    blocks.into_iter().collect()
+}
+
+/// Returns the blast resistance of a block state, including the fluid inside
+/// of it.
+fn blast_resistance(block: BlockState) -> f32 {
+    let resistance = block.to_kind().blast_resistance();
+
+    if block.get(PropName::Waterlogged) == Some(PropValue::True) {
+        resistance.max(BlockKind::Water.blast_resistance())
+    } else {
+        resistance
+    }
+}
+
+/// Computes the damage and knockback, in blocks per tick, of an explosion at
+/// `position` on something with the hitbox `bb`. Returns `None` if the hitbox
+/// is out of range.
+///
+/// The knockback pushes away from the explosion towards `eye_height` above the
+/// bottom of the hitbox and is weakened by blocks between the explosion and the
+/// hitbox.
+pub(crate) fn impact<C: Config>(
+    position: Vec3<f64>,
+    power: f32,
+    bb: Aabb<f64>,
+    eye_height: f64,
+    chunks: &Chunks<C>,
+) -> Option<(f32, Vec3<f64>)> {
+    let range = power as f64 * 2.0;
+    let feet = Vec3::new(bb.center().x, bb.min.y, bb.center().z);
+
+    let dist = feet.distance(position) / range;
+
+    if dist > 1.0 {
+        return None;
+    }
+
+    let eye = Vec3::new(feet.x, feet.y + eye_height, feet.z);
+
+    if eye == position {
+        return None;
+    }
+
+    let dir = (eye - position).normalized();
+
+    let exposure = exposure(position, bb, |from, to| {
+        let diff = to - from;
+        let len = diff.magnitude();
+
+        len > 0.0 && chunks.raycast(from, diff / len, len, |_, _| true).is_some()
+    });
+
+    let strength = (1.0 - dist) * exposure;
+    let damage = ((strength * strength + strength) / 2.0 * 7.0 * range + 1.0).floor();
+
+    Some((damage as f32, dir * strength))
+}
+
+/// Returns the fraction of points on the hitbox `bb` that can see `source`.
+/// `blocked` is given a point on the hitbox and the source, and returns
+/// whether the line between them is obstructed.
+fn exposure(
+    source: Vec3<f64>,
+    bb: Aabb<f64>,
+    mut blocked: impl FnMut(Vec3<f64>, Vec3<f64>) -> bool,
+) -> f64 {
+    let size = bb.size();
+
+    let step_x = 1.0 / (size.w * 2.0 + 1.0);
+    let step_y = 1.0 / (size.h * 2.0 + 1.0);
+    let step_z = 1.0 / (size.d * 2.0 + 1.0);
+
+    let offset_x = (1.0 - (1.0 / step_x).floor() * step_x) / 2.0;
+    let offset_z = (1.0 - (1.0 / step_z).floor() * step_z) / 2.0;
+
+    let mut visible = 0;
+    let mut total = 0;
+
+    let mut tx = 0.0;
+    while tx <= 1.0 {
+        let mut ty = 0.0;
+        while ty <= 1.0 {
+            let mut tz = 0.0;
+            while tz <= 1.0 {
+                let point = Vec3::new(
+                    bb.min.x + (bb.max.x - bb.min.x) * tx + offset_x,
+                    bb.min.y + (bb.max.y - bb.min.y) * ty,
+                    bb.min.z + (bb.max.z - bb.min.z) * tz + offset_z,
+                );
+
+                if !blocked(point, source) {
+                    visible += 1;
+                }
+                total += 1;
+
+                tz += step_z;
+            }
+            ty += step_y;
+        }
+        tx += step_x;
+    }
+
+    visible as f64 / total as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explosion_blocks_and_exposure() {
+        let mut rng = rand::thread_rng();
+        let center = Vec3::new(0.5, 64.5, 0.5);
+
+        let stone = |_| Some(BlockState::STONE);
+        let blocks = destroyed_blocks(center, 4.0, &mut rng, stone);
+
+        assert!(blocks.contains(&BlockPos::new(0, 64, 0)));
+        assert!(blocks
+            .iter()
+            .all(|b| { Vec3::new(b.x, b.y, b.z).as_::<f64>().distance(center) < 4.0 }));
+
+        let bedrock = |_| Some(BlockState::BEDROCK);
+        assert!(destroyed_blocks(center, 4.0, &mut rng, bedrock).is_empty());
+
+        let outside = |_| None;
+        assert!(destroyed_blocks(center, 4.0, &mut rng, outside).is_empty());
+
+        let bb = Aabb {
+            min: Vec3::new(2.0, 64.0, 0.0),
+            max: Vec3::new(2.6, 65.8, 0.6),
+        };
+
+        assert_eq!(exposure(center, bb, |_, _| false), 1.0);
+        assert_eq!(exposure(center, bb, |_, _| true), 0.0);
+        assert_eq!(
+            exposure(center, bb, |from, _| from.y < 65.0),
+            // Of the 5 rows of points, the bottom 3 are blocked.
+            0.4
+        );
+    }
+}